@@ -1,18 +1,27 @@
 use crate::{
-    commands,
+    commands::{
+        self,
+        allowances::AllowanceParty,
+        completions::{key_ref_candidates, vesting_type_candidates},
+    },
     utils::{
-        config::get_key_pair_from_vesting,
-        constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL},
-        format_with_thousands_separator,
+        constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL, WASM_NAMES},
+        exit, format_with_thousands_separator, get_state_root_hash, journal,
+        key_ref::{parse_key_ref, parse_vesting_type, KeyRef},
+        wasm,
     },
 };
 use casper_rust_wasm_sdk::{
     helpers::motes_to_cspr,
     types::{key::Key, public_key::PublicKey},
 };
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use clap_complete::{engine::ArgValueCandidates, Shell};
 use cowl_vesting::enums::VestingType;
-use std::fmt::{self, Display};
+use std::{
+    fmt::{self, Display},
+    io::ErrorKind,
+};
 use strum::IntoEnumIterator;
 
 /// CLI Tool for managing contracts and token distributions
@@ -23,6 +32,14 @@ use strum::IntoEnumIterator;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Deploy wasm files that differ from the lockfile.
+    #[arg(
+        long,
+        global = true,
+        help = "Deploy wasm files missing from or differing from the wasm lockfile"
+    )]
+    pub allow_unlocked_wasm: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -39,7 +56,10 @@ pub enum Commands {
         name = "list-addr",
         about = "List all funded addresses from current config"
     )]
-    ListFundedAdresses,
+    ListFundedAdresses {
+        #[command(flatten)]
+        state: StateIdentifierArgs,
+    },
 
     /// Deploy smart contracts.
     #[command(
@@ -67,12 +87,20 @@ pub enum Commands {
     )]
     VestingInfo {
         /// Specify the vesting type (e.g., linear, cliff).
-        #[arg(long, help = "The vesting type to retrieve information for")]
+        #[arg(
+            long,
+            value_parser = parse_vesting_type,
+            add = ArgValueCandidates::new(vesting_type_candidates),
+            help = "The vesting type to retrieve information for"
+        )]
         vesting_type: String,
 
         /// Call the entry point in the contract for more detailed information.
         #[arg(long, help = "Call the contract's entry point before")]
         call_entry_point: bool,
+
+        #[command(flatten)]
+        state: StateIdentifierArgs,
     },
 
     /// Check the status of a vesting type.
@@ -81,9 +109,14 @@ pub enum Commands {
         /// Specify the vesting type (e.g., linear, cliff).
         #[arg(
             long,
+            value_parser = parse_vesting_type,
+            add = ArgValueCandidates::new(vesting_type_candidates),
             help = "The vesting type to check the status for. Contract's entrypoint will be called before to update the value to retrieve."
         )]
         vesting_type: String,
+
+        #[command(flatten)]
+        state: StateIdentifierArgs,
     },
 
     /// Retrieve the balance of a vesting or public key.
@@ -93,15 +126,25 @@ pub enum Commands {
     )]
     Balance {
         /// Specify the vesting type (optional).
-        #[arg(long, help = "The vesting type to retrieve the balance for")]
+        #[arg(
+            long,
+            value_parser = parse_vesting_type,
+            add = ArgValueCandidates::new(vesting_type_candidates),
+            help = "The vesting type to retrieve the balance for"
+        )]
         vesting_type: Option<String>,
 
         /// Specify the public or account key (optional).
         #[arg(
             long,
+            value_parser = parse_key_ref,
+            add = ArgValueCandidates::new(key_ref_candidates),
             help = "The public key or account hash to retrieve the balance for"
         )]
-        key: Option<String>,
+        key: Option<KeyRef>,
+
+        #[command(flatten)]
+        state: StateIdentifierArgs,
     },
 
     /// Transfer tokens between accounts or vesting types.
@@ -113,20 +156,23 @@ pub enum Commands {
         /// Specify the source (public key signing).
         #[arg(
             long,
+            value_parser = parse_key_ref,
+            add = ArgValueCandidates::new(key_ref_candidates),
             help = "The source (public key signing) to transfer from.
             Example: 016fd7fb5f002d82f3813c76ac83940d4d886035395ddd9be66c9a4a2993b63aaf"
         )]
-        from: String,
+        from: KeyRef,
 
         /// Specify the destination (public key, account hash, or vesting type).
         #[arg(
             long,
+            value_parser = parse_key_ref,
             help = "The destination (public key, account hash, or vesting type) to transfer to.
             Example: 01868e06026ba9c8695f6f3bb10d44782004dbc144ff65017cf484436f9cf7b0f6
             Example: Treasury
             Example: account-hash-31dfd6356d4be001607bd2d6b163c9b23967873a849a96813781674cf5e4d96b"
         )]
-        to: String,
+        to: KeyRef,
 
         /// The amount to transfer.
         #[arg(
@@ -134,6 +180,13 @@ pub enum Commands {
             help = "The amount to transfer in the smallest unit (e.g., '100000000000' represents 100 COWL). Example: '100000000000'"
         )]
         amount: String,
+
+        /// Off-chain memo recorded in the deploy history.
+        #[arg(
+            long,
+            help = "Off-chain memo recorded with the deploy in the local history, like an invoice reference"
+        )]
+        memo: Option<String>,
     },
 
     /// Allowance command that checks or manages allowances between accounts.
@@ -144,16 +197,57 @@ pub enum Commands {
     Allowance {
         #[arg(
             long,
+            value_parser = parse_key_ref,
             help = "The owner (vesting type or public key/account hash) of the allowance"
         )]
-        owner: String,
+        owner: KeyRef,
 
         /// Specify the spender (vesting type or public key).
         #[arg(
             long,
+            value_parser = parse_key_ref,
             help = "The spender (vesting type or public key/account hash) of the allowance"
         )]
-        spender: String,
+        spender: KeyRef,
+
+        #[command(flatten)]
+        state: StateIdentifierArgs,
+    },
+
+    /// List the allowances of an owner or of a spender.
+    #[command(
+        name = "allowances",
+        about = "List the non-zero allowances between an owner or spender and every known account",
+        group(
+            ArgGroup::new("allowance_party")
+                .required(true)
+                .args(["owner", "spender"])
+        )
+    )]
+    Allowances {
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            help = "The owner (vesting type, alias or public key/account hash) to list the spenders of"
+        )]
+        owner: Option<KeyRef>,
+
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            help = "The spender (vesting type, alias or public key/account hash) to list the owners of"
+        )]
+        spender: Option<KeyRef>,
+
+        /// Discover counterparts from the allowance events.
+        #[arg(
+            long,
+            help = "Also check the accounts found in the allowance events of the token"
+        )]
+        scan_events: bool,
+
+        #[command(flatten)]
+        state: StateIdentifierArgs,
     },
 
     /// Transfer tokens from an account using a previously granted allowance.
@@ -165,23 +259,27 @@ pub enum Commands {
         /// Specify the allowed operator (public key signing).
         #[arg(
             long,
+            value_parser = parse_key_ref,
             help = "The allowed operator (public key signing) to transfer from"
         )]
-        operator: String,
+        operator: KeyRef,
 
         /// Specify the source (vesting type or public key/account hash) to transfer from.
         #[arg(
             long,
+            value_parser = parse_key_ref,
+            add = ArgValueCandidates::new(key_ref_candidates),
             help = "The source (vesting type or public key/account hash) to transfer from"
         )]
-        from: String,
+        from: KeyRef,
 
         /// Specify the destination (vesting type or public key/account hash).
         #[arg(
             long,
+            value_parser = parse_key_ref,
             help = "The destination (vesting type or public key/account hash) to transfer to"
         )]
-        to: String,
+        to: KeyRef,
 
         /// The amount to transfer.
         #[arg(
@@ -189,6 +287,13 @@ pub enum Commands {
             help = "The amount to transfer in the smallest unit (e.g., '100000000000' represents 100 COWL). Example: '100000000000'"
         )]
         amount: String,
+
+        /// Off-chain memo recorded in the deploy history.
+        #[arg(
+            long,
+            help = "Off-chain memo recorded with the deploy in the local history, like an invoice reference"
+        )]
+        memo: Option<String>,
     },
 
     /// Increase the allowance of a spender for an owner.
@@ -197,14 +302,19 @@ pub enum Commands {
         about = "Increase the allowance of a spender for a given owner"
     )]
     IncreaseAllowance {
-        #[arg(long, help = "The owner (public key signing) of the tokens")]
-        owner: String,
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            help = "The owner (public key signing) of the tokens"
+        )]
+        owner: KeyRef,
 
         #[arg(
             long,
+            value_parser = parse_key_ref,
             help = "The spender (vesting type or public key/account hash) to increase allowance of"
         )]
-        spender: String,
+        spender: KeyRef,
 
         /// The amount to increase.
         #[arg(
@@ -220,14 +330,19 @@ pub enum Commands {
         about = "Decrease the allowance of a spender for a given owner"
     )]
     DecreaseAllowance {
-        #[arg(long, help = "The owner (public key signing) of the tokens")]
-        owner: String,
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            help = "The owner (public key signing) of the tokens"
+        )]
+        owner: KeyRef,
 
         #[arg(
             long,
+            value_parser = parse_key_ref,
             help = "The spender (vesting type or public key/account hash) to decrease allowance of"
         )]
-        spender: String,
+        spender: KeyRef,
 
         /// The amount to decrease.
         #[arg(
@@ -237,28 +352,125 @@ pub enum Commands {
         amount: String,
     },
 
+    /// Set the allowance of a spender for an owner to an exact amount.
+    #[command(
+        name = "approve",
+        about = "Set the allowance of a spender for a given owner to an exact amount"
+    )]
+    Approve {
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            help = "The owner (public key signing) of the tokens"
+        )]
+        owner: KeyRef,
+
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            help = "The spender (vesting type or public key/account hash) to set the allowance of"
+        )]
+        spender: KeyRef,
+
+        /// The exact allowance.
+        #[arg(
+            long,
+            help = "The allowance in the smallest unit (e.g., '100000000000' represents 100 COWL). Example: '100000000000'"
+        )]
+        amount: String,
+    },
+
+    /// Set the allowance of a spender for an owner to zero.
+    #[command(
+        name = "revoke",
+        about = "Revoke the allowance of a spender for a given owner"
+    )]
+    Revoke {
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            help = "The owner (public key signing) of the tokens"
+        )]
+        owner: KeyRef,
+
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            help = "The spender (vesting type or public key/account hash) to revoke the allowance of"
+        )]
+        spender: KeyRef,
+    },
+
     /// Fund and retrieve the balance of a vesting or public key.
     #[command(
         name = "fund-cspr",
-        about = "Fund and retrieve the balance for a specific vesting type or key (Public key or Account hash)"
+        about = "Fund and retrieve the balance for a specific vesting type or key (Public key or Account hash)",
+        group(
+            ArgGroup::new("fund_targets")
+                .required(true)
+                .args(["vesting_type", "key", "all_vesting", "targets"])
+        )
     )]
     Fund {
         /// Specify the vesting type (optional).
-        #[arg(long, help = "The vesting type to retrieve the balance for")]
+        #[arg(
+            long,
+            value_parser = parse_vesting_type,
+            add = ArgValueCandidates::new(vesting_type_candidates),
+            help = "The vesting type to retrieve the balance for"
+        )]
         vesting_type: Option<String>,
 
         /// Specify the public or account key (optional).
         #[arg(
             long,
+            value_parser = parse_key_ref,
+            add = ArgValueCandidates::new(key_ref_candidates),
             help = "The public key or account hash to retrieve the balance for"
         )]
-        key: Option<String>,
+        key: Option<KeyRef>,
+
+        /// Fund every vesting account.
+        #[arg(long, help = "Fund every configured vesting account")]
+        all_vesting: bool,
+
+        /// File listing the targets to fund.
+        #[arg(
+            long,
+            help = "File listing one target per line (vesting type, account name, alias, public key or hash)"
+        )]
+        targets: Option<String>,
+
         /// The amount to fund.
         #[arg(
             long,
+            required_unless_present = "top_up_to",
+            conflicts_with = "top_up_to",
             help = "The amount to fund in the smallest unit (e.g., '2500000000' represents 2.5 CSPR). Example: '2500000000' (minimum)"
         )]
-        amount: String,
+        amount: Option<String>,
+
+        /// Balance to top up each target to.
+        #[arg(
+            long,
+            help = "Fund each target with its deficit up to this balance in the smallest unit. Example: '100000000000'"
+        )]
+        top_up_to: Option<String>,
+
+        /// Transfer id of the native transfer.
+        #[arg(
+            long,
+            help = "Transfer id required by some recipients like exchanges, the first of consecutive ids when funding several targets"
+        )]
+        transfer_id: Option<u64>,
+
+        /// Generate the transfer id.
+        #[arg(
+            long,
+            conflicts_with = "transfer_id",
+            help = "Generate a unique transfer id, printed and recorded in the deploy history"
+        )]
+        generate_transfer_id: bool,
     },
 
     /// Upgrade contratc and enable events
@@ -274,10 +486,12 @@ pub enum Commands {
         /// Specify the source (public key signing).
         #[arg(
             long,
+            value_parser = parse_key_ref,
+            add = ArgValueCandidates::new(key_ref_candidates),
             help = "The source (public key signing) to deposit from.
                     Example: 016fd7fb5f002d82f3813c76ac83940d4d886035395ddd9be66c9a4a2993b63aaf"
         )]
-        from: String,
+        from: KeyRef,
 
         /// The amount to deposit.
         #[arg(
@@ -317,16 +531,21 @@ pub enum Commands {
         name = "swap-balance",
         about = "Retrieve the balance of the swap contract"
     )]
-    SwapBalance,
+    SwapBalance {
+        #[command(flatten)]
+        state: StateIdentifierArgs,
+    },
     #[command(name = "cspr-to-cowl", about = "Swap CSPR to COWL")]
     CsprToCowl {
         /// Specify the source (public key signing).
         #[arg(
             long,
+            value_parser = parse_key_ref,
+            add = ArgValueCandidates::new(key_ref_candidates),
             help = "The source (public key signing) to deposit from.
                             Example: 016fd7fb5f002d82f3813c76ac83940d4d886035395ddd9be66c9a4a2993b63aaf"
         )]
-        from: String,
+        from: KeyRef,
         /// The amount to swap.
         #[arg(
             long,
@@ -339,10 +558,12 @@ pub enum Commands {
         /// Specify the source (public key signing).
         #[arg(
             long,
+            value_parser = parse_key_ref,
+            add = ArgValueCandidates::new(key_ref_candidates),
             help = "The source (public key signing) to deposit from.
                             Example: 016fd7fb5f002d82f3813c76ac83940d4d886035395ddd9be66c9a4a2993b63aaf"
         )]
-        from: String,
+        from: KeyRef,
         /// The amount to swap.
         #[arg(
             long,
@@ -360,12 +581,570 @@ pub enum Commands {
         #[arg(long, help = "The duration of the Swap (duration in seconds)")]
         duration: String,
     },
+
+    /// Retrieve the status and receipt of a deploy.
+    #[command(
+        name = "deploy-status",
+        about = "Retrieve the execution result, cost, block and events of a deploy"
+    )]
+    DeployStatus {
+        /// The deploy hash to look up.
+        #[arg(help = "The deploy hash to look up")]
+        deploy_hash: String,
+
+        /// Poll the node until the deploy is processed.
+        #[arg(long, help = "Poll the node until the deploy is processed")]
+        wait: bool,
+
+        /// Interval between polls.
+        #[arg(long, default_value_t = 5, help = "Interval between polls in seconds")]
+        interval: u64,
+
+        /// Maximum time to wait for the deploy.
+        #[arg(
+            long,
+            default_value_t = 300,
+            help = "Maximum time to wait for the deploy in seconds"
+        )]
+        timeout: u64,
+    },
+
+    /// Browse the local journal of submitted deploys.
+    #[command(
+        name = "history",
+        about = "List or export the local journal of submitted deploys"
+    )]
+    History {
+        #[command(subcommand)]
+        command: HistoryCommands,
+    },
+
+    /// Refresh pending journal entries from the node.
+    #[command(
+        name = "reconcile",
+        about = "Refresh the status and cost of pending deploys in the local journal"
+    )]
+    Reconcile,
+
+    /// Bootstrap a network from a plan.
+    #[command(
+        name = "bootstrap",
+        about = "Deploy, fund and configure a network from a plan file, skipping completed steps"
+    )]
+    Bootstrap {
+        /// The network to bootstrap.
+        #[arg(
+            long,
+            default_value = "local",
+            value_parser = ["local", "testnet"],
+            help = "The network to bootstrap, checked against CHAIN_NAME"
+        )]
+        network: String,
+
+        /// The plan file.
+        #[arg(
+            long,
+            default_value = "bootstrap.json",
+            help = "JSON plan of the contracts, fundings, allocations, swap liquidity and swap window"
+        )]
+        plan: String,
+    },
+
+    /// Diff a deployment manifest against the chain.
+    #[command(
+        name = "plan",
+        about = "Show the installs, upgrades and calls needed to reach a deployment manifest"
+    )]
+    Plan {
+        /// The manifest file.
+        #[arg(
+            long,
+            default_value = "manifest.json",
+            help = "JSON manifest of the token, vesting and swap install arguments"
+        )]
+        manifest: String,
+    },
+
+    /// Apply a deployment manifest.
+    #[command(
+        name = "apply",
+        about = "Execute the installs, upgrades and calls needed to reach a deployment manifest"
+    )]
+    Apply {
+        /// The manifest file.
+        #[arg(
+            long,
+            default_value = "manifest.json",
+            help = "JSON manifest of the token, vesting and swap install arguments"
+        )]
+        manifest: String,
+    },
+
+    /// Consume the events of the COWL contracts.
+    #[command(
+        name = "events",
+        about = "Consume the CES events of the token, vesting and swap contracts"
+    )]
+    Events {
+        #[command(subcommand)]
+        command: EventsCommands,
+    },
+
+    /// Manage the address book.
+    #[command(
+        name = "contacts",
+        about = "Manage aliases of public keys, account hashes or contract package hashes"
+    )]
+    Contacts {
+        #[command(subcommand)]
+        command: ContactsCommands,
+    },
+
+    /// Export snapshots of the token state.
+    #[command(name = "snapshot", about = "Export snapshots of the token state")]
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+
+    /// Inspect the installed contracts.
+    #[command(
+        name = "contracts",
+        about = "Inspect the versions of the token, vesting and swap contracts"
+    )]
+    Contracts {
+        #[command(subcommand)]
+        command: ContractsCommands,
+    },
+
+    /// Manage the modalities of the contracts.
+    #[command(
+        name = "modalities",
+        about = "Read or set the events mode and other modalities of the contracts without redeploying"
+    )]
+    Modalities {
+        #[command(subcommand)]
+        command: ModalitiesCommands,
+    },
+
+    /// Start an interactive shell.
+    #[command(
+        name = "shell",
+        about = "Run commands in an interactive shell keeping keys and contract hashes cached"
+    )]
+    Shell,
+
+    /// Generate shell completions.
+    #[command(
+        name = "completions",
+        about = "Print the completion registration of a shell, completing vesting types, accounts and contacts"
+    )]
+    Completions {
+        /// Shell of the completion script.
+        #[arg(value_enum, help = "Shell to register the completions for")]
+        shell: Shell,
+    },
+
+    /// Generate man pages.
+    #[command(name = "man", about = "Print the man page or write a page per command")]
+    Man {
+        /// Directory of the man pages.
+        #[arg(
+            long = "out-dir",
+            help = "Directory to write a man page per command to, the main page is printed to stdout by default"
+        )]
+        out_dir: Option<String>,
+    },
+
+    /// Manage the wasm files.
+    #[command(
+        name = "wasm",
+        about = "Configure, lock and verify the contract and session wasm files"
+    )]
+    Wasm {
+        #[command(subcommand)]
+        command: WasmCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ContactsCommands {
+    /// Add or replace a contact.
+    #[command(name = "add", about = "Add or replace a contact")]
+    Add {
+        /// Alias of the contact.
+        #[arg(help = "Alias usable wherever a key is accepted. Example: exchange")]
+        alias: String,
+
+        /// Key of the contact.
+        #[arg(help = "The public key, account hash or contract package hash of the contact")]
+        key: String,
+    },
+
+    /// List the contacts.
+    #[command(name = "list", about = "List the contacts")]
+    List,
+
+    /// Remove a contact.
+    #[command(name = "remove", about = "Remove a contact")]
+    Remove {
+        /// Alias of the contact.
+        #[arg(help = "Alias of the contact to remove")]
+        alias: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SnapshotCommands {
+    /// Export all token holders.
+    #[command(
+        name = "holders",
+        about = "Export every token holder with its balance at a block, matched against the total supply"
+    )]
+    Holders {
+        #[command(flatten)]
+        state: StateIdentifierArgs,
+
+        /// Export format.
+        #[arg(long, default_value = commands::history::EXPORT_FORMAT_CSV, help = "Export format: csv or json")]
+        format: String,
+
+        /// Output file.
+        #[arg(
+            long,
+            help = "File to write the snapshot to, printed to stdout by default"
+        )]
+        out: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ContractsCommands {
+    /// List the versions of a contract package.
+    #[command(
+        name = "versions",
+        about = "List every version of a contract package with its status, entry points, named keys and install deploy"
+    )]
+    Versions {
+        #[command(flatten)]
+        contract: ContractArgs,
+    },
+
+    /// Check an upgrade before running it.
+    #[command(
+        name = "preflight",
+        about = "Compare an installed contract with its wasm, its token package and the Installer balance before an upgrade"
+    )]
+    Preflight {
+        #[command(flatten)]
+        contract: ContractArgs,
+    },
+}
+
+/// One of the contracts installed by the Installer.
+#[derive(Args, Debug, Clone)]
+#[group(required = true, multiple = false)]
+pub struct ContractArgs {
+    /// The token contract.
+    #[arg(long, help = "The token contract")]
+    token: bool,
+
+    /// The vesting contract.
+    #[arg(long, help = "The vesting contract")]
+    vesting: bool,
+
+    /// The swap contract.
+    #[arg(long, help = "The swap contract")]
+    swap: bool,
+}
+
+impl ContractArgs {
+    fn contract(&self) -> &'static str {
+        if self.token {
+            commands::contracts::CONTRACT_TOKEN
+        } else if self.vesting {
+            commands::contracts::CONTRACT_VESTING
+        } else {
+            commands::contracts::CONTRACT_SWAP
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ModalitiesCommands {
+    /// Set modalities of a contract.
+    #[command(
+        name = "set",
+        about = "Call set_modalities on a contract",
+        group(
+            ArgGroup::new("modalities")
+                .required(true)
+                .multiple(true)
+                .args(["events_mode", "enable_mint_burn"])
+        )
+    )]
+    Set {
+        /// The contract to set modalities of.
+        #[arg(
+            long,
+            value_parser = [
+                commands::contracts::CONTRACT_TOKEN,
+                commands::contracts::CONTRACT_VESTING,
+                commands::contracts::CONTRACT_SWAP,
+            ],
+            help = "The contract to set modalities of"
+        )]
+        contract: String,
+
+        /// The events mode.
+        #[arg(
+            long,
+            ignore_case = true,
+            value_parser = [
+                commands::modalities::EVENTS_MODE_NO_EVENTS,
+                commands::modalities::EVENTS_MODE_CES,
+            ],
+            help = "Events emitted by the contract"
+        )]
+        events_mode: Option<String>,
+
+        /// Enable mint and burn.
+        #[arg(
+            long,
+            help = "Enable the mint and burn entry points, token contract only"
+        )]
+        enable_mint_burn: Option<bool>,
+    },
+
+    /// Read the modalities of the contracts.
+    #[command(
+        name = "get",
+        about = "Read the current modalities of the contracts from their named keys"
+    )]
+    Get {
+        /// The contract to read modalities of.
+        #[arg(
+            long,
+            value_parser = [
+                commands::contracts::CONTRACT_TOKEN,
+                commands::contracts::CONTRACT_VESTING,
+                commands::contracts::CONTRACT_SWAP,
+            ],
+            help = "The contract to read modalities of, all contracts by default"
+        )]
+        contract: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WasmCommands {
+    /// Show or set the wasm directory.
+    #[command(
+        name = "path",
+        about = "Show or set the directory of the wasm files, overridden by WASM_PATH"
+    )]
+    Path {
+        /// The wasm directory.
+        #[arg(help = "Directory to read the wasm files from. Example: /opt/cowl/wasm")]
+        wasm_path: Option<String>,
+    },
+
+    /// Record the wasm hashes.
+    #[command(
+        name = "lock",
+        about = "Record the SHA-256 hashes of the wasm files in the lockfile"
+    )]
+    Lock,
+
+    /// Verify the wasm hashes.
+    #[command(
+        name = "verify",
+        about = "Check the SHA-256 hashes of the wasm files against the lockfile"
+    )]
+    Verify,
+
+    /// Show a wasm file.
+    #[command(
+        name = "info",
+        about = "Show the hash and the exported entry points of a wasm file"
+    )]
+    Info {
+        /// The wasm name.
+        #[arg(value_parser = WASM_NAMES, help = "Name of the wasm file, without extension")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EventsCommands {
+    /// Print events live from the events stream.
+    #[command(
+        name = "watch",
+        about = "Decode and print the contracts events live from EVENTS_ADDRESS"
+    )]
+    Watch {
+        /// Event ID to resume from.
+        #[arg(long, help = "Event ID of the stream to resume from")]
+        start_from: Option<u64>,
+
+        /// Output file.
+        #[arg(long, help = "File to append the decoded events to, one JSON per line")]
+        output: Option<String>,
+    },
+}
+
+/// Global state to query, the latest state by default.
+#[derive(Args, Debug, Clone, Default)]
+#[group(multiple = false)]
+pub struct StateIdentifierArgs {
+    /// Query the state at a block height.
+    #[arg(long, help = "Query the state at a block height")]
+    block_height: Option<u64>,
+
+    /// Query the state at a block hash.
+    #[arg(long, help = "Query the state at a block hash")]
+    block_hash: Option<String>,
+
+    /// Query the state at a state root hash.
+    #[arg(long, help = "Query the state at a state root hash")]
+    state_root_hash: Option<String>,
+}
+
+impl StateIdentifierArgs {
+    /// Block height or hash to query, `None` for the latest block or a state root hash.
+    fn block_identifier(&self) -> Option<String> {
+        self.block_height
+            .map(|block_height| block_height.to_string())
+            .or_else(|| self.block_hash.clone())
+    }
+
+    /// Resolves the state root hash to query, `None` for the latest state.
+    async fn resolve_state_root_hash(&self) -> Option<String> {
+        let block_identifier = if let Some(state_root_hash) = &self.state_root_hash {
+            return Some(state_root_hash.clone());
+        } else if let Some(block_height) = self.block_height {
+            block_height.to_string()
+        } else if let Some(block_hash) = &self.block_hash {
+            block_hash.clone()
+        } else {
+            return None;
+        };
+
+        match get_state_root_hash(&block_identifier).await {
+            Some(state_root_hash) => Some(state_root_hash),
+            None => {
+                log::error!("Failed to retrieve state root hash of block {block_identifier}");
+                exit(1)
+            }
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommands {
+    /// List journal entries.
+    #[command(name = "list", about = "List deploys recorded in the local journal")]
+    List {
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+    },
+
+    /// Export journal entries.
+    #[command(
+        name = "export",
+        about = "Export deploys recorded in the local journal"
+    )]
+    Export {
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+
+        /// Export format.
+        #[arg(long, default_value = commands::history::EXPORT_FORMAT_JSON, help = "Export format: json or csv")]
+        format: String,
+
+        /// Output file.
+        #[arg(
+            long,
+            help = "File to write the export to, printed to stdout by default"
+        )]
+        output: Option<String>,
+    },
+
+    /// Reconstruct the token history of an account from the chain.
+    #[command(
+        name = "transfers",
+        about = "Reconstruct the transfers, allowances and swaps of an account with its running balance"
+    )]
+    Transfers {
+        /// The account to reconstruct the history for.
+        #[arg(
+            long,
+            value_parser = parse_key_ref,
+            add = ArgValueCandidates::new(key_ref_candidates),
+            help = "The public key, account hash or vesting type to reconstruct the history for"
+        )]
+        key: KeyRef,
+
+        /// First block height to scan.
+        #[arg(
+            long,
+            help = "First block height to scan, the install of the contracts by default"
+        )]
+        from_height: Option<u64>,
+
+        /// Last block height to scan.
+        #[arg(long, help = "Last block height to scan, the latest block by default")]
+        to_height: Option<u64>,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryFilterArgs {
+    /// Filter by status.
+    #[arg(long, help = "Filter by status: Pending, Success or Failure")]
+    status: Option<String>,
+
+    /// Filter by command.
+    #[arg(long, help = "Filter by command name. Example: transfer")]
+    command: Option<String>,
+
+    /// Filter by signer.
+    #[arg(long, help = "Filter by signer public key")]
+    signer: Option<String>,
+
+    /// Maximum number of entries.
+    #[arg(long, help = "Only keep the most recent entries")]
+    limit: Option<usize>,
+}
+
+impl From<HistoryFilterArgs> for commands::history::HistoryFilter {
+    fn from(args: HistoryFilterArgs) -> Self {
+        Self {
+            status: args.status,
+            command: args.command,
+            signer: args.signer,
+            limit: args.limit,
+        }
+    }
 }
 
 pub async fn run() {
     let cli = Cli::parse();
 
-    log::info!("Command executed: {}", cli.command);
+    journal::set_current_command(std::env::args().skip(1).collect());
+
+    run_command(cli).await
+}
+
+/// Runs a parsed command, from the command line or from the shell.
+pub async fn run_command(cli: Cli) {
+    wasm::set_allow_unlocked_wasm(cli.allow_unlocked_wasm);
+
+    match &cli.command {
+        // Scripts and pages printed to stdout must not start with the log line
+        Commands::Completions { .. } | Commands::Man { out_dir: None } => {}
+        command => log::info!("Command executed: {}", command),
+    }
 
     match cli.command {
         Commands::Types => {
@@ -373,7 +1152,10 @@ pub async fn run() {
                 println!("{vesting_type}");
             }
         }
-        Commands::ListFundedAdresses => commands::addresses::print_funded_addresses().await,
+        Commands::ListFundedAdresses { state } => {
+            let state_root_hash = state.resolve_state_root_hash().await;
+            commands::addresses::print_funded_addresses(state_root_hash.as_deref()).await
+        }
         Commands::DeployContracts {
             token,
             vesting,
@@ -389,101 +1171,128 @@ pub async fn run() {
                 commands::deploy::deploy_all_contracts().await
             };
 
-            if let Err(e) = result {
-                log::error!("Error deploying contracts: {}", e);
-                std::process::exit(1);
+            match result {
+                Ok(()) => {}
+                // Declining to acknowledge breaking differences leaves the contract as is
+                Err(e) if e.kind() == ErrorKind::Interrupted => log::warn!("Upgrade aborted: {e}"),
+                Err(e) => {
+                    log::error!("Error deploying contracts: {}", e);
+                    exit(1);
+                }
             }
         }
         Commands::VestingInfo {
             vesting_type,
             call_entry_point,
+            state,
         } => {
+            let state_root_hash = state.resolve_state_root_hash().await;
+            if call_entry_point && state_root_hash.is_some() {
+                log::warn!("Entry point is not called when querying a past state");
+            }
             commands::info::print_vesting_info(
                 vesting_type
                     .as_str()
                     .try_into()
                     .expect("Failed to convert vesting type"),
-                call_entry_point,
+                call_entry_point && state_root_hash.is_none(),
+                state_root_hash.as_deref(),
             )
             .await
         }
-        Commands::VestingStatus { vesting_type } => {
-            let call_entry_point = true; // Always call entry point before getting status
+        Commands::VestingStatus {
+            vesting_type,
+            state,
+        } => {
+            let state_root_hash = state.resolve_state_root_hash().await;
+            // Always call entry point before getting the latest status
+            let call_entry_point = state_root_hash.is_none();
             commands::status::print_vesting_status(
                 vesting_type
                     .as_str()
                     .try_into()
                     .expect("Failed to convert vesting type"),
                 call_entry_point,
+                state_root_hash.as_deref(),
             )
             .await
         }
-        Commands::Balance { vesting_type, key } => {
+        Commands::Balance {
+            vesting_type,
+            key,
+            state,
+        } => {
+            let state_root_hash = state.resolve_state_root_hash().await;
             commands::balance::print_balance(
                 vesting_type.map(|f| {
                     f.as_str()
                         .try_into()
                         .expect("Failed to convert vesting type")
                 }),
-                key.map(|formatted_str| parse_key_from_formatted_str(&formatted_str)),
+                resolve_maybe_key(key).await,
                 None,
+                state_root_hash.as_deref(),
             )
             .await
         }
-        Commands::Transfer { from, to, amount } => {
-            // Retrieve the key pair for the recipient
-            let to_key = if let Some(key_pair) = get_key_pair_from_vesting(&to).await {
-                Key::from_account(key_pair.public_key.to_account_hash())
-            } else {
-                parse_key_from_formatted_str(&to)
-            };
+        Commands::Transfer {
+            from,
+            to,
+            amount,
+            memo,
+        } => {
+            let to_key = resolve_key(&to).await;
+            journal::set_memo(memo);
 
-            commands::transfer::print_transfer(
-                PublicKey::new(&from).expect("Failed to convert public key to key"),
-                to_key,
-                amount,
+            commands::transfer::print_transfer(resolve_public_key(&from).await, to_key, amount)
+                .await
+        }
+        Commands::Allowance {
+            owner,
+            spender,
+            state,
+        } => {
+            let owner_key = resolve_key(&owner).await;
+            let spender_key = resolve_key(&spender).await;
+            let state_root_hash = state.resolve_state_root_hash().await;
+            commands::allowance::print_get_allowance(
+                &owner_key,
+                &spender_key,
+                state_root_hash.as_deref(),
             )
             .await
         }
-        Commands::Allowance { owner, spender } => {
-            // Retrieve the key pair for the owner
-            let owner_key = if let Some(key_pair) = get_key_pair_from_vesting(&owner).await {
-                Key::from_account(key_pair.public_key.to_account_hash())
-            } else {
-                parse_key_from_formatted_str(&owner)
-            };
-
-            // Retrieve the key pair for the spender
-            let spender_key = if let Some(key_pair) = get_key_pair_from_vesting(&spender).await {
-                Key::from_account(key_pair.public_key.to_account_hash())
-            } else {
-                parse_key_from_formatted_str(&spender)
+        Commands::Allowances {
+            owner,
+            spender,
+            scan_events,
+            state,
+        } => {
+            let party = match (owner, spender) {
+                (Some(owner), _) => AllowanceParty::Owner(resolve_key(&owner).await),
+                (_, Some(spender)) => AllowanceParty::Spender(resolve_key(&spender).await),
+                _ => {
+                    log::error!("An owner or a spender is required");
+                    exit(1)
+                }
             };
-
-            commands::allowance::print_get_allowance(&owner_key, &spender_key).await
+            let state_root_hash = state.resolve_state_root_hash().await;
+            commands::allowances::print_allowances(party, scan_events, state_root_hash.as_deref())
+                .await
         }
         Commands::TransferFrom {
             operator,
             from,
             to,
             amount,
+            memo,
         } => {
-            // Retrieve the key pair for the recipient
-            let from_key = if let Some(key_pair) = get_key_pair_from_vesting(&from).await {
-                Key::from_account(key_pair.public_key.to_account_hash())
-            } else {
-                parse_key_from_formatted_str(&from)
-            };
-
-            // Retrieve the key pair for the recipient
-            let to_key = if let Some(key_pair) = get_key_pair_from_vesting(&to).await {
-                Key::from_account(key_pair.public_key.to_account_hash())
-            } else {
-                parse_key_from_formatted_str(&to)
-            };
+            let from_key = resolve_key(&from).await;
+            let to_key = resolve_key(&to).await;
+            journal::set_memo(memo);
 
             commands::transfer_from::print_transfer_from(
-                PublicKey::new(&operator).expect("Failed to convert public key to key"),
+                resolve_public_key(&operator).await,
                 from_key,
                 to_key,
                 amount,
@@ -495,15 +1304,10 @@ pub async fn run() {
             spender,
             amount,
         } => {
-            // Retrieve the key pair for the spender
-            let spender_key = if let Some(key_pair) = get_key_pair_from_vesting(&spender).await {
-                Key::from_account(key_pair.public_key.to_account_hash())
-            } else {
-                parse_key_from_formatted_str(&spender)
-            };
+            let spender_key = resolve_key(&spender).await;
 
             commands::allowance::print_increase_allowance(
-                &PublicKey::new(&owner).expect("Failed to convert public key to key"),
+                &resolve_public_key(&owner).await,
                 &spender_key,
                 amount,
             )
@@ -514,42 +1318,96 @@ pub async fn run() {
             spender,
             amount,
         } => {
-            // Retrieve the key pair for the spender
-            let spender_key = if let Some(key_pair) = get_key_pair_from_vesting(&spender).await {
-                Key::from_account(key_pair.public_key.to_account_hash())
-            } else {
-                parse_key_from_formatted_str(&spender)
-            };
+            let spender_key = resolve_key(&spender).await;
             commands::allowance::print_decrease_allowance(
-                &PublicKey::new(&owner).expect("Failed to convert public key to key"),
+                &resolve_public_key(&owner).await,
+                &spender_key,
+                amount,
+            )
+            .await
+        }
+        Commands::Approve {
+            owner,
+            spender,
+            amount,
+        } => {
+            let spender_key = resolve_key(&spender).await;
+            commands::allowance::print_approve(
+                &resolve_public_key(&owner).await,
                 &spender_key,
                 amount,
             )
             .await
         }
+        Commands::Revoke { owner, spender } => {
+            let spender_key = resolve_key(&spender).await;
+            commands::allowance::print_revoke(&resolve_public_key(&owner).await, &spender_key).await
+        }
         Commands::Fund {
             vesting_type,
             key,
-            amount,
-        } => {
+            all_vesting,
+            targets,
+            amount: Some(amount),
+            top_up_to: None,
+            transfer_id,
+            generate_transfer_id,
+        } if !all_vesting && targets.is_none() => {
+            let transfer_id = transfer_id
+                .or_else(|| generate_transfer_id.then(commands::fund::generate_transfer_id));
             commands::fund::print_fund_addresses(
                 vesting_type.map(|f| {
                     f.as_str()
                         .try_into()
                         .expect("Failed to convert vesting type")
                 }),
-                key.map(|formatted_str| parse_key_from_formatted_str(&formatted_str)),
+                resolve_maybe_key(key).await,
                 amount,
+                transfer_id,
             )
             .await
         }
+        Commands::Fund {
+            vesting_type,
+            key,
+            all_vesting,
+            targets,
+            amount,
+            top_up_to,
+            transfer_id,
+            generate_transfer_id: _,
+        } => {
+            // Transfers to several targets always carry distinct ids, generated unless given
+            let first_transfer_id =
+                transfer_id.unwrap_or_else(commands::fund::generate_transfer_id);
+            let fund_targets = if all_vesting {
+                commands::fund::vesting_fund_targets().await
+            } else if let Some(path) = targets {
+                commands::fund::read_fund_targets(&path)
+                    .await
+                    .unwrap_or_else(|err| {
+                        log::error!("{err}");
+                        exit(1)
+                    })
+            } else if let Some(vesting_type) = vesting_type {
+                vec![commands::fund::FundTarget {
+                    key: resolve_key(&KeyRef::Name(vesting_type.clone())).await,
+                    label: vesting_type,
+                }]
+            } else {
+                let key_ref = key.expect("fund-cspr requires a target");
+                vec![commands::fund::FundTarget {
+                    key: resolve_key(&key_ref).await,
+                    label: key_ref.to_string(),
+                }]
+            };
+            commands::fund::print_fund_targets(fund_targets, amount, top_up_to, first_transfer_id)
+                .await;
+        }
         Commands::UpgradeEvents => commands::upgrade_events::print_upgrade_events().await,
         Commands::DepositCowl { from, amount } => {
-            commands::deposit_cowl::print_deposit_cowl(
-                PublicKey::new(&from).expect("Failed to convert public key to key"),
-                amount,
-            )
-            .await
+            commands::deposit_cowl::print_deposit_cowl(resolve_public_key(&from).await, amount)
+                .await
         }
         Commands::DepositCspr { amount } => {
             commands::deposit_cspr::print_deposit_cspr(amount).await
@@ -560,25 +1418,109 @@ pub async fn run() {
         Commands::WithdrawCspr { amount } => {
             commands::withdraw_cspr::print_withdraw_cspr(amount).await
         }
-        Commands::SwapBalance => commands::swap_balance::print_swap_balance().await,
+        Commands::SwapBalance { state } => {
+            let state_root_hash = state.resolve_state_root_hash().await;
+            commands::swap_balance::print_swap_balance(state_root_hash.as_deref()).await
+        }
         Commands::CsprToCowl { from, amount } => {
-            commands::cspr_to_cowl::print_cspr_to_cowl(
-                PublicKey::new(&from).expect("Failed to convert public key to key"),
-                amount,
-            )
-            .await
+            commands::cspr_to_cowl::print_cspr_to_cowl(resolve_public_key(&from).await, amount)
+                .await
         }
         Commands::CowlToCspr { from, amount } => {
-            commands::cowl_to_cspr::print_cowl_to_cspr(
-                PublicKey::new(&from).expect("Failed to convert public key to key"),
-                amount,
-            )
-            .await
+            commands::cowl_to_cspr::print_cowl_to_cspr(resolve_public_key(&from).await, amount)
+                .await
         }
         Commands::UpdateTimes {
             start_time,
             duration,
         } => commands::update_times::print_update_times(start_time, duration).await,
+        Commands::DeployStatus {
+            deploy_hash,
+            wait,
+            interval,
+            timeout,
+        } => {
+            commands::deploy_status::print_deploy_status(deploy_hash, wait, interval, timeout).await
+        }
+        Commands::History { command } => match command {
+            HistoryCommands::List { filter } => {
+                commands::history::print_history(filter.into()).await
+            }
+            HistoryCommands::Export {
+                filter,
+                format,
+                output,
+            } => commands::history::export_history(filter.into(), format, output).await,
+            HistoryCommands::Transfers {
+                key,
+                from_height,
+                to_height,
+            } => {
+                let key = resolve_key(&key).await;
+                commands::transfer_history::print_transfer_history(key, from_height, to_height)
+                    .await
+            }
+        },
+        Commands::Reconcile => commands::history::reconcile().await,
+        Commands::Bootstrap { network, plan } => {
+            commands::bootstrap::print_bootstrap(network, plan).await
+        }
+        Commands::Plan { manifest } => commands::manifest::print_plan(manifest).await,
+        Commands::Apply { manifest } => commands::manifest::apply(manifest).await,
+        Commands::Events { command } => match command {
+            EventsCommands::Watch { start_from, output } => {
+                commands::events::watch_events(start_from, output).await
+            }
+        },
+        Commands::Contacts { command } => match command {
+            ContactsCommands::Add { alias, key } => {
+                commands::contacts::print_add_contact(alias, key).await
+            }
+            ContactsCommands::List => commands::contacts::print_contacts().await,
+            ContactsCommands::Remove { alias } => {
+                commands::contacts::print_remove_contact(alias).await
+            }
+        },
+        Commands::Snapshot { command } => match command {
+            SnapshotCommands::Holders { state, format, out } => {
+                commands::snapshot::print_holders_snapshot(
+                    state.block_identifier(),
+                    state.state_root_hash,
+                    format,
+                    out,
+                )
+                .await
+            }
+        },
+        Commands::Contracts { command } => match command {
+            ContractsCommands::Versions { contract } => {
+                commands::contracts::print_contract_versions(contract.contract()).await
+            }
+            ContractsCommands::Preflight { contract } => {
+                commands::preflight::print_preflight(contract.contract()).await
+            }
+        },
+        Commands::Modalities { command } => match command {
+            ModalitiesCommands::Set {
+                contract,
+                events_mode,
+                enable_mint_burn,
+            } => {
+                commands::modalities::set_modalities(contract, events_mode, enable_mint_burn).await
+            }
+            ModalitiesCommands::Get { contract } => {
+                commands::modalities::print_modalities(contract).await
+            }
+        },
+        Commands::Shell => commands::shell::shell().await,
+        Commands::Completions { shell } => commands::completions::print_completions(shell).await,
+        Commands::Man { out_dir } => commands::completions::print_man(out_dir).await,
+        Commands::Wasm { command } => match command {
+            WasmCommands::Path { wasm_path } => commands::wasm::print_wasm_path(wasm_path).await,
+            WasmCommands::Lock => commands::wasm::print_wasm_lock().await,
+            WasmCommands::Verify => commands::wasm::print_wasm_verify().await,
+            WasmCommands::Info { name } => commands::wasm::print_wasm_info(name).await,
+        },
     }
 }
 
@@ -586,7 +1528,7 @@ impl Display for Commands {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Commands::Types => write!(f, "List vesting Types"),
-            Commands::ListFundedAdresses => write!(f, "List Funded Adresses"),
+            Commands::ListFundedAdresses { .. } => write!(f, "List Funded Adresses"),
 
             Commands::DeployContracts {
                 token,
@@ -607,14 +1549,15 @@ impl Display for Commands {
                 }
             }
 
-            Commands::VestingInfo {
-                vesting_type,
-                call_entry_point: _,
-            } => write!(f, "Vesting Info for {vesting_type}",),
-            Commands::VestingStatus { vesting_type } => {
+            Commands::VestingInfo { vesting_type, .. } => {
+                write!(f, "Vesting Info for {vesting_type}",)
+            }
+            Commands::VestingStatus { vesting_type, .. } => {
                 write!(f, "Vesting Status for {vesting_type}",)
             }
-            Commands::Balance { vesting_type, key } => {
+            Commands::Balance {
+                vesting_type, key, ..
+            } => {
                 if let Some(vesting_type) = vesting_type {
                     write!(f, "Balance for {}", vesting_type)
                 } else if let Some(key) = key {
@@ -623,7 +1566,9 @@ impl Display for Commands {
                     write!(f, "Balance: No vesting_type or key provided",)
                 }
             }
-            Commands::Transfer { from, to, amount } => {
+            Commands::Transfer {
+                from, to, amount, ..
+            } => {
                 write!(
                     f,
                     "Transfer {} {} ({} {}) \nfrom {} \nto: {}",
@@ -635,18 +1580,24 @@ impl Display for Commands {
                     to.clone()
                 )
             }
-            Commands::Allowance { owner, spender } => {
+            Commands::Allowance { owner, spender, .. } => {
                 write!(
                     f,
                     "{} Allowance \nfrom {owner} \nto {spender}",
                     *COWL_CEP_18_TOKEN_SYMBOL
                 )
             }
+            Commands::Allowances { owner, spender, .. } => match (owner, spender) {
+                (Some(owner), _) => write!(f, "Allowances of owner {owner}"),
+                (_, Some(spender)) => write!(f, "Allowances of spender {spender}"),
+                _ => write!(f, "Allowances"),
+            },
             Commands::TransferFrom {
                 operator,
                 from,
                 to,
                 amount,
+                ..
             } => {
                 write!(
                     f,
@@ -690,24 +1641,58 @@ impl Display for Commands {
                     spender.clone(),
                 )
             }
+            Commands::Approve {
+                owner: _,
+                spender,
+                amount,
+            } => {
+                write!(
+                    f,
+                    "Approve Allowance {} {} ({} {}) \nof {}",
+                    format_with_thousands_separator(&motes_to_cspr(amount).unwrap()),
+                    *COWL_CEP_18_TOKEN_SYMBOL,
+                    amount,
+                    *COWL_CEP_18_COOL_SYMBOL,
+                    spender.clone(),
+                )
+            }
+            Commands::Revoke { owner: _, spender } => {
+                write!(f, "Revoke Allowance \nof {}", spender.clone())
+            }
             Commands::Fund {
                 vesting_type,
                 key,
+                all_vesting,
+                targets,
                 amount,
+                top_up_to,
+                ..
             } => {
-                let entity = vesting_type
-                    .clone()
-                    .map(|v| v.to_string())
-                    .or_else(|| key.clone());
+                let entity = if *all_vesting {
+                    Some("all vesting accounts".to_string())
+                } else if let Some(path) = targets {
+                    Some(format!("targets of {path}"))
+                } else {
+                    vesting_type
+                        .clone()
+                        .map(|v| v.to_string())
+                        .or_else(|| key.as_ref().map(|key| key.to_string()))
+                };
 
-                let message = match entity {
-                    Some(ref entity_str) => format!(
+                let message = match (entity, amount, top_up_to) {
+                    (Some(ref entity_str), _, Some(top_up_to)) => format!(
+                        "CSPR Funding up to {} CSPR ({} motes) for {}",
+                        format_with_thousands_separator(&motes_to_cspr(top_up_to).unwrap()),
+                        top_up_to,
+                        entity_str
+                    ),
+                    (Some(ref entity_str), Some(amount), None) => format!(
                         "CSPR Funding of {} CSPR ({} motes) for {}",
                         format_with_thousands_separator(&motes_to_cspr(amount).unwrap()),
                         amount,
                         entity_str
                     ),
-                    None => "CSPR Funding: No vesting_type or key provided".to_string(),
+                    _ => "CSPR Funding: No vesting_type or key provided".to_string(),
                 };
 
                 write!(f, "{}", message)
@@ -742,7 +1727,7 @@ impl Display for Commands {
                 format_with_thousands_separator(&motes_to_cspr(amount).unwrap()),
                 amount,
             ),
-            Commands::SwapBalance => write!(f, "Get Swap contract balance"),
+            Commands::SwapBalance { .. } => write!(f, "Get Swap contract balance"),
             Commands::CsprToCowl { from, amount } => write!(
                 f,
                 "Swap {} CSPR ({} motes) to {}\nfrom {}",
@@ -768,15 +1753,105 @@ impl Display for Commands {
                 "Update times start: {} for duration {}",
                 start_time, duration,
             ),
+            Commands::DeployStatus { deploy_hash, .. } => {
+                write!(f, "Deploy status for {deploy_hash}")
+            }
+            Commands::History { command } => match command {
+                HistoryCommands::List { .. } => write!(f, "List deploy history"),
+                HistoryCommands::Export { format, .. } => {
+                    write!(f, "Export deploy history as {format}")
+                }
+                HistoryCommands::Transfers { key, .. } => {
+                    write!(f, "Transfer history for {key}")
+                }
+            },
+            Commands::Reconcile => write!(f, "Reconcile pending deploys"),
+            Commands::Bootstrap { network, plan } => {
+                write!(f, "Bootstrap {network} from {plan}")
+            }
+            Commands::Plan { manifest } => write!(f, "Plan {manifest}"),
+            Commands::Apply { manifest } => write!(f, "Apply {manifest}"),
+            Commands::Events { command } => match command {
+                EventsCommands::Watch { start_from, .. } => match start_from {
+                    Some(start_from) => write!(f, "Watch events from event {start_from}"),
+                    None => write!(f, "Watch events"),
+                },
+            },
+            Commands::Contacts { command } => match command {
+                ContactsCommands::Add { alias, key } => write!(f, "Add contact {alias} for {key}"),
+                ContactsCommands::List => write!(f, "List contacts"),
+                ContactsCommands::Remove { alias } => write!(f, "Remove contact {alias}"),
+            },
+            Commands::Snapshot { command } => match command {
+                SnapshotCommands::Holders { state, .. } => {
+                    match (state.block_identifier(), &state.state_root_hash) {
+                        (Some(block_identifier), _) => {
+                            write!(f, "Snapshot holders at block {block_identifier}")
+                        }
+                        (None, Some(state_root_hash)) => {
+                            write!(f, "Snapshot holders at state root hash {state_root_hash}")
+                        }
+                        (None, None) => write!(f, "Snapshot holders"),
+                    }
+                }
+            },
+            Commands::Contracts { command } => match command {
+                ContractsCommands::Versions { contract } => {
+                    write!(f, "Versions of the {} contract", contract.contract())
+                }
+                ContractsCommands::Preflight { contract } => {
+                    write!(
+                        f,
+                        "Preflight of the {} contract upgrade",
+                        contract.contract()
+                    )
+                }
+            },
+            Commands::Modalities { command } => match command {
+                ModalitiesCommands::Set { contract, .. } => {
+                    write!(f, "Set modalities of the {contract} contract")
+                }
+                ModalitiesCommands::Get { contract } => match contract {
+                    Some(contract) => write!(f, "Modalities of the {contract} contract"),
+                    None => write!(f, "Modalities of the contracts"),
+                },
+            },
+            Commands::Shell => write!(f, "Shell"),
+            Commands::Completions { shell } => write!(f, "Completions for {shell}"),
+            Commands::Man { out_dir } => match out_dir {
+                Some(out_dir) => write!(f, "Man pages to {out_dir}"),
+                None => write!(f, "Man page"),
+            },
+            Commands::Wasm { command } => match command {
+                WasmCommands::Path { wasm_path } => match wasm_path {
+                    Some(wasm_path) => write!(f, "Set wasm path to {wasm_path}"),
+                    None => write!(f, "Show wasm path"),
+                },
+                WasmCommands::Lock => write!(f, "Lock wasm hashes"),
+                WasmCommands::Verify => write!(f, "Verify wasm hashes"),
+                WasmCommands::Info { name } => write!(f, "Wasm info for {name}"),
+            },
         }
     }
 }
 
-fn parse_key_from_formatted_str(formatted_str: &str) -> Key {
-    if let Ok(public_key) = PublicKey::new(formatted_str) {
-        Key::from_formatted_str(&public_key.to_account_hash().to_formatted_string())
-            .expect("Failed to convert public key to key")
-    } else {
-        Key::from_formatted_str(formatted_str).expect("Failed to convert key")
+async fn resolve_key(key_ref: &KeyRef) -> Key {
+    key_ref.to_key().await.unwrap_or_else(|err| {
+        log::error!("{err}");
+        exit(1)
+    })
+}
+
+async fn resolve_maybe_key(maybe_key_ref: Option<KeyRef>) -> Option<Key> {
+    match maybe_key_ref {
+        Some(key_ref) => Some(resolve_key(&key_ref).await),
+        None => None,
     }
 }
+
+async fn resolve_public_key(key_ref: &KeyRef) -> PublicKey {
+    key_ref.to_public_key().await.unwrap_or_else(|err| {
+        log::error!("{err}");
+        exit(1)
+    })
+}
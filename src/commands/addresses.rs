@@ -1,67 +1,93 @@
 use super::balance::{get_balance, get_cspr_account_balance};
 use crate::utils::{
     config::CONFIG_LOCK,
-    constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL},
+    constants::{BALANCE_QUERY_CONCURRENCY, COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL},
     format_with_thousands_separator,
+    keys::KeyPair,
 };
 use casper_rust_wasm_sdk::{helpers::motes_to_cspr, types::key::Key};
+use futures::{future::join, stream, StreamExt};
 use indexmap::IndexMap;
 
-pub async fn list_funded_addresses() -> Option<IndexMap<String, IndexMap<String, String>>> {
-    // Acquire the lock and clone info
-    let cloned_config = {
-        let config_lock = CONFIG_LOCK.lock().await;
-        config_lock.clone()
-    };
+async fn funded_address(
+    vesting_type: &str,
+    key_pair: &KeyPair,
+    maybe_state_root_hash: Option<&str>,
+) -> IndexMap<String, String> {
+    let mut key_map = IndexMap::new();
 
-    if let Some(config) = cloned_config.as_ref() {
-        let mut key_info_map: IndexMap<String, IndexMap<String, String>> = IndexMap::new();
-        for (vesting_type, (key_pair, _)) in config {
-            let mut key_map = IndexMap::new();
+    key_map.insert(
+        "public_key".to_string(),
+        key_pair.public_key.to_string().clone(),
+    );
+    key_map.insert(
+        "account_hash".to_string(),
+        key_pair
+            .public_key
+            .clone()
+            .to_account_hash()
+            .to_formatted_string(),
+    );
 
-            key_map.insert(
-                "public_key".to_string(),
-                key_pair.public_key.to_string().clone(),
-            );
-            key_map.insert(
-                "account_hash".to_string(),
-                key_pair
-                    .public_key
-                    .clone()
-                    .to_account_hash()
-                    .to_formatted_string(),
-            );
+    let ((balance, balance_motes), balance_token) = join(
+        get_cspr_account_balance(
+            Some(key_pair),
+            Some(vesting_type.to_string()),
+            None,
+            maybe_state_root_hash,
+        ),
+        get_balance(
+            None,
+            Some(Key::from_account(
+                key_pair.public_key.clone().to_account_hash(),
+            )),
+            maybe_state_root_hash,
+        ),
+    )
+    .await;
 
-            let (balance, balance_motes) =
-                get_cspr_account_balance(Some(key_pair), Some(vesting_type.to_string()), None)
-                    .await;
+    key_map.insert("balance_motes".to_string(), balance_motes);
 
-            key_map.insert("balance_motes".to_string(), balance_motes);
+    key_map.insert(
+        "balance_CSPR".to_string(),
+        format_with_thousands_separator(&balance),
+    );
 
-            key_map.insert(
-                "balance_CSPR".to_string(),
-                format_with_thousands_separator(&balance),
-            );
+    key_map.insert(
+        format!("balance_{}", *COWL_CEP_18_COOL_SYMBOL),
+        balance_token.clone(),
+    );
+    key_map.insert(
+        format!("balance_{}", *COWL_CEP_18_TOKEN_SYMBOL),
+        format_with_thousands_separator(&motes_to_cspr(&balance_token).unwrap()),
+    );
 
-            let balance_token = get_balance(
-                None,
-                Some(Key::from_account(
-                    key_pair.public_key.clone().to_account_hash(),
-                )),
-            )
-            .await;
+    if let Some(state_root_hash) = maybe_state_root_hash {
+        key_map.insert("state_root_hash".to_string(), state_root_hash.to_string());
+    }
 
-            key_map.insert(
-                format!("balance_{}", *COWL_CEP_18_COOL_SYMBOL),
-                balance_token.clone(),
-            );
-            key_map.insert(
-                format!("balance_{}", *COWL_CEP_18_TOKEN_SYMBOL),
-                format_with_thousands_separator(&motes_to_cspr(&balance_token).unwrap()),
-            );
+    key_map
+}
 
-            key_info_map.insert(vesting_type.clone(), key_map);
-        }
+pub async fn list_funded_addresses(
+    maybe_state_root_hash: Option<&str>,
+) -> Option<IndexMap<String, IndexMap<String, String>>> {
+    // Acquire the lock and clone info
+    let cloned_config = {
+        let config_lock = CONFIG_LOCK.lock().await;
+        config_lock.clone()
+    };
+
+    if let Some(config) = cloned_config.as_ref() {
+        // Balances of every account are fetched concurrently
+        let key_info_map: IndexMap<String, IndexMap<String, String>> = stream::iter(config)
+            .map(|(vesting_type, (key_pair, _))| async move {
+                let key_map = funded_address(vesting_type, key_pair, maybe_state_root_hash).await;
+                (vesting_type.clone(), key_map)
+            })
+            .buffered(BALANCE_QUERY_CONCURRENCY)
+            .collect()
+            .await;
 
         // Sort key_info_map by its keys
         let sorted_key_info_map = sort_indexmap(key_info_map);
@@ -80,8 +106,8 @@ fn sort_indexmap<K: Ord + Clone + std::hash::Hash, V: Clone>(
     sorted_entries.into_iter().collect()
 }
 
-pub async fn print_funded_addresses() {
-    if let Some(key_info_map) = list_funded_addresses().await {
+pub async fn print_funded_addresses(maybe_state_root_hash: Option<&str>) {
+    if let Some(key_info_map) = list_funded_addresses(maybe_state_root_hash).await {
         let json_output = serde_json::to_string_pretty(&key_info_map).unwrap();
         log::info!("{}", json_output);
     }
@@ -1,26 +1,39 @@
+use super::verification::{BalanceCheck, BalanceSnapshot, ExpectedDelta};
 use crate::utils::{
     call_token_set_allowance_entry_point,
-    constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL},
-    format_with_thousands_separator, get_contract_cep18_hash_keys, get_dictionary_item_params,
+    constants::{
+        COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL, COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT,
+    },
+    contacts::format_key_with_alias,
+    exit, format_with_thousands_separator, get_contract_cep18_hash_keys,
+    get_contract_cep18_hash_keys_at, get_dictionary_item_params, is_value_not_found,
     keys::retrieve_private_key,
-    prompt_yes_no, sdk, stored_value_to_parsed_string,
+    prompt_yes_no, sdk, stored_value_to_parsed_string, warn_if_contract_cannot_spend,
 };
+use bigdecimal::num_bigint::{BigInt, BigUint};
 use casper_rust_wasm_sdk::{
     helpers::{make_dictionary_item_key, motes_to_cspr},
     types::{key::Key, public_key::PublicKey},
 };
 use cowl_vesting::constants::DICT_ALLOWANCES;
 use serde_json::to_string;
+use std::str::FromStr;
 
-pub async fn get_allowance(owner: &Key, spender: &Key) -> String {
+/// Queries the allowance of a spender over an owner's tokens, failing when the token contract
+/// does not exist or the query fails. A missing allowance dictionary item is an allowance never
+/// set, read as zero.
+pub async fn query_allowance(
+    owner: &Key,
+    spender: &Key,
+    maybe_state_root_hash: Option<&str>,
+) -> Result<String, String> {
     // Retrieve contract token hash and package hash
-    let (cowl_cep18_token_contract_hash, _) = match get_contract_cep18_hash_keys().await {
-        Some((hash, package_hash)) => (hash, package_hash),
-        None => {
-            log::error!("Failed to retrieve contract token hash and package hash.");
-            return 0.to_string();
-        }
-    };
+    let (cowl_cep18_token_contract_hash, _) =
+        get_contract_cep18_hash_keys_at(maybe_state_root_hash)
+            .await
+            .ok_or_else(|| {
+                "Failed to retrieve contract token hash and package hash.".to_string()
+            })?;
 
     let dictionary_key = make_dictionary_item_key(owner, spender);
 
@@ -32,33 +45,46 @@ pub async fn get_allowance(owner: &Key, spender: &Key) -> String {
     );
 
     // Query the contract dictionary for the allowance
-    let allowance_result = sdk()
-        .query_contract_dict(dictionary_item, None::<&str>, None, None)
-        .await;
-
-    // Handle query result and extract stored value
-    let stored_value = match allowance_result {
+    let stored_value = match sdk()
+        .query_contract_dict(dictionary_item, maybe_state_root_hash, None, None)
+        .await
+    {
         Ok(result) => result.result.stored_value,
+        Err(err) if is_value_not_found(&format!("{:?}", err)) => return Ok(0.to_string()),
         Err(err) => {
-            log::error!("Failed to query allowance from the contract.{}", err);
-            return 0.to_string();
+            return Err(format!(
+                "Failed to query allowance from the contract.{}",
+                err
+            ))
         }
     };
 
-    let json_string = match to_string(&stored_value) {
-        Ok(s) => s,
-        Err(_) => {
-            log::error!("Failed to serialize stored value into JSON.");
-            return 0.to_string();
-        }
-    };
-    stored_value_to_parsed_string(&json_string).unwrap_or_default()
+    let json_string = to_string(&stored_value)
+        .map_err(|_| "Failed to serialize stored value into JSON.".to_string())?;
+    Ok(stored_value_to_parsed_string(&json_string).unwrap_or_default())
+}
+
+pub async fn get_allowance(
+    owner: &Key,
+    spender: &Key,
+    maybe_state_root_hash: Option<&str>,
+) -> String {
+    query_allowance(owner, spender, maybe_state_root_hash)
+        .await
+        .unwrap_or_else(|err| {
+            log::error!("{err}");
+            0.to_string()
+        })
 }
 
-pub async fn print_get_allowance(owner: &Key, spender: &Key) {
-    let allowance = get_allowance(owner, spender).await;
+pub async fn print_get_allowance(owner: &Key, spender: &Key, maybe_state_root_hash: Option<&str>) {
+    let allowance = get_allowance(owner, spender, maybe_state_root_hash).await;
+
+    if let Some(state_root_hash) = maybe_state_root_hash {
+        log::info!("State root hash {}", state_root_hash);
+    }
 
-    log::info!("Allowance for {}", spender.to_formatted_string());
+    log::info!("Allowance for {}", format_key_with_alias(spender));
     log::info!("{} {}", allowance, *COWL_CEP_18_COOL_SYMBOL);
     log::info!(
         "{} {}",
@@ -82,6 +108,10 @@ pub async fn set_allowance(
         }
     };
 
+    if !decrease {
+        warn_if_contract_cannot_spend(spender).await;
+    }
+
     // Retrieve the private key
     let secret_key = retrieve_private_key(owner).await;
 
@@ -98,6 +128,26 @@ pub async fn set_allowance(
         return None;
     }
 
+    let owner_key = Key::from_account(owner.to_account_hash());
+    let change = ExpectedDelta::amount(&amount);
+    let snapshot = BalanceSnapshot::take(vec![
+        BalanceCheck::allowance(
+            &owner_key,
+            spender,
+            if decrease {
+                ExpectedDelta::SaturatingDecrease(change)
+            } else {
+                ExpectedDelta::Exact(change)
+            },
+        ),
+        BalanceCheck::cspr(
+            "owner",
+            &owner_key,
+            ExpectedDelta::paying(&COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT),
+        ),
+    ])
+    .await;
+
     // Call the token decrease/increase entry point
     call_token_set_allowance_entry_point(
         &cowl_cep18_token_contract_package_hash,
@@ -109,13 +159,15 @@ pub async fn set_allowance(
     )
     .await;
 
-    let to_allowance = get_allowance(&Key::from_account(owner.to_account_hash()), spender).await;
+    snapshot.verify().await;
+
+    let to_allowance = get_allowance(&owner_key, spender, None).await;
     Some(to_allowance)
 }
 
 pub async fn print_increase_allowance(owner: &PublicKey, spender: &Key, amount: String) {
     if let Some(allowance) = set_allowance(owner, spender, amount, false).await {
-        log::info!("Increase allowance for {}", spender.to_formatted_string());
+        log::info!("Increase allowance for {}", format_key_with_alias(spender));
         log::info!(
             "{} {}",
             format_with_thousands_separator(&motes_to_cspr(&allowance).unwrap()),
@@ -125,8 +177,8 @@ pub async fn print_increase_allowance(owner: &PublicKey, spender: &Key, amount:
 }
 
 pub async fn print_decrease_allowance(owner: &PublicKey, spender: &Key, amount: String) {
-    if let Some(allowance) = set_allowance(owner, spender, amount, false).await {
-        log::info!("Decrease allowance for {}", spender.to_formatted_string());
+    if let Some(allowance) = set_allowance(owner, spender, amount, true).await {
+        log::info!("Decrease allowance for {}", format_key_with_alias(spender));
         log::info!(
             "{} {}",
             format_with_thousands_separator(&motes_to_cspr(&allowance).unwrap()),
@@ -134,3 +186,150 @@ pub async fn print_decrease_allowance(owner: &PublicKey, spender: &Key, amount:
         );
     }
 }
+
+/// Sets the allowance of a spender to an exact amount, increasing or decreasing it by the
+/// difference with the current allowance. The allowance is read again right before submitting
+/// and the approval is refused if it changed meanwhile, the final value is then verified.
+pub async fn approve(owner: &PublicKey, spender: &Key, amount: String) -> Option<String> {
+    let Ok(target) = BigUint::from_str(&amount) else {
+        log::error!("Invalid amount {amount}");
+        exit(1)
+    };
+
+    // Retrieve contract token hash and package hash
+    let (_, cowl_cep18_token_contract_package_hash) = match get_contract_cep18_hash_keys().await {
+        Some((hash, package_hash)) => (hash, package_hash),
+        None => {
+            log::error!("Failed to retrieve contract token hash and package hash.");
+            return None;
+        }
+    };
+
+    let owner_key = Key::from_account(owner.to_account_hash());
+    let read_allowance = current_allowance(&owner_key, spender).await;
+    let current = BigUint::from_str(&read_allowance).unwrap_or_default();
+    if current == target {
+        log::info!(
+            "Allowance for {} is already {}",
+            format_key_with_alias(spender),
+            amount
+        );
+        return Some(read_allowance);
+    }
+
+    let decrease = target < current;
+    let difference = if decrease {
+        &current - &target
+    } else {
+        &target - &current
+    }
+    .to_string();
+
+    if !decrease {
+        warn_if_contract_cannot_spend(spender).await;
+    }
+
+    // Retrieve the private key
+    let secret_key = retrieve_private_key(owner).await;
+
+    let answer = prompt_yes_no(&format!(
+        "Please confirm setting allowance of {} to {} ({}), {} it by {} ({})?",
+        &spender.to_formatted_string(),
+        format_with_thousands_separator(&motes_to_cspr(&amount).unwrap()),
+        *COWL_CEP_18_TOKEN_SYMBOL,
+        if decrease { "decreasing" } else { "increasing" },
+        format_with_thousands_separator(&motes_to_cspr(&difference).unwrap()),
+        *COWL_CEP_18_TOKEN_SYMBOL,
+    ));
+
+    if !answer {
+        log::warn!("Setting allowance aborted.");
+        return None;
+    }
+
+    // The difference only holds for the allowance it was computed from
+    let submit_allowance = current_allowance(&owner_key, spender).await;
+    if submit_allowance != read_allowance {
+        log::error!(
+            "Allowance for {} changed from {} to {} since it was read, approve refused",
+            format_key_with_alias(spender),
+            read_allowance,
+            submit_allowance
+        );
+        exit(1)
+    }
+
+    let snapshot = BalanceSnapshot::take(vec![
+        BalanceCheck::allowance(
+            &owner_key,
+            spender,
+            ExpectedDelta::Exact(BigInt::from(target.clone()) - BigInt::from(current)),
+        ),
+        BalanceCheck::cspr(
+            "owner",
+            &owner_key,
+            ExpectedDelta::paying(&COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT),
+        ),
+    ])
+    .await;
+
+    let (success_message, _) = call_token_set_allowance_entry_point(
+        &cowl_cep18_token_contract_package_hash,
+        owner,
+        secret_key.expect("Failed to retrieve sender private key."),
+        spender,
+        difference,
+        decrease,
+    )
+    .await;
+    if success_message.is_empty() {
+        log::error!(
+            "Failed to set allowance for {}",
+            format_key_with_alias(spender)
+        );
+        exit(1)
+    }
+
+    snapshot.verify().await;
+
+    let final_allowance = current_allowance(&owner_key, spender).await;
+    if BigUint::from_str(&final_allowance).ok() != Some(target) {
+        log::error!(
+            "Allowance for {} is {} after approve, expected {}",
+            format_key_with_alias(spender),
+            final_allowance,
+            amount
+        );
+        exit(1)
+    }
+    Some(final_allowance)
+}
+
+// Any error other than an allowance never set aborts, as the allowance would be unknown
+async fn current_allowance(owner: &Key, spender: &Key) -> String {
+    query_allowance(owner, spender, None)
+        .await
+        .unwrap_or_else(|err| {
+            log::error!("Could not read the allowance: {err}");
+            exit(1)
+        })
+}
+
+pub async fn print_approve(owner: &PublicKey, spender: &Key, amount: String) {
+    if let Some(allowance) = approve(owner, spender, amount).await {
+        log::info!("Approve allowance for {}", format_key_with_alias(spender));
+        log::info!("{} {}", allowance, *COWL_CEP_18_COOL_SYMBOL);
+        log::info!(
+            "{} {}",
+            format_with_thousands_separator(&motes_to_cspr(&allowance).unwrap()),
+            *COWL_CEP_18_TOKEN_SYMBOL
+        );
+    }
+}
+
+pub async fn print_revoke(owner: &PublicKey, spender: &Key) {
+    if let Some(allowance) = approve(owner, spender, 0.to_string()).await {
+        log::info!("Revoke allowance for {}", format_key_with_alias(spender));
+        log::info!("{} {}", allowance, *COWL_CEP_18_COOL_SYMBOL);
+    }
+}
@@ -0,0 +1,215 @@
+use super::allowance::query_allowance;
+use crate::utils::{
+    config::CONFIG_LOCK,
+    constants::{BALANCE_QUERY_CONCURRENCY, COWL_CEP_18_TOKEN_SYMBOL},
+    contacts::{contact_alias, load_contacts},
+    events::{events_start_height, get_event_sources, scan_events, EVENT_SOURCE_TOKEN},
+    format_with_thousands_separator, get_block, get_contract_swap_hash_keys,
+    get_contract_vesting_hash_keys,
+    key_ref::parse_key,
+};
+use bigdecimal::num_bigint::BigUint;
+use casper_rust_wasm_sdk::{helpers::motes_to_cspr, types::key::Key};
+use futures::{stream, StreamExt};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+const LABEL_VESTING_CONTRACT: &str = "vesting contract";
+const LABEL_SWAP_CONTRACT: &str = "swap contract";
+const LABEL_EVENTS: &str = "events";
+
+// Events of the token naming an owner and a spender
+const ALLOWANCE_EVENTS: [&str; 4] = [
+    "SetAllowance",
+    "IncreaseAllowance",
+    "DecreaseAllowance",
+    "TransferFrom",
+];
+
+/// Side of the allowances to list, the other side being every known account.
+#[derive(Debug, Clone)]
+pub enum AllowanceParty {
+    Owner(Key),
+    Spender(Key),
+}
+
+/// Known accounts by formatted key: configured and vesting accounts, contacts and the vesting
+/// and swap contract packages.
+async fn known_accounts() -> IndexMap<String, String> {
+    let mut accounts = IndexMap::new();
+
+    let cloned_config = {
+        let config_lock = CONFIG_LOCK.lock().await;
+        config_lock.clone()
+    };
+    let mut config_accounts: Vec<(String, Key)> = cloned_config
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, (key_pair, _))| {
+            (
+                name,
+                Key::from_account(key_pair.public_key.to_account_hash()),
+            )
+        })
+        .collect();
+    config_accounts.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, key) in config_accounts {
+        accounts.entry(key.to_formatted_string()).or_insert(name);
+    }
+
+    for (alias, value) in load_contacts().unwrap_or_default() {
+        match parse_key(&value) {
+            Ok(key) => {
+                accounts.entry(key.to_formatted_string()).or_insert(alias);
+            }
+            Err(err) => log::warn!("Skipping contact {alias}: {err}"),
+        }
+    }
+
+    let packages = [
+        (
+            LABEL_VESTING_CONTRACT,
+            get_contract_vesting_hash_keys().await,
+        ),
+        (LABEL_SWAP_CONTRACT, get_contract_swap_hash_keys().await),
+    ];
+    for (label, hash_keys) in packages {
+        if let Some(key) = hash_keys.and_then(|(_, package_hash)| parse_key(&package_hash).ok()) {
+            accounts
+                .entry(key.to_formatted_string())
+                .or_insert(label.to_string());
+        }
+    }
+    accounts
+}
+
+/// Finds the counterparts of a key in the allowance events of the token.
+async fn event_counterparts(party: &AllowanceParty) -> Result<Vec<String>, String> {
+    let event_sources: Vec<_> = get_event_sources()
+        .await
+        .into_iter()
+        .filter(|source| source.label == EVENT_SOURCE_TOKEN)
+        .collect();
+    if event_sources.is_empty() {
+        return Err("Events are not enabled on the token contract".to_string());
+    }
+    let block_height = get_block(None)
+        .await
+        .and_then(|block| block["header"]["height"].as_u64())
+        .ok_or_else(|| "Failed to retrieve the latest block".to_string())?;
+
+    let (field, counterpart_field, key) = match party {
+        AllowanceParty::Owner(key) => ("owner", "spender", key),
+        AllowanceParty::Spender(key) => ("spender", "owner", key),
+    };
+    let formatted_key = key.to_formatted_string();
+    let mut counterparts = vec![];
+    let from_height = events_start_height(&event_sources).await?;
+    for block_event in scan_events(from_height, block_height, &event_sources).await? {
+        let fields = &block_event.event.fields;
+        if !ALLOWANCE_EVENTS.contains(&block_event.event.name.as_str())
+            || fields.get(field) != Some(&formatted_key)
+        {
+            continue;
+        }
+        if let Some(counterpart) = fields.get(counterpart_field) {
+            if !counterparts.contains(counterpart) {
+                counterparts.push(counterpart.clone());
+            }
+        }
+    }
+    Ok(counterparts)
+}
+
+/// Checks the allowances between a key and every known account, keeping the non-zero ones.
+/// Counterparts found in the allowance events of the token are checked as well when
+/// `discover_from_events` is set.
+pub async fn list_allowances(
+    party: &AllowanceParty,
+    discover_from_events: bool,
+    maybe_state_root_hash: Option<&str>,
+) -> Vec<IndexMap<String, Value>> {
+    let key = match party {
+        AllowanceParty::Owner(key) | AllowanceParty::Spender(key) => key,
+    };
+    let mut counterparts = known_accounts().await;
+    if discover_from_events {
+        match event_counterparts(party).await {
+            Ok(event_counterparts) => {
+                for counterpart in event_counterparts {
+                    let label = Key::from_formatted_str(&counterpart)
+                        .ok()
+                        .and_then(|key| contact_alias(&key))
+                        .unwrap_or_else(|| LABEL_EVENTS.to_string());
+                    counterparts.entry(counterpart).or_insert(label);
+                }
+            }
+            Err(err) => log::warn!("Skipping spender discovery from events: {err}"),
+        }
+    }
+    counterparts.shift_remove(&key.to_formatted_string());
+    log::info!("Checking allowances with {} accounts", counterparts.len());
+
+    // Allowances are queried concurrently, in the order of the known accounts
+    let allowances: Vec<Option<IndexMap<String, Value>>> = stream::iter(counterparts)
+        .map(|(counterpart, label)| async move {
+            let counterpart_key = Key::from_formatted_str(&counterpart).ok()?;
+            let (owner, spender) = match party {
+                AllowanceParty::Owner(owner) => (owner, &counterpart_key),
+                AllowanceParty::Spender(spender) => (&counterpart_key, spender),
+            };
+            // An allowance that cannot be read is skipped, a missing one being zero
+            let allowance = match query_allowance(owner, spender, maybe_state_root_hash).await {
+                Ok(allowance) => allowance,
+                Err(err) => {
+                    log::warn!("Skipping the allowance of {label}: {err}");
+                    return None;
+                }
+            };
+            if BigUint::from_str(&allowance).unwrap_or_default() == BigUint::default() {
+                return None;
+            }
+
+            let mut entry = IndexMap::new();
+            entry.insert("label".to_string(), json!(label));
+            entry.insert("key".to_string(), json!(counterpart));
+            entry.insert("allowance".to_string(), json!(allowance));
+            entry.insert(
+                format!("allowance_{}", *COWL_CEP_18_TOKEN_SYMBOL),
+                json!(format_with_thousands_separator(
+                    &motes_to_cspr(&allowance).unwrap_or_default()
+                )),
+            );
+            Some(entry)
+        })
+        .buffered(BALANCE_QUERY_CONCURRENCY)
+        .collect()
+        .await;
+    allowances.into_iter().flatten().collect()
+}
+
+pub async fn print_allowances(
+    party: AllowanceParty,
+    discover_from_events: bool,
+    maybe_state_root_hash: Option<&str>,
+) {
+    let allowances = list_allowances(&party, discover_from_events, maybe_state_root_hash).await;
+
+    let mut allowances_map = IndexMap::new();
+    match &party {
+        AllowanceParty::Owner(owner) => {
+            allowances_map.insert("owner".to_string(), json!(owner.to_formatted_string()));
+        }
+        AllowanceParty::Spender(spender) => {
+            allowances_map.insert("spender".to_string(), json!(spender.to_formatted_string()));
+        }
+    }
+    if let Some(state_root_hash) = maybe_state_root_hash {
+        allowances_map.insert("state_root_hash".to_string(), json!(state_root_hash));
+    }
+    allowances_map.insert("allowances".to_string(), json!(allowances));
+
+    let json_output = serde_json::to_string_pretty(&allowances_map).unwrap();
+    log::info!("\n{}", json_output);
+}
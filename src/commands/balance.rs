@@ -1,8 +1,9 @@
 use crate::utils::{
     config::get_key_pair_from_vesting,
     constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL, DEFAULT_BALANCE},
-    format_with_thousands_separator, get_contract_cep18_hash_keys, get_contract_swap_purse,
-    get_dictionary_item_params,
+    contacts::contact_alias,
+    format_with_thousands_separator, get_contract_cep18_hash_keys_at, get_contract_purse,
+    get_dictionary_item_params, is_value_not_found,
     keys::{get_key_pair_from_key, KeyPair},
     sdk, stored_value_to_parsed_string,
 };
@@ -14,23 +15,18 @@ use cowl_vesting::{constants::DICT_BALANCES, enums::VestingType};
 use indexmap::IndexMap;
 use serde_json::to_string;
 
-pub async fn get_balance(
+/// Queries the token balance of a vesting account or key, a balance never written being zero.
+/// Fails when the balance cannot be read, like on an error of the node.
+pub async fn query_balance(
     maybe_vesting_type: Option<VestingType>,
     maybe_key: Option<Key>,
-) -> String {
-    let dictionary_key = match determine_dictionary_key(maybe_vesting_type, maybe_key.clone()).await
-    {
-        Ok(key) => key,
-        Err(err) => {
-            log::error!("{err}");
-            return DEFAULT_BALANCE.to_string();
-        }
-    };
+    maybe_state_root_hash: Option<&str>,
+) -> Result<String, String> {
+    let dictionary_key = determine_dictionary_key(maybe_vesting_type, maybe_key).await?;
 
-    let cowl_cep18_token_contract_hash = match fetch_contract_hash().await {
-        Some(hash) => hash,
-        None => return DEFAULT_BALANCE.to_string(),
-    };
+    let cowl_cep18_token_contract_hash = fetch_contract_hash(maybe_state_root_hash)
+        .await
+        .ok_or_else(|| "Failed to retrieve contract token hash.".to_string())?;
 
     let dictionary_item = get_dictionary_item_params(
         &cowl_cep18_token_contract_hash,
@@ -39,18 +35,29 @@ pub async fn get_balance(
     );
 
     let balance_result = sdk()
-        .query_contract_dict(dictionary_item, None::<&str>, None, None)
+        .query_contract_dict(dictionary_item, maybe_state_root_hash, None, None)
         .await;
 
-    // Process the result directly
     match balance_result {
         Ok(response) => {
-            let stored_value = &response.result.stored_value; // Assuming `stored_value` is what you need
-            let json_string = to_string(stored_value).ok().unwrap();
-            stored_value_to_parsed_string(&json_string).unwrap_or_default()
+            let json_string = to_string(&response.result.stored_value)
+                .map_err(|_| "Failed to serialize stored value into JSON.".to_string())?;
+            Ok(stored_value_to_parsed_string(&json_string).unwrap_or_default())
         }
+        Err(err) if is_value_not_found(&format!("{:?}", err)) => Ok(DEFAULT_BALANCE.to_string()),
+        Err(err) => Err(format!("Failed to query balance: {err}")),
+    }
+}
+
+pub async fn get_balance(
+    maybe_vesting_type: Option<VestingType>,
+    maybe_key: Option<Key>,
+    maybe_state_root_hash: Option<&str>,
+) -> String {
+    match query_balance(maybe_vesting_type, maybe_key.clone(), maybe_state_root_hash).await {
+        Ok(balance) => balance,
         Err(err) => {
-            log_balance_error(err.to_string(), maybe_vesting_type, maybe_key).await;
+            log_balance_error(err, maybe_vesting_type, maybe_key).await;
             DEFAULT_BALANCE.to_string()
         }
     }
@@ -75,14 +82,8 @@ async fn determine_dictionary_key(
             )
         })
     } else if let Some(key) = maybe_key {
-        if key.to_formatted_string().contains("account") {
-            get_base64_key_from_account_hash(
-                &key.clone()
-                    .into_account()
-                    .ok_or_else(|| "get_balance method expects an account".to_string())?
-                    .to_formatted_string(),
-            )
-            .map_err(|err| {
+        if let Some(account_hash) = key.clone().into_account() {
+            get_base64_key_from_account_hash(&account_hash.to_formatted_string()).map_err(|err| {
                 format!(
                     "Failed to retrieve account hash for {}: {:?}",
                     key.to_formatted_string(),
@@ -90,6 +91,7 @@ async fn determine_dictionary_key(
                 )
             })
         } else {
+            // Contract and package hashes hold balances under their `hash-` key
             get_base64_key_from_key_hash(&key.to_formatted_string()).map_err(|err| {
                 format!(
                     "Failed to retrieve contract hash for {}: {:?}",
@@ -103,8 +105,8 @@ async fn determine_dictionary_key(
     }
 }
 
-async fn fetch_contract_hash() -> Option<String> {
-    match get_contract_cep18_hash_keys().await {
+async fn fetch_contract_hash(maybe_state_root_hash: Option<&str>) -> Option<String> {
+    match get_contract_cep18_hash_keys_at(maybe_state_root_hash).await {
         Some((hash, _)) => Some(hash.to_string()),
         None => {
             log::error!("Failed to retrieve contract token hash and package hash.");
@@ -153,6 +155,7 @@ pub async fn print_balance(
     maybe_vesting_type: Option<VestingType>,
     maybe_key: Option<Key>,
     maybe_contract: Option<Key>,
+    maybe_state_root_hash: Option<&str>,
 ) {
     let mut key_info_map: IndexMap<String, IndexMap<String, String>> = IndexMap::new();
     let mut key_map = IndexMap::new();
@@ -160,6 +163,7 @@ pub async fn print_balance(
     let balance_token = get_balance(
         maybe_vesting_type,
         maybe_key.clone().or_else(|| maybe_contract.clone()),
+        maybe_state_root_hash,
     )
     .await;
 
@@ -167,6 +171,7 @@ pub async fn print_balance(
         maybe_vesting_type,
         maybe_key.clone(),
         maybe_contract.clone(),
+        maybe_state_root_hash,
     )
     .await;
 
@@ -192,16 +197,64 @@ pub async fn print_balance(
         .or_else(|| maybe_contract.map(|contract| contract.to_formatted_string()))
         .unwrap_or_default();
 
+    if let Some(alias) = maybe_key
+        .as_ref()
+        .or(maybe_contract.as_ref())
+        .and_then(contact_alias)
+    {
+        key_map.insert("alias".to_string(), alias);
+    }
+
+    if let Some(state_root_hash) = maybe_state_root_hash {
+        key_map.insert("state_root_hash".to_string(), state_root_hash.to_string());
+    }
+
     key_info_map.insert(identifier.clone(), key_map);
 
     let json_output = serde_json::to_string_pretty(&key_info_map).unwrap();
     log::info!("\n{}", json_output);
 }
 
+/// Queries the CSPR balance in motes of an account main purse or of a contract purse, an account
+/// never funded holding zero. Fails when the balance cannot be read.
+pub async fn query_cspr_balance(
+    key: &Key,
+    maybe_state_root_hash: Option<&str>,
+) -> Result<String, String> {
+    let purse_identifier = match key.clone().into_account() {
+        Some(account_hash) => PurseIdentifier::from_main_purse_under_account_hash(account_hash),
+        None => {
+            let purse_uref = get_contract_purse(key, maybe_state_root_hash)
+                .await
+                .ok_or_else(|| format!("No CSPR purse for {}", key.to_formatted_string()))?;
+            PurseIdentifier::from_purse_uref(purse_uref)
+        }
+    };
+
+    let balance_result = sdk()
+        .query_balance(
+            None,
+            None,
+            Some(purse_identifier),
+            maybe_state_root_hash.map(str::to_string),
+            None,
+            None,
+            None,
+        )
+        .await;
+
+    match balance_result {
+        Ok(balance) => Ok(balance.result.balance.to_string()),
+        Err(err) if is_value_not_found(&format!("{:?}", err)) => Ok(DEFAULT_BALANCE.to_string()),
+        Err(err) => Err(format!("Failed to query CSPR balance: {err}")),
+    }
+}
+
 pub async fn get_cspr_account_balance(
     key_pair: Option<&KeyPair>,
     string_identifier: Option<String>,
     maybe_key: Option<Key>,
+    maybe_state_root_hash: Option<&str>,
 ) -> (String, String) {
     let purse_identifier = determine_purse_identifier(key_pair, maybe_key.clone());
     if purse_identifier.is_none() {
@@ -211,7 +264,15 @@ pub async fn get_cspr_account_balance(
     let purse_identifier = purse_identifier.unwrap();
 
     let maybe_balance_motes = sdk()
-        .query_balance(None, None, Some(purse_identifier), None, None, None, None)
+        .query_balance(
+            None,
+            None,
+            Some(purse_identifier),
+            maybe_state_root_hash.map(str::to_string),
+            None,
+            None,
+            None,
+        )
         .await;
 
     let balance_motes = match maybe_balance_motes {
@@ -269,15 +330,31 @@ fn handle_balance_error(
     DEFAULT_BALANCE.to_string()
 }
 
-pub async fn get_cspr_contract_balance(contract_package: &Key) -> (String, String) {
-    let purse_uref = get_contract_swap_purse(contract_package)
-        .await
-        .expect("contract should have a purse Uref");
+pub async fn get_cspr_contract_balance(
+    contract_package: &Key,
+    maybe_state_root_hash: Option<&str>,
+) -> (String, String) {
+    let Some(purse_uref) = get_contract_purse(contract_package, maybe_state_root_hash).await else {
+        log::warn!(
+            "No CSPR purse for Contract\n\
+            - Key: {}",
+            contract_package.to_formatted_string()
+        );
+        return (DEFAULT_BALANCE.to_string(), DEFAULT_BALANCE.to_string());
+    };
 
     let purse_identifier = PurseIdentifier::from_purse_uref(purse_uref);
 
     let maybe_balance_motes = sdk()
-        .query_balance(None, None, Some(purse_identifier), None, None, None, None)
+        .query_balance(
+            None,
+            None,
+            Some(purse_identifier),
+            maybe_state_root_hash.map(str::to_string),
+            None,
+            None,
+            None,
+        )
         .await;
 
     let balance_motes = if let Ok(balance_motes) = maybe_balance_motes {
@@ -298,37 +375,58 @@ async fn get_cspr_balance_from_vesting_or_key(
     maybe_vesting_type: Option<VestingType>,
     maybe_key: Option<Key>,
     maybe_contract: Option<Key>,
+    maybe_state_root_hash: Option<&str>,
 ) -> (String, String) {
     if let Some(contract) = maybe_contract {
-        return get_cspr_contract_balance(&contract).await;
+        return get_cspr_contract_balance(&contract, maybe_state_root_hash).await;
     }
 
     if let Some(vesting_type) = maybe_vesting_type {
-        return handle_vesting_type_balance(vesting_type).await;
+        return handle_vesting_type_balance(vesting_type, maybe_state_root_hash).await;
     }
 
     if let Some(key) = maybe_key {
-        return handle_key_balance(key).await;
+        if key.clone().into_account().is_none() {
+            return get_cspr_contract_balance(&key, maybe_state_root_hash).await;
+        }
+        return handle_key_balance(key, maybe_state_root_hash).await;
     }
 
     (DEFAULT_BALANCE.to_string(), DEFAULT_BALANCE.to_string())
 }
 
-async fn handle_vesting_type_balance(vesting_type: VestingType) -> (String, String) {
+async fn handle_vesting_type_balance(
+    vesting_type: VestingType,
+    maybe_state_root_hash: Option<&str>,
+) -> (String, String) {
     let key_pair = get_key_pair_from_vesting(&vesting_type.to_string())
         .await
         .unwrap();
-    get_cspr_account_balance(Some(&key_pair), Some(vesting_type.to_string()), None).await
+    get_cspr_account_balance(
+        Some(&key_pair),
+        Some(vesting_type.to_string()),
+        None,
+        maybe_state_root_hash,
+    )
+    .await
 }
 
-async fn handle_key_balance(key: Key) -> (String, String) {
+async fn handle_key_balance(key: Key, maybe_state_root_hash: Option<&str>) -> (String, String) {
     let (maybe_vesting_type, maybe_key_pair) = get_key_pair_from_key(&key).await;
 
     match (maybe_vesting_type, maybe_key_pair) {
         (Some(vesting_type), Some(key_pair)) => {
-            get_cspr_account_balance(Some(&key_pair), Some(vesting_type), None).await
+            get_cspr_account_balance(
+                Some(&key_pair),
+                Some(vesting_type),
+                None,
+                maybe_state_root_hash,
+            )
+            .await
+        }
+        (None, Some(key_pair)) => {
+            get_cspr_account_balance(Some(&key_pair), None, None, maybe_state_root_hash).await
         }
-        (None, Some(key_pair)) => get_cspr_account_balance(Some(&key_pair), None, None).await,
-        _ => get_cspr_account_balance(None, None, Some(key)).await,
+        _ => get_cspr_account_balance(None, None, Some(key), maybe_state_root_hash).await,
     }
 }
@@ -0,0 +1,398 @@
+use crate::{
+    commands::{
+        balance::{query_balance, query_cspr_balance},
+        deploy::{deploy_cep18_token, deploy_swap_contract, deploy_vesting_contract},
+        deposit_cowl::deposit_cowl,
+        deposit_cspr::deposit_cspr,
+        fund::{generate_transfer_id, parse_fund_target, print_fund_targets, vesting_fund_targets},
+        transfer::transfer,
+        update_times::{get_swap_times, update_times},
+    },
+    utils::{
+        assume_yes,
+        config::get_key_pair_from_vesting,
+        constants::{CHAIN_NAME, INSTALLER},
+        exit, get_contract_cep18_hash_keys, get_contract_swap_hash_keys,
+        get_contract_vesting_hash_keys,
+        journal::load_journal,
+        key_ref::parse_key_ref,
+        prompt_yes_no,
+    },
+};
+use bigdecimal::num_bigint::BigUint;
+use casper_rust_wasm_sdk::types::key::Key;
+use chrono::Utc;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{fs, future::Future, io::Error, str::FromStr};
+
+// Chain names of the networks a plan can be bootstrapped on
+const NETWORK_CHAIN_NAMES: [(&str, &str); 2] =
+    [("local", "casper-net-1"), ("testnet", "casper-test")];
+
+const STEP_DONE: &str = "done";
+const STEP_SKIPPED: &str = "skipped";
+
+/// Contracts to deploy, all of them by default.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContractsPlan {
+    pub token: bool,
+    pub vesting: bool,
+    pub swap: bool,
+}
+
+impl Default for ContractsPlan {
+    fn default() -> Self {
+        Self {
+            token: true,
+            vesting: true,
+            swap: true,
+        }
+    }
+}
+
+/// Accounts to top up with CSPR from the Installer.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FundPlan {
+    #[serde(default)]
+    pub all_vesting: bool,
+    #[serde(default)]
+    pub targets: Vec<String>,
+    pub top_up_to: String,
+}
+
+/// Tokens an account should hold, topped up from `from`, the Installer by default.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AllocationPlan {
+    pub to: String,
+    pub amount: String,
+    #[serde(default)]
+    pub from: Option<String>,
+}
+
+/// COWL and CSPR the swap contract should hold, deposited by the Installer.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SwapLiquidityPlan {
+    pub cowl: Option<String>,
+    pub cspr: Option<String>,
+}
+
+/// Swap window, opened now when no start time is given.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SwapWindowPlan {
+    #[serde(default)]
+    pub start_time: Option<u64>,
+    pub duration: u64,
+}
+
+/// Declarative description of a network setup, applied in order by `bootstrap`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BootstrapPlan {
+    pub contracts: ContractsPlan,
+    pub fund: Option<FundPlan>,
+    pub allocations: Vec<AllocationPlan>,
+    pub swap_liquidity: Option<SwapLiquidityPlan>,
+    pub swap_window: Option<SwapWindowPlan>,
+}
+
+pub fn load_plan(path: &str) -> Result<BootstrapPlan, String> {
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("Failed to read {path}: {err}"))?;
+    serde_json::from_str(&content).map_err(|err| format!("Invalid plan {path}: {err}"))
+}
+
+fn parse_motes(amount: &str) -> Result<BigUint, String> {
+    BigUint::from_str(amount).map_err(|_| format!("Invalid amount {amount}"))
+}
+
+/// Amount missing from `balance` to reach `target`, none if already reached.
+fn deficit(balance: &str, target: &str) -> Result<Option<BigUint>, String> {
+    let balance = BigUint::from_str(balance).map_err(|_| format!("Invalid balance {balance}"))?;
+    let target = parse_motes(target)?;
+    Ok((balance < target).then(|| target - balance))
+}
+
+async fn deploy_contract(
+    steps: &mut IndexMap<String, String>,
+    name: &str,
+    deployed: bool,
+    deploy: impl Future<Output = Result<(), Error>>,
+) -> Result<(), String> {
+    let step = format!("deploy {name} contract");
+    if deployed {
+        log::info!("The {name} contract is already deployed");
+        steps.insert(step, STEP_SKIPPED.to_string());
+        return Ok(());
+    }
+    deploy
+        .await
+        .map_err(|err| format!("Failed to deploy the {name} contract: {err}"))?;
+    steps.insert(step, STEP_DONE.to_string());
+    Ok(())
+}
+
+async fn deploy_contracts(
+    plan: &ContractsPlan,
+    steps: &mut IndexMap<String, String>,
+) -> Result<(), String> {
+    // The vesting and swap contracts are installed against the token contract
+    if plan.token {
+        let deployed = get_contract_cep18_hash_keys().await.is_some();
+        deploy_contract(steps, "token", deployed, deploy_cep18_token()).await?;
+    }
+    if plan.vesting {
+        let deployed = get_contract_vesting_hash_keys().await.is_some();
+        deploy_contract(steps, "vesting", deployed, deploy_vesting_contract()).await?;
+    }
+    if plan.swap {
+        let deployed = get_contract_swap_hash_keys().await.is_some();
+        deploy_contract(steps, "swap", deployed, deploy_swap_contract()).await?;
+    }
+    Ok(())
+}
+
+async fn fund_accounts(
+    plan: &FundPlan,
+    steps: &mut IndexMap<String, String>,
+) -> Result<(), String> {
+    let mut targets = if plan.all_vesting {
+        vesting_fund_targets().await
+    } else {
+        vec![]
+    };
+    for target in &plan.targets {
+        targets.push(parse_fund_target(target).await?);
+    }
+
+    let funded = print_fund_targets(
+        targets,
+        None,
+        Some(plan.top_up_to.clone()),
+        generate_transfer_id(),
+    )
+    .await;
+    let status = if funded == 0 { STEP_SKIPPED } else { STEP_DONE };
+    steps.insert("fund accounts".to_string(), status.to_string());
+    Ok(())
+}
+
+async fn allocate_tokens(
+    plan: &AllocationPlan,
+    steps: &mut IndexMap<String, String>,
+) -> Result<(), String> {
+    let target = parse_fund_target(&plan.to).await?;
+    let from = match &plan.from {
+        Some(from) => parse_key_ref(from)?.to_public_key().await?,
+        None => {
+            get_key_pair_from_vesting(INSTALLER)
+                .await
+                .ok_or_else(|| "No key configured for the Installer".to_string())?
+                .public_key
+        }
+    };
+
+    let step = format!("allocate tokens to {}", target.label);
+    let balance = query_balance(None, Some(target.key.clone()), None)
+        .await
+        .map_err(|err| format!("Could not read the balance of {}: {err}", target.label))?;
+    let Some(amount) = deficit(&balance, &plan.amount)? else {
+        log::info!("{} already holds {} tokens", target.label, balance);
+        steps.insert(step, STEP_SKIPPED.to_string());
+        return Ok(());
+    };
+
+    transfer(from, target.key, amount.to_string())
+        .await
+        .ok_or_else(|| format!("Failed to allocate tokens to {}", target.label))?;
+    steps.insert(step, STEP_DONE.to_string());
+    Ok(())
+}
+
+async fn add_swap_liquidity(
+    plan: &SwapLiquidityPlan,
+    steps: &mut IndexMap<String, String>,
+) -> Result<(), String> {
+    let (_, cowl_swap_contract_package_hash) =
+        get_contract_swap_hash_keys().await.ok_or_else(|| {
+            "Swap contract package does not exist in installer named keys".to_string()
+        })?;
+    let swap_key = Key::from_formatted_str(&cowl_swap_contract_package_hash)
+        .map_err(|_| format!("Invalid swap package {cowl_swap_contract_package_hash}"))?;
+
+    if let Some(cowl) = &plan.cowl {
+        let balance = query_balance(None, Some(swap_key.clone()), None)
+            .await
+            .map_err(|err| format!("Could not read the swap COWL balance: {err}"))?;
+        let status = match deficit(&balance, cowl)? {
+            Some(amount) => {
+                let key_pair = get_key_pair_from_vesting(INSTALLER)
+                    .await
+                    .ok_or_else(|| "No key configured for the Installer".to_string())?;
+                deposit_cowl(key_pair.public_key, amount.to_string()).await;
+                STEP_DONE
+            }
+            None => STEP_SKIPPED,
+        };
+        steps.insert("deposit swap COWL".to_string(), status.to_string());
+    }
+
+    if let Some(cspr) = &plan.cspr {
+        let balance_motes = query_cspr_balance(&swap_key, None)
+            .await
+            .map_err(|err| format!("Could not read the swap CSPR balance: {err}"))?;
+        let status = match deficit(&balance_motes, cspr)? {
+            Some(amount) => {
+                deposit_cspr(amount.to_string()).await;
+                STEP_DONE
+            }
+            None => STEP_SKIPPED,
+        };
+        steps.insert("deposit swap CSPR".to_string(), status.to_string());
+    }
+    Ok(())
+}
+
+async fn open_swap_window(
+    plan: &SwapWindowPlan,
+    steps: &mut IndexMap<String, String>,
+) -> Result<(), String> {
+    let now = Utc::now().timestamp() as u64;
+    let (start_time, end_time) = get_swap_times().await.ok_or_else(|| {
+        "Swap contract package does not exist in installer named keys".to_string()
+    })?;
+
+    // Without a start time, any open window of the planned duration is kept
+    let up_to_date = match plan.start_time {
+        Some(planned_start_time) => {
+            start_time == planned_start_time && end_time == planned_start_time + plan.duration
+        }
+        None => {
+            end_time.saturating_sub(start_time) == plan.duration
+                && (start_time..end_time).contains(&now)
+        }
+    };
+
+    let step = "update swap window".to_string();
+    if up_to_date {
+        log::info!("The swap window is already open from {start_time} to {end_time}");
+        steps.insert(step, STEP_SKIPPED.to_string());
+        return Ok(());
+    }
+
+    let start_time = plan.start_time.unwrap_or(now);
+    update_times(start_time.to_string(), plan.duration.to_string()).await;
+    steps.insert(step, STEP_DONE.to_string());
+    Ok(())
+}
+
+async fn contract_hashes() -> IndexMap<String, Value> {
+    let mut contracts = IndexMap::new();
+    for (name, hash_keys) in [
+        ("token", get_contract_cep18_hash_keys().await),
+        ("vesting", get_contract_vesting_hash_keys().await),
+        ("swap", get_contract_swap_hash_keys().await),
+    ] {
+        if let Some((contract_hash, contract_package_hash)) = hash_keys {
+            contracts.insert(
+                name.to_string(),
+                json!({
+                    "contract_hash": contract_hash,
+                    "contract_package_hash": contract_package_hash,
+                }),
+            );
+        }
+    }
+    contracts
+}
+
+/// Applies a plan step by step, skipping the steps already reflected on chain, and returns the
+/// status of every step with the resulting contract and deploy hashes.
+pub async fn bootstrap(network: &str, plan_path: &str) -> Result<IndexMap<String, Value>, String> {
+    let chain_name = NETWORK_CHAIN_NAMES
+        .iter()
+        .find(|(name, _)| *name == network)
+        .map(|(_, chain_name)| *chain_name)
+        .ok_or_else(|| format!("Unknown network {network}"))?;
+    if CHAIN_NAME.as_str() != chain_name {
+        return Err(format!(
+            "Network {network} expects chain {chain_name}, CHAIN_NAME is {}",
+            *CHAIN_NAME
+        ));
+    }
+
+    let plan = load_plan(plan_path)?;
+
+    if !prompt_yes_no(&format!(
+        "Please confirm bootstrap of {network} ({chain_name}) from {plan_path}?"
+    )) {
+        return Err("Bootstrap aborted.".to_string());
+    }
+    // The plan is confirmed as a whole, its deploys are not confirmed one by one
+    let assume_yes_guard = assume_yes();
+
+    let journal_length = load_journal()
+        .map(|entries| entries.len())
+        .unwrap_or_default();
+    let mut steps = IndexMap::new();
+
+    deploy_contracts(&plan.contracts, &mut steps).await?;
+    if let Some(fund) = &plan.fund {
+        fund_accounts(fund, &mut steps).await?;
+    }
+    for allocation in &plan.allocations {
+        allocate_tokens(allocation, &mut steps).await?;
+    }
+    if let Some(swap_liquidity) = &plan.swap_liquidity {
+        add_swap_liquidity(swap_liquidity, &mut steps).await?;
+    }
+    if let Some(swap_window) = &plan.swap_window {
+        open_swap_window(swap_window, &mut steps).await?;
+    }
+
+    drop(assume_yes_guard);
+
+    let deploys: Vec<Value> = load_journal()
+        .unwrap_or_default()
+        .into_iter()
+        .skip(journal_length)
+        .map(|entry| {
+            json!({
+                "deploy_hash": entry.deploy_hash,
+                "action": entry
+                    .entry_point
+                    .or(entry.session_wasm)
+                    .or(entry.recipient)
+                    .unwrap_or_default(),
+                "status": entry.status,
+            })
+        })
+        .collect();
+
+    let mut summary = IndexMap::new();
+    summary.insert("network".to_string(), json!(network));
+    summary.insert("chain_name".to_string(), json!(chain_name));
+    summary.insert("steps".to_string(), json!(steps));
+    summary.insert("contracts".to_string(), json!(contract_hashes().await));
+    summary.insert("deploys".to_string(), json!(deploys));
+    Ok(summary)
+}
+
+pub async fn print_bootstrap(network: String, plan_path: String) {
+    match bootstrap(&network, &plan_path).await {
+        Ok(summary) => {
+            let json_output = serde_json::to_string_pretty(&summary).unwrap();
+            log::info!("\n{}", json_output);
+        }
+        Err(err) => {
+            log::error!("{err}");
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    cli::Cli,
+    utils::{contacts::load_contacts, exit, key_ref::account_names},
+};
+use clap::CommandFactory;
+use clap_complete::{engine::CompletionCandidate, CompleteEnv, Shell};
+use clap_mangen::{generate_to, Man};
+use cowl_vesting::enums::VestingType;
+use std::{env, fs, io};
+use strum::IntoEnumIterator;
+
+pub const FLAG_VESTING_TYPE: &str = "vesting-type";
+// Flags taking a key reference, completed with account names and contact aliases
+pub const FLAGS_KEY_REF: [&str; 2] = ["key", "from"];
+// Environment variable through which the shell requests completions from the CLI
+const COMPLETE_ENV_VAR: &str = "COMPLETE";
+
+/// Account names and contact aliases, completing key references.
+pub fn key_ref_names() -> Vec<String> {
+    let mut names = account_names();
+    if let Ok(contacts) = load_contacts() {
+        names.extend(contacts.into_keys());
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Completes the `--vesting-type` flag with the vesting types.
+pub fn vesting_type_candidates() -> Vec<CompletionCandidate> {
+    VestingType::iter()
+        .map(|vesting_type| CompletionCandidate::new(vesting_type.to_string()))
+        .collect()
+}
+
+/// Completes the `--key` and `--from` flags with the accounts and the contacts read at the time
+/// of completion.
+pub fn key_ref_candidates() -> Vec<CompletionCandidate> {
+    key_ref_names()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Prints the script registering the completions of a shell. The script calls the CLI back on
+/// each completion, so that flag values stay current without regenerating it.
+pub async fn print_completions(shell: Shell) {
+    env::set_var(COMPLETE_ENV_VAR, shell.to_string());
+    let result = CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_ENV_VAR)
+        .try_complete(env::args_os().take(1), None);
+    if let Err(err) = result {
+        log::error!("Failed to write the completion registration of {shell}: {err}");
+        exit(1)
+    }
+}
+
+/// Prints the man page of the CLI, or writes a page per command to a directory.
+pub async fn print_man(maybe_out_dir: Option<String>) {
+    let command = Cli::command();
+    let Some(out_dir) = maybe_out_dir else {
+        if let Err(err) = Man::new(command).render(&mut io::stdout()) {
+            log::error!("Failed to render the man page: {err}");
+            exit(1)
+        }
+        return;
+    };
+
+    let result = fs::create_dir_all(&out_dir).and_then(|_| generate_to(command, &out_dir));
+    match result {
+        Ok(()) => log::info!("Man pages written to {out_dir}"),
+        Err(err) => {
+            log::error!("Failed to write the man pages to {out_dir}: {err}");
+            exit(1)
+        }
+    }
+}
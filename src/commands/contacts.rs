@@ -0,0 +1,48 @@
+use crate::utils::{
+    constants::CONTACTS_FILE_PATH,
+    contacts::{add_contact, load_contacts, remove_contact},
+    exit,
+    key_ref::parse_key,
+};
+use indexmap::IndexMap;
+
+pub async fn print_add_contact(alias: String, key: String) {
+    if let Err(err) = add_contact(&alias, &key) {
+        log::error!("{err}");
+        exit(1)
+    }
+    log::info!("Contact {alias} added for {key}");
+}
+
+pub async fn print_remove_contact(alias: String) {
+    match remove_contact(&alias) {
+        Ok(key) => log::info!("Contact {alias} removed for {key}"),
+        Err(err) => {
+            log::error!("{err}");
+            exit(1)
+        }
+    }
+}
+
+pub async fn print_contacts() {
+    let contacts = match load_contacts() {
+        Ok(contacts) => contacts,
+        Err(err) => {
+            log::error!("Failed to read contacts {}: {}", *CONTACTS_FILE_PATH, err);
+            exit(1)
+        }
+    };
+
+    let mut contact_info_map: IndexMap<String, IndexMap<String, String>> = IndexMap::new();
+    for (alias, key) in contacts {
+        let mut key_map = IndexMap::new();
+        if let Ok(parsed_key) = parse_key(&key) {
+            key_map.insert("key".to_string(), parsed_key.to_formatted_string());
+        }
+        key_map.insert("contact".to_string(), key);
+        contact_info_map.insert(alias, key_map);
+    }
+
+    let json_output = serde_json::to_string_pretty(&contact_info_map).unwrap();
+    log::info!("\n{}", json_output);
+}
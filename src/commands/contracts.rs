@@ -0,0 +1,256 @@
+use crate::utils::{
+    constants::{DEFAULT_CEP_18_TOKEN_NAME, DEFAULT_SWAP_NAME, DEFAULT_VESTING_NAME},
+    exit, get_contract_cep18_hash_keys, get_contract_swap_hash_keys,
+    get_contract_vesting_hash_keys,
+    journal::{load_journal, JournalEntry},
+    query_stored_value,
+    receipt::{get_deploy_json, DEPLOY_STATUS_FAILURE},
+};
+use casper_rust_wasm_sdk::types::key::Key;
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+
+pub const CONTRACT_TOKEN: &str = "token";
+pub const CONTRACT_VESTING: &str = "vesting";
+pub const CONTRACT_SWAP: &str = "swap";
+
+const VERSION_ENABLED: &str = "enabled";
+const VERSION_DISABLED: &str = "disabled";
+
+pub fn contract_wasm_name(contract: &str) -> &'static str {
+    match contract {
+        CONTRACT_TOKEN => DEFAULT_CEP_18_TOKEN_NAME,
+        CONTRACT_VESTING => DEFAULT_VESTING_NAME,
+        _ => DEFAULT_SWAP_NAME,
+    }
+}
+
+/// Retrieves the contract and package hashes of the token, vesting or swap contract.
+pub async fn get_contract_hash_keys_of(contract: &str) -> Option<(String, String)> {
+    match contract {
+        CONTRACT_TOKEN => get_contract_cep18_hash_keys().await,
+        CONTRACT_VESTING => get_contract_vesting_hash_keys().await,
+        _ => get_contract_swap_hash_keys().await,
+    }
+}
+
+fn version_id(version: &Value) -> String {
+    format!(
+        "{}.{}",
+        version["protocol_version_major"], version["contract_version"]
+    )
+}
+
+fn entry_point_access(entry_point: &Value) -> Value {
+    match entry_point["access"].as_str() {
+        Some(access) => json!(access),
+        None => entry_point["access"].clone(),
+    }
+}
+
+// Successful installs and upgrades of a wasm recorded in the local journal
+fn journal_installs(wasm_name: &str) -> Vec<JournalEntry> {
+    let wasm_file = format!("{wasm_name}.wasm");
+    load_journal()
+        .unwrap_or_else(|err| {
+            log::warn!("Failed to read the deploy journal: {err}");
+            vec![]
+        })
+        .into_iter()
+        .filter(|entry| {
+            entry
+                .session_wasm
+                .as_ref()
+                .is_some_and(|session_wasm| session_wasm.ends_with(&wasm_file))
+                && entry.status != DEPLOY_STATUS_FAILURE
+        })
+        .collect()
+}
+
+/// Finds the journal entry of the deploy whose execution wrote a contract hash.
+async fn find_install<'a>(
+    installs: &'a [JournalEntry],
+    contract_hash: &str,
+) -> Option<&'a JournalEntry> {
+    let contract_hash = contract_hash
+        .rsplit('-')
+        .next()
+        .unwrap_or(contract_hash)
+        .to_lowercase();
+    for entry in installs {
+        let Ok(deploy) = get_deploy_json(&entry.deploy_hash).await else {
+            continue;
+        };
+        if deploy["execution_results"]
+            .to_string()
+            .contains(&contract_hash)
+        {
+            return Some(entry);
+        }
+    }
+    None
+}
+
+async fn version_info(
+    version: &Value,
+    disabled: bool,
+    live: bool,
+    installs: &[JournalEntry],
+) -> IndexMap<String, Value> {
+    let contract_hash = version["contract_hash"].as_str().unwrap_or_default();
+    let mut version_map = IndexMap::new();
+    version_map.insert("version".to_string(), json!(version_id(version)));
+    version_map.insert("contract_hash".to_string(), json!(contract_hash));
+    version_map.insert(
+        "status".to_string(),
+        json!(if disabled {
+            VERSION_DISABLED
+        } else {
+            VERSION_ENABLED
+        }),
+    );
+    version_map.insert("live".to_string(), json!(live));
+
+    let contract = match Key::from_formatted_str(&contract_hash.replace("contract-", "hash-")) {
+        Ok(contract_key) => query_stored_value(contract_key, None)
+            .await
+            .map(|stored_value| stored_value["Contract"].clone())
+            .filter(|contract| !contract.is_null()),
+        Err(_) => None,
+    };
+    match contract {
+        Some(contract) => {
+            version_map.insert(
+                "protocol_version".to_string(),
+                contract["protocol_version"].clone(),
+            );
+            let entry_points: IndexMap<String, Value> = contract["entry_points"]
+                .as_array()
+                .map(|entry_points| {
+                    entry_points
+                        .iter()
+                        .filter_map(|entry_point| {
+                            let name = entry_point["name"].as_str()?;
+                            Some((name.to_string(), entry_point_access(entry_point)))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            for (name, access) in &entry_points {
+                if access.get("Groups").is_some() {
+                    log::warn!(
+                        "Entry point {name} of version {} is restricted to access groups {}",
+                        version_id(version),
+                        access["Groups"]
+                    );
+                }
+            }
+            version_map.insert("entry_points".to_string(), json!(entry_points));
+            let named_keys: IndexMap<String, Value> = contract["named_keys"]
+                .as_array()
+                .map(|named_keys| {
+                    named_keys
+                        .iter()
+                        .filter_map(|named_key| {
+                            let name = named_key["name"].as_str()?;
+                            Some((name.to_string(), named_key["key"].clone()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            version_map.insert("named_keys".to_string(), json!(named_keys));
+        }
+        None => log::warn!("Failed to retrieve contract {contract_hash}"),
+    }
+
+    match find_install(installs, contract_hash).await {
+        Some(entry) => {
+            version_map.insert("deploy_hash".to_string(), json!(entry.deploy_hash));
+            version_map.insert("deploy_timestamp".to_string(), json!(entry.timestamp));
+            version_map.insert("wasm_hash".to_string(), json!(entry.wasm_hash));
+        }
+        None => {
+            version_map.insert("deploy_hash".to_string(), Value::Null);
+        }
+    }
+    version_map
+}
+
+/// Lists every version of a contract package, with its status, entry points, named keys and
+/// the deploy that created it when found in the local journal.
+pub async fn print_contract_versions(contract: &str) {
+    let Some((_, contract_package_hash)) = get_contract_hash_keys_of(contract).await else {
+        log::error!("The {contract} contract does not exist in installer named keys");
+        exit(1)
+    };
+
+    let contract_package = match Key::from_formatted_str(&contract_package_hash) {
+        Ok(key) => query_stored_value(key, None)
+            .await
+            .map(|stored_value| stored_value["ContractPackage"].clone())
+            .filter(|contract_package| !contract_package.is_null()),
+        Err(_) => None,
+    };
+    let Some(contract_package) = contract_package else {
+        log::error!("Failed to retrieve contract package {contract_package_hash}");
+        exit(1)
+    };
+
+    let versions = contract_package["versions"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let disabled_versions: Vec<String> = contract_package["disabled_versions"]
+        .as_array()
+        .map(|disabled_versions| disabled_versions.iter().map(version_id).collect())
+        .unwrap_or_default();
+    let live_version = versions
+        .iter()
+        .rev()
+        .map(version_id)
+        .find(|version| !disabled_versions.contains(version));
+
+    let groups = contract_package["groups"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    if !groups.is_empty() {
+        log::warn!(
+            "Contract package {contract_package_hash} has {} access groups",
+            groups.len()
+        );
+    }
+
+    let installs = journal_installs(contract_wasm_name(contract));
+    let mut versions_info = vec![];
+    for version in &versions {
+        let id = version_id(version);
+        let disabled = disabled_versions.contains(&id);
+        if disabled {
+            log::warn!("Version {id} of the {contract} contract is disabled");
+        }
+        let live = live_version.as_ref() == Some(&id);
+        versions_info.push(version_info(version, disabled, live, &installs).await);
+    }
+
+    let mut contract_info_map = IndexMap::new();
+    contract_info_map.insert("contract".to_string(), json!(contract));
+    contract_info_map.insert(
+        "contract_package_hash".to_string(),
+        json!(contract_package_hash),
+    );
+    contract_info_map.insert(
+        "lock_status".to_string(),
+        contract_package["lock_status"].clone(),
+    );
+    contract_info_map.insert(
+        "upgrades".to_string(),
+        json!(versions.len().saturating_sub(1)),
+    );
+    contract_info_map.insert("live_version".to_string(), json!(live_version));
+    contract_info_map.insert("access_groups".to_string(), json!(groups));
+    contract_info_map.insert("versions".to_string(), json!(versions_info));
+
+    let json_output = serde_json::to_string_pretty(&contract_info_map).unwrap();
+    log::info!("\n{}", json_output);
+}
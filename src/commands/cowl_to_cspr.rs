@@ -1,18 +1,24 @@
 use crate::{
-    commands::balance::print_balance,
+    commands::{
+        balance::print_balance,
+        preflight::{abort_on_failed_checks, check_cspr_balance, check_token_balance},
+        verification::{BalanceCheck, BalanceSnapshot, ExpectedDelta},
+    },
     utils::{
+        confirmation::confirm_deploy,
         constants::{
             CHAIN_NAME, COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL,
-            COWL_COWL_TO_CSPR_CALL_PAYMENT_AMOUNT, DEFAULT_SWAP_COWL_TO_CSPR_SESSION,
-            EVENTS_ADDRESS, TTL, WASM_PATH,
+            COWL_COWL_TO_CSPR_CALL_PAYMENT_AMOUNT, DEFAULT_SWAP_COWL_TO_CSPR_SESSION, TTL,
         },
-        format_with_thousands_separator, get_contract_cep18_hash_keys, get_contract_swap_hash_keys,
+        exit, format_with_thousands_separator, get_contract_cep18_hash_keys,
+        get_contract_swap_hash_keys,
+        journal::{record_submitted, JournalEntry},
         keys::retrieve_private_key,
         prompt_yes_no, read_wasm_file, sdk,
+        wasm::{check_wasm_hash, wasm_file_path},
     },
 };
 use casper_rust_wasm_sdk::{
-    deploy_watcher::watcher::EventParseResult,
     helpers::motes_to_cspr,
     types::{
         deploy_hash::DeployHash,
@@ -24,7 +30,6 @@ use casper_rust_wasm_sdk::{
 use cowl_swap::constants::{ARG_COWL_CEP18_CONTRACT_PACKAGE, ARG_COWL_SWAP_CONTRACT_PACKAGE};
 use cowl_vesting::constants::ARG_AMOUNT;
 use serde_json::json;
-use std::process;
 
 pub async fn cowl_to_cspr(from: PublicKey, amount: String) {
     let (cowl_cep18_token_contract_hash, cowl_cep18_token_package_hash) =
@@ -35,7 +40,7 @@ pub async fn cowl_to_cspr(from: PublicKey, amount: String) {
 
     if cowl_cep18_token_contract_hash.is_empty() {
         log::error!("Token contract does not exist in installer named keys");
-        process::exit(1)
+        exit(1)
     }
 
     let (_, cowl_swap_contract_package_hash) = match get_contract_swap_hash_keys().await {
@@ -45,9 +50,14 @@ pub async fn cowl_to_cspr(from: PublicKey, amount: String) {
 
     if cowl_swap_contract_package_hash.is_empty() {
         log::error!("Swap contract package does not exist in installer named keys");
-        process::exit(1)
+        exit(1)
     }
 
+    abort_on_failed_checks(vec![
+        check_token_balance(&Key::from_account(from.to_account_hash()), &amount).await,
+        check_cspr_balance(&from, &COWL_COWL_TO_CSPR_CALL_PAYMENT_AMOUNT, None).await,
+    ]);
+
     // Retrieve the private key
     let secret_key = retrieve_private_key(&from).await;
 
@@ -64,6 +74,26 @@ pub async fn cowl_to_cspr(from: PublicKey, amount: String) {
         return;
     }
 
+    let sender = Key::from_account(from.to_account_hash());
+    let swapped = ExpectedDelta::amount(&amount);
+    let mut checks = vec![
+        BalanceCheck::token("sender", &sender, ExpectedDelta::Exact(-&swapped)),
+        BalanceCheck::cspr("sender", &sender, ExpectedDelta::Any),
+    ];
+    if let Ok(swap_key) = Key::from_formatted_str(&cowl_swap_contract_package_hash) {
+        checks.push(BalanceCheck::token(
+            "swap contract",
+            &swap_key,
+            ExpectedDelta::Exact(swapped),
+        ));
+        checks.push(BalanceCheck::cspr(
+            "swap contract",
+            &swap_key,
+            ExpectedDelta::Decrease,
+        ));
+    }
+    let snapshot = BalanceSnapshot::take(checks).await;
+
     let deploy_params = DeployStrParams::new(
         &CHAIN_NAME,
         &from.to_string(),
@@ -73,7 +103,7 @@ pub async fn cowl_to_cspr(from: PublicKey, amount: String) {
     );
 
     let session_params = SessionStrParams::default();
-    let path = &format!("{}{}.wasm", WASM_PATH, DEFAULT_SWAP_COWL_TO_CSPR_SESSION);
+    let path = &wasm_file_path(DEFAULT_SWAP_COWL_TO_CSPR_SESSION);
     let module_bytes = match read_wasm_file(path) {
         Ok(module_bytes) => module_bytes,
         Err(err) => {
@@ -81,6 +111,7 @@ pub async fn cowl_to_cspr(from: PublicKey, amount: String) {
             return;
         }
     };
+    let wasm_hash = check_wasm_hash(DEFAULT_SWAP_COWL_TO_CSPR_SESSION, &module_bytes);
     session_params.set_session_bytes(module_bytes.into());
 
     let args_deposit_cspr_json = json!([
@@ -121,7 +152,7 @@ pub async fn cowl_to_cspr(from: PublicKey, amount: String) {
 
     if api_version.is_empty() {
         log::error!("Failed to retrieve contract API version");
-        process::exit(1)
+        exit(1)
     }
 
     let deploy_hash = DeployHash::from(
@@ -135,55 +166,40 @@ pub async fn cowl_to_cspr(from: PublicKey, amount: String) {
 
     if deploy_hash_as_string.is_empty() {
         log::error!("Failed to retrieve deploy hash");
-        process::exit(1)
+        exit(1)
     }
 
+    record_submitted(JournalEntry {
+        contract_package: Some(cowl_swap_contract_package_hash.clone()),
+        session_wasm: Some(path.to_string()),
+        wasm_hash: Some(wasm_hash),
+        ..JournalEntry::new(
+            &deploy_hash_as_string,
+            &from.to_string(),
+            &COWL_COWL_TO_CSPR_CALL_PAYMENT_AMOUNT,
+        )
+    });
+
     log::info!(
         "Wait deploy_hash for deposit {} {}",
         "CSPR",
         deploy_hash_as_string,
     );
 
-    let event_parse_result: EventParseResult = sdk()
-        .wait_deploy(&EVENTS_ADDRESS, &deploy_hash_as_string, None)
-        .await
-        .unwrap();
-    let motes = event_parse_result
-        .clone()
-        .body
-        .unwrap()
-        .deploy_processed
-        .unwrap()
-        .execution_result
-        .success
-        .unwrap_or_else(|| {
-            log::error!("Could not retrieved cost for deploy hash {deploy_hash_as_string}");
-            log::error!("{:?}", &event_parse_result);
-            process::exit(1)
-        })
-        .cost;
-
-    let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap());
-
-    let finalized_approvals = true;
-    let get_deploy = sdk()
-        .get_deploy(deploy_hash, Some(finalized_approvals), None, None)
-        .await;
-    let get_deploy = get_deploy.unwrap();
-    let result = DeployHash::from(get_deploy.result.deploy.hash).to_string();
-    log::info!("Processed deploy hash {result}");
-    log::info!("Cost {cost} CSPR ({motes} motes)");
+    confirm_deploy(&deploy_hash_as_string).await;
+
+    snapshot.verify().await;
 
     let key = Some(Key::from_account(from.to_account_hash()));
     log::info!("Balance for {}", from.to_string());
-    print_balance(None, key.clone(), None).await;
+    print_balance(None, key.clone(), None, None).await;
 
     let key = Key::from_formatted_str(&cowl_swap_contract_package_hash).ok();
     log::info!(
         "Balance for Swap Contract Package {}",
         cowl_swap_contract_package_hash
     );
-    print_balance(None, None, key).await;
+    print_balance(None, None, key, None).await;
 }
 
 pub async fn print_cowl_to_cspr(from: PublicKey, amount: String) {
@@ -1,17 +1,23 @@
 use crate::{
-    commands::balance::print_balance,
+    commands::{
+        balance::print_balance,
+        preflight::{abort_on_failed_checks, check_cspr_balance},
+        verification::{BalanceCheck, BalanceSnapshot, ExpectedDelta},
+    },
     utils::{
+        confirmation::confirm_deploy,
         constants::{
             CHAIN_NAME, COWL_CSPR_TO_COWL_CALL_PAYMENT_AMOUNT, DEFAULT_SWAP_CSPR_TO_COWL_SESSION,
-            EVENTS_ADDRESS, TTL, WASM_PATH,
+            TTL,
         },
-        format_with_thousands_separator, get_contract_swap_hash_keys,
+        exit, format_with_thousands_separator, get_contract_swap_hash_keys,
+        journal::{record_submitted, JournalEntry},
         keys::retrieve_private_key,
         prompt_yes_no, read_wasm_file, sdk,
+        wasm::{check_wasm_hash, wasm_file_path},
     },
 };
 use casper_rust_wasm_sdk::{
-    deploy_watcher::watcher::EventParseResult,
     helpers::motes_to_cspr,
     types::{
         deploy_hash::DeployHash,
@@ -23,7 +29,6 @@ use casper_rust_wasm_sdk::{
 use cowl_swap::constants::ARG_COWL_SWAP_CONTRACT_PACKAGE;
 use cowl_vesting::constants::ARG_AMOUNT;
 use serde_json::json;
-use std::process;
 
 pub async fn cspr_to_cowl(from: PublicKey, amount: String) {
     let (_, cowl_swap_contract_package_hash) = match get_contract_swap_hash_keys().await {
@@ -33,9 +38,13 @@ pub async fn cspr_to_cowl(from: PublicKey, amount: String) {
 
     if cowl_swap_contract_package_hash.is_empty() {
         log::error!("Swap contract package does not exist in installer named keys");
-        process::exit(1)
+        exit(1)
     }
 
+    abort_on_failed_checks(vec![
+        check_cspr_balance(&from, &COWL_CSPR_TO_COWL_CALL_PAYMENT_AMOUNT, Some(&amount)).await,
+    ]);
+
     // Retrieve the private key
     let secret_key = retrieve_private_key(&from).await;
 
@@ -52,6 +61,30 @@ pub async fn cspr_to_cowl(from: PublicKey, amount: String) {
         return;
     }
 
+    let sender = Key::from_account(from.to_account_hash());
+    let swapped = ExpectedDelta::amount(&amount);
+    let mut checks = vec![
+        BalanceCheck::cspr(
+            "sender",
+            &sender,
+            ExpectedDelta::signer(&swapped, &COWL_CSPR_TO_COWL_CALL_PAYMENT_AMOUNT),
+        ),
+        BalanceCheck::token("sender", &sender, ExpectedDelta::Increase),
+    ];
+    if let Ok(swap_key) = Key::from_formatted_str(&cowl_swap_contract_package_hash) {
+        checks.push(BalanceCheck::cspr(
+            "swap contract",
+            &swap_key,
+            ExpectedDelta::Exact(swapped),
+        ));
+        checks.push(BalanceCheck::token(
+            "swap contract",
+            &swap_key,
+            ExpectedDelta::Decrease,
+        ));
+    }
+    let snapshot = BalanceSnapshot::take(checks).await;
+
     let deploy_params = DeployStrParams::new(
         &CHAIN_NAME,
         &from.to_string(),
@@ -61,7 +94,7 @@ pub async fn cspr_to_cowl(from: PublicKey, amount: String) {
     );
 
     let session_params = SessionStrParams::default();
-    let path = &format!("{}{}.wasm", WASM_PATH, DEFAULT_SWAP_CSPR_TO_COWL_SESSION);
+    let path = &wasm_file_path(DEFAULT_SWAP_CSPR_TO_COWL_SESSION);
     let module_bytes = match read_wasm_file(path) {
         Ok(module_bytes) => module_bytes,
         Err(err) => {
@@ -69,6 +102,7 @@ pub async fn cspr_to_cowl(from: PublicKey, amount: String) {
             return;
         }
     };
+    let wasm_hash = check_wasm_hash(DEFAULT_SWAP_CSPR_TO_COWL_SESSION, &module_bytes);
     session_params.set_session_bytes(module_bytes.into());
 
     let args_deposit_cspr_json = json!([
@@ -104,7 +138,7 @@ pub async fn cspr_to_cowl(from: PublicKey, amount: String) {
 
     if api_version.is_empty() {
         log::error!("Failed to retrieve contract API version");
-        process::exit(1)
+        exit(1)
     }
 
     let deploy_hash = DeployHash::from(
@@ -118,55 +152,40 @@ pub async fn cspr_to_cowl(from: PublicKey, amount: String) {
 
     if deploy_hash_as_string.is_empty() {
         log::error!("Failed to retrieve deploy hash");
-        process::exit(1)
+        exit(1)
     }
 
+    record_submitted(JournalEntry {
+        contract_package: Some(cowl_swap_contract_package_hash.clone()),
+        session_wasm: Some(path.to_string()),
+        wasm_hash: Some(wasm_hash),
+        ..JournalEntry::new(
+            &deploy_hash_as_string,
+            &from.to_string(),
+            &COWL_CSPR_TO_COWL_CALL_PAYMENT_AMOUNT,
+        )
+    });
+
     log::info!(
         "Wait deploy_hash for deposit {} {}",
         "CSPR",
         deploy_hash_as_string,
     );
 
-    let event_parse_result: EventParseResult = sdk()
-        .wait_deploy(&EVENTS_ADDRESS, &deploy_hash_as_string, None)
-        .await
-        .unwrap();
-    let motes = event_parse_result
-        .clone()
-        .body
-        .unwrap()
-        .deploy_processed
-        .unwrap()
-        .execution_result
-        .success
-        .unwrap_or_else(|| {
-            log::error!("Could not retrieved cost for deploy hash {deploy_hash_as_string}");
-            log::error!("{:?}", &event_parse_result);
-            process::exit(1)
-        })
-        .cost;
-
-    let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap());
-
-    let finalized_approvals = true;
-    let get_deploy = sdk()
-        .get_deploy(deploy_hash, Some(finalized_approvals), None, None)
-        .await;
-    let get_deploy = get_deploy.unwrap();
-    let result = DeployHash::from(get_deploy.result.deploy.hash).to_string();
-    log::info!("Processed deploy hash {result}");
-    log::info!("Cost {cost} CSPR ({motes} motes)");
+    confirm_deploy(&deploy_hash_as_string).await;
+
+    snapshot.verify().await;
 
     let key = Some(Key::from_account(from.to_account_hash()));
     log::info!("Balance for {}", from.to_string());
-    print_balance(None, key.clone(), None).await;
+    print_balance(None, key.clone(), None, None).await;
 
     let key = Key::from_formatted_str(&cowl_swap_contract_package_hash).ok();
     log::info!(
         "Balance for Swap Contract Package {}",
         cowl_swap_contract_package_hash
     );
-    print_balance(None, None, key).await;
+    print_balance(None, None, key, None).await;
 }
 
 pub async fn print_cspr_to_cowl(from: PublicKey, amount: String) {
@@ -1,117 +1,90 @@
-use crate::utils::{
-    config::{get_key_pair_from_vesting, CONFIG_LOCK},
-    constants::{
-        CHAIN_NAME, COWL_CEP_18_INSTALL_PAYMENT_AMOUNT, COWL_CEP_18_TOKEN_DECIMALS,
-        COWL_CEP_18_TOKEN_NAME, COWL_CEP_18_TOKEN_SYMBOL, COWL_SWAP_INSTALL_PAYMENT_AMOUNT,
-        COWL_SWAP_NAME, COWL_VESTING_INSTALL_PAYMENT_AMOUNT, COWL_VESTING_NAME,
-        COWL_VESTING_UPDATE_PAYMENT_AMOUNT, DEFAULT_CEP_18_TOKEN_DECIMALS,
-        DEFAULT_CEP_18_TOKEN_NAME, DEFAULT_SWAP_NAME, DEFAULT_VESTING_NAME, EVENTS_ADDRESS,
-        INSTALLER, TTL, WASM_PATH,
+use crate::{
+    commands::{
+        contracts::{CONTRACT_SWAP, CONTRACT_TOKEN, CONTRACT_VESTING},
+        manifest::{resolve_vesting_addresses, SwapManifest, TokenManifest, VestingManifest},
+        preflight::{preflight_upgrade, upgrade_payment_amount},
     },
-    format_with_thousands_separator, get_contract_cep18_hash_keys, get_contract_swap_hash_keys,
-    get_contract_vesting_hash_keys,
-    keys::format_base64_to_pem,
-    prompt_yes_no, read_wasm_file, sdk,
-};
-use casper_rust_wasm_sdk::{
-    deploy_watcher::watcher::EventParseResult,
-    helpers::motes_to_cspr,
-    types::{
-        deploy_hash::DeployHash,
-        deploy_params::{deploy_str_params::DeployStrParams, session_str_params::SessionStrParams},
+    utils::{
+        config::get_key_pair_from_vesting,
+        confirmation::confirm_deploy,
+        constants::{
+            ARG_ENABLE_MINT_BURN, CHAIN_NAME, COWL_CEP_18_INSTALL_PAYMENT_AMOUNT,
+            COWL_CEP_18_TOKEN_NAME, COWL_SWAP_INSTALL_PAYMENT_AMOUNT, COWL_SWAP_NAME,
+            COWL_VESTING_INSTALL_PAYMENT_AMOUNT, COWL_VESTING_NAME,
+            COWL_VESTING_UPDATE_PAYMENT_AMOUNT, DEFAULT_CEP_18_TOKEN_NAME, DEFAULT_SWAP_NAME,
+            DEFAULT_VESTING_NAME, INSTALLER, TTL,
+        },
+        exit, get_contract_cep18_hash_keys, get_contract_swap_hash_keys,
+        get_contract_vesting_hash_keys,
+        journal::{record_submitted, JournalEntry},
+        keys::format_base64_to_pem,
+        prompt_yes_no, read_wasm_file, registry, sdk,
+        wasm::{check_wasm_hash, wasm_file_path},
     },
 };
-use cowl_swap::constants::{ARG_DURATION, ARG_START_TIME};
-use cowl_vesting::{
-    constants::{ARG_COWL_CEP18_CONTRACT_PACKAGE, ARG_UPGRADE_FLAG},
-    enums::EventsMode,
+use casper_rust_wasm_sdk::types::{
+    deploy_hash::DeployHash,
+    deploy_params::{deploy_str_params::DeployStrParams, session_str_params::SessionStrParams},
 };
-use once_cell::sync::Lazy;
+use cowl_swap::constants::{ARG_DURATION, ARG_START_TIME};
+use cowl_vesting::constants::{ARG_COWL_CEP18_CONTRACT_PACKAGE, ARG_UPGRADE_FLAG};
 use serde_json::{json, Value};
-use std::{io::Error, process};
-use tokio::sync::Mutex;
+use std::io::{Error, ErrorKind};
 
 const ARG_NAME: &str = "name";
 const ARG_SYMBOL: &str = "symbol";
 const ARG_DECIMALS: &str = "decimals";
 const ARG_TOTAL_SUPPLY: &str = "total_supply";
 const ARG_EVENTS_MODE: &str = "events_mode";
-const ARG_ENABLE_MINT_BURN: &str = "enable_mint_burn";
 
-static ARGS_CEP18_JSON: Lazy<Mutex<Value>> = Lazy::new(|| {
-    Mutex::new(json!([
-        {
+fn upgrade_flag_arg() -> Value {
+    json!({
+        "name": ARG_UPGRADE_FLAG.to_string(),
+        "type": "Bool",
+        "value": true
+    })
+}
+
+fn cep18_args(token: &TokenManifest, upgrade: bool) -> Value {
+    let mut args = vec![
+        json!({
             "name": ARG_NAME,
             "type": "String",
             "value": *COWL_CEP_18_TOKEN_NAME
-        },
-        {
+        }),
+        json!({
             "name": ARG_SYMBOL,
             "type": "String",
-            "value": *COWL_CEP_18_TOKEN_SYMBOL
-        },
-        {
+            "value": token.symbol
+        }),
+        json!({
             "name": ARG_DECIMALS,
             "type": "U8",
-            "value": COWL_CEP_18_TOKEN_DECIMALS.parse::<u8>().unwrap_or(DEFAULT_CEP_18_TOKEN_DECIMALS)
-        },
-        {
+            "value": token.decimals
+        }),
+        // The token reads its total supply as a U256. A U8 zero, sent before the manifest set
+        // the supply, only worked as both serialize to a single zero byte.
+        json!({
             "name": ARG_TOTAL_SUPPLY,
-            "type": "U8",
-            "value": 0
-        },
-        {
+            "type": "U256",
+            "value": token.total_supply
+        }),
+        json!({
             "name": ARG_EVENTS_MODE,
             "type": "U8",
-            "value": EventsMode::CES as u8
-        },
-        {
+            "value": token.events_mode
+        }),
+        json!({
             "name": ARG_ENABLE_MINT_BURN,
             "type": "Bool",
-            "value": true
-        }
-    ]))
-});
-
-static ARGS_VESTING_JSON: Lazy<Mutex<Value>> = Lazy::new(|| {
-    Mutex::new(json!([
-        {
-            "name": ARG_NAME,
-            "type": "String",
-            "value": *COWL_VESTING_NAME
-        },
-        {
-            "name": ARG_EVENTS_MODE,
-            "type": "U8",
-            "value": EventsMode::CES as u8
-        },
-    ]))
-});
-
-static ARGS_SWAP_JSON: Lazy<Mutex<Value>> = Lazy::new(|| {
-    Mutex::new(json!([
-        {
-            "name": ARG_NAME,
-            "type": "String",
-            "value": *COWL_SWAP_NAME
-        },
-        {
-            "name": ARG_EVENTS_MODE,
-            "type": "U8",
-            "value": EventsMode::CES as u8
-        },
-        {
-            "name": ARG_START_TIME,
-            "type": "U64",
-            "value":0_u64
-        },
-        {
-            "name": ARG_DURATION,
-            "type": "U64",
-            "value": 1_u64
-        },
-    ]))
-});
+            "value": token.enable_mint_burn
+        }),
+    ];
+    if upgrade {
+        args.push(upgrade_flag_arg());
+    }
+    Value::Array(args)
+}
 
 pub async fn deploy_all_contracts() -> Result<(), Error> {
     deploy_cep18_token().await?;
@@ -121,14 +94,13 @@ pub async fn deploy_all_contracts() -> Result<(), Error> {
 }
 
 pub async fn deploy_cep18_token() -> Result<(), Error> {
-    let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
-
     let (contract_cep18_hash, _) = match get_contract_cep18_hash_keys().await {
         Some((hash, package_hash)) => (hash, package_hash),
         None => (String::from(""), String::from("")),
     };
 
-    if !contract_cep18_hash.is_empty() {
+    let upgrade = !contract_cep18_hash.is_empty();
+    if upgrade {
         let answer = prompt_yes_no(&format!(
             "Token contract already exists at {}, do you want to upgrade?",
             contract_cep18_hash
@@ -139,20 +111,19 @@ pub async fn deploy_cep18_token() -> Result<(), Error> {
                 "You chose to upgrade token contract {}",
                 contract_cep18_hash
             );
-            let mut args = ARGS_CEP18_JSON.lock().await;
-            if let Some(array) = args.as_array_mut() {
-                array.push(json!({
-                    "name": ARG_UPGRADE_FLAG.to_string(),
-                    "type": "Bool",
-                    "value": true
-                }));
-            }
         } else {
             log::info!("You chose not to upgrade {}", contract_cep18_hash);
             return Ok(());
         }
     }
 
+    install_cep18_token(&TokenManifest::default(), upgrade).await
+}
+
+/// Installs the token contract with the arguments of the manifest, or upgrades it.
+pub async fn install_cep18_token(token: &TokenManifest, upgrade: bool) -> Result<(), Error> {
+    let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
+
     let deploy_params = DeployStrParams::new(
         &CHAIN_NAME,
         &key_pair.public_key.to_string(),
@@ -164,7 +135,7 @@ pub async fn deploy_cep18_token() -> Result<(), Error> {
     );
 
     let session_params = SessionStrParams::default();
-    let path = &format!("{}{}.wasm", WASM_PATH, DEFAULT_CEP_18_TOKEN_NAME);
+    let path = &wasm_file_path(DEFAULT_CEP_18_TOKEN_NAME);
     let module_bytes = match read_wasm_file(path) {
         Ok(module_bytes) => module_bytes,
         Err(err) => {
@@ -172,8 +143,17 @@ pub async fn deploy_cep18_token() -> Result<(), Error> {
             return Err(err);
         }
     };
+    let wasm_hash = check_wasm_hash(DEFAULT_CEP_18_TOKEN_NAME, &module_bytes);
+    if upgrade {
+        preflight_upgrade(
+            CONTRACT_TOKEN,
+            &module_bytes,
+            upgrade_payment_amount(CONTRACT_TOKEN),
+        )
+        .await?;
+    }
     session_params.set_session_bytes(module_bytes.into());
-    session_params.set_session_args_json(&ARGS_CEP18_JSON.lock().await.to_string());
+    session_params.set_session_args_json(&cep18_args(token, upgrade).to_string());
 
     let install = sdk()
         .install(
@@ -187,7 +167,7 @@ pub async fn deploy_cep18_token() -> Result<(), Error> {
 
     if api_version.is_empty() {
         log::error!("Failed to retrieve contract API version");
-        process::exit(1)
+        exit(1)
     }
 
     let deploy_hash = DeployHash::from(
@@ -201,10 +181,20 @@ pub async fn deploy_cep18_token() -> Result<(), Error> {
 
     if deploy_hash_as_string.is_empty() {
         log::error!("Failed to retrieve deploy hash");
-        process::exit(1)
+        exit(1)
     }
 
-    if !contract_cep18_hash.is_empty() {
+    record_submitted(JournalEntry {
+        session_wasm: Some(path.to_string()),
+        wasm_hash: Some(wasm_hash),
+        ..JournalEntry::new(
+            &deploy_hash_as_string,
+            &key_pair.public_key.to_string(),
+            &COWL_CEP_18_INSTALL_PAYMENT_AMOUNT,
+        )
+    });
+
+    if upgrade {
         log::info!(
             "Wait deploy_hash for token upgrade {}",
             deploy_hash_as_string
@@ -216,42 +206,15 @@ pub async fn deploy_cep18_token() -> Result<(), Error> {
         );
     }
 
-    let event_parse_result: EventParseResult = sdk()
-        .wait_deploy(&EVENTS_ADDRESS, &deploy_hash_as_string, None)
-        .await
-        .unwrap();
-
-    let motes = event_parse_result
-        .clone()
-        .body
-        .unwrap()
-        .deploy_processed
-        .unwrap()
-        .execution_result
-        .success
-        .unwrap_or_else(|| {
-            log::error!("Could not retrieved cost for deploy hash {deploy_hash_as_string}");
-            log::error!("{:?}", &event_parse_result);
-            process::exit(1)
-        })
-        .cost;
-
-    let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap());
-
-    let finalized_approvals = true;
-    let get_deploy = sdk()
-        .get_deploy(deploy_hash, Some(finalized_approvals), None, None)
-        .await;
-    let get_deploy = get_deploy.unwrap();
-    let result = DeployHash::from(get_deploy.result.deploy.hash).to_string();
-    log::info!("Processed deploy hash {result}");
-    log::info!("Cost {cost} CSPR ({motes} motes)");
+    confirm_deploy(&deploy_hash_as_string).await;
+    // The contract hashes of the Installer changed with the install or upgrade
+    registry::clear_latest();
     let (contract_cep18_hash, contract_cep18_package_hash) =
         match get_contract_cep18_hash_keys().await {
             Some((hash, package_hash)) => (hash, package_hash),
             None => {
                 log::error!("Failed to retrieve contract CEP18 keys");
-                process::exit(1)
+                exit(1)
             }
         };
     log::info!("contract_cep18_hash {contract_cep18_hash}");
@@ -260,25 +223,13 @@ pub async fn deploy_cep18_token() -> Result<(), Error> {
 }
 
 pub async fn deploy_vesting_contract() -> Result<(), Error> {
-    let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
-
-    let (cowl_cep18_token_contract_hash, cowl_cep18_token_package_hash) =
-        match get_contract_cep18_hash_keys().await {
-            Some((hash, package_hash)) => (hash, package_hash),
-            None => (String::from(""), String::from("")),
-        };
-
-    if cowl_cep18_token_contract_hash.is_empty() {
-        log::error!("Token contract does not exist in installer named keys");
-        process::exit(1)
-    }
-
     let (contract_vesting_hash, _) = match get_contract_vesting_hash_keys().await {
         Some((hash, package_hash)) => (hash, package_hash),
         None => (String::from(""), String::from("")),
     };
 
-    if !contract_vesting_hash.is_empty() {
+    let upgrade = !contract_vesting_hash.is_empty();
+    if upgrade {
         let answer = prompt_yes_no(&format!(
             "Vesting contract already exists at {}, do you want to upgrade?",
             contract_vesting_hash
@@ -289,14 +240,6 @@ pub async fn deploy_vesting_contract() -> Result<(), Error> {
                 "You chose to upgrade vesting contract at {}",
                 contract_vesting_hash
             );
-            let mut args_vesting_json = ARGS_VESTING_JSON.lock().await;
-            if let Some(array) = args_vesting_json.as_array_mut() {
-                array.push(json!({
-                    "name": ARG_UPGRADE_FLAG.to_string(),
-                    "type": "Bool",
-                    "value": true
-                }));
-            }
         } else {
             log::info!(
                 "You chose not to upgrade vesting contract at {}",
@@ -306,6 +249,59 @@ pub async fn deploy_vesting_contract() -> Result<(), Error> {
         }
     }
 
+    install_vesting_contract(&VestingManifest::default(), upgrade).await
+}
+
+/// Installs the vesting contract with the addresses of the manifest, or upgrades it.
+pub async fn install_vesting_contract(
+    vesting: &VestingManifest,
+    upgrade: bool,
+) -> Result<(), Error> {
+    let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
+
+    let (cowl_cep18_token_contract_hash, cowl_cep18_token_package_hash) =
+        match get_contract_cep18_hash_keys().await {
+            Some((hash, package_hash)) => (hash, package_hash),
+            None => (String::from(""), String::from("")),
+        };
+
+    if cowl_cep18_token_contract_hash.is_empty() {
+        log::error!("Token contract does not exist in installer named keys");
+        exit(1)
+    }
+
+    let mut args = vec![
+        json!({
+            "name": ARG_NAME,
+            "type": "String",
+            "value": *COWL_VESTING_NAME
+        }),
+        json!({
+            "name": ARG_EVENTS_MODE,
+            "type": "U8",
+            "value": vesting.events_mode
+        }),
+    ];
+    if upgrade {
+        args.push(upgrade_flag_arg());
+    } else {
+        let addresses = resolve_vesting_addresses(vesting)
+            .await
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        for (vesting_type, address) in addresses {
+            args.push(json!({
+                "name": vesting_type,
+                "type": "Key",
+                "value": address
+            }));
+        }
+        args.push(json!({
+            "name": ARG_COWL_CEP18_CONTRACT_PACKAGE,
+            "type": "Key",
+            "value": cowl_cep18_token_package_hash
+        }));
+    }
+
     let deploy_params = DeployStrParams::new(
         &CHAIN_NAME,
         &key_pair.public_key.to_string(),
@@ -317,7 +313,7 @@ pub async fn deploy_vesting_contract() -> Result<(), Error> {
     );
 
     let session_params = SessionStrParams::default();
-    let path = &format!("{}{}.wasm", WASM_PATH, DEFAULT_VESTING_NAME);
+    let path = &wasm_file_path(DEFAULT_VESTING_NAME);
     let module_bytes = match read_wasm_file(path) {
         Ok(module_bytes) => module_bytes,
         Err(err) => {
@@ -325,55 +321,33 @@ pub async fn deploy_vesting_contract() -> Result<(), Error> {
             return Err(err);
         }
     };
+    let wasm_hash = check_wasm_hash(DEFAULT_VESTING_NAME, &module_bytes);
+    if upgrade {
+        preflight_upgrade(
+            CONTRACT_VESTING,
+            &module_bytes,
+            upgrade_payment_amount(CONTRACT_VESTING),
+        )
+        .await?;
+    }
     session_params.set_session_bytes(module_bytes.into());
+    session_params.set_session_args_json(&Value::Array(args).to_string());
 
-    {
-        let mut args_vesting_json = ARGS_VESTING_JSON.lock().await;
-        if contract_vesting_hash.is_empty() {
-            let config_lock = CONFIG_LOCK.lock().await;
-            if let Some(config) = config_lock.as_ref() {
-                for (vesting_type, (key_pair, maybe_vesting_info)) in config {
-                    if let Some(_vesting_info) = maybe_vesting_info {
-                        if let Some(array) = args_vesting_json.as_array_mut() {
-                            array.push(json!({
-                                "name": *vesting_type,
-                                "type": "Key",
-                                "value": key_pair.public_key.to_account_hash().to_formatted_string()
-                            }));
-                        }
-                    }
-                }
-            }
-            drop(config_lock);
-            if let Some(array) = args_vesting_json.as_array_mut() {
-                array.push(json!({
-                    "name": ARG_COWL_CEP18_CONTRACT_PACKAGE,
-                    "type": "Key",
-                    "value": cowl_cep18_token_package_hash
-                }));
-            }
-        }
-        session_params.set_session_args_json(&args_vesting_json.to_string());
-    }
+    let payment_amount = if upgrade {
+        COWL_VESTING_UPDATE_PAYMENT_AMOUNT.as_str()
+    } else {
+        COWL_VESTING_INSTALL_PAYMENT_AMOUNT.as_str()
+    };
 
     let install = sdk()
-        .install(
-            deploy_params,
-            session_params,
-            if contract_vesting_hash.is_empty() {
-                &COWL_VESTING_INSTALL_PAYMENT_AMOUNT
-            } else {
-                &COWL_VESTING_UPDATE_PAYMENT_AMOUNT
-            },
-            None,
-        )
+        .install(deploy_params, session_params, payment_amount, None)
         .await;
 
     let api_version = install.as_ref().unwrap().result.api_version.to_string();
 
     if api_version.is_empty() {
         log::error!("Failed to retrieve contract API version");
-        process::exit(1)
+        exit(1)
     }
 
     let deploy_hash = DeployHash::from(
@@ -387,10 +361,20 @@ pub async fn deploy_vesting_contract() -> Result<(), Error> {
 
     if deploy_hash_as_string.is_empty() {
         log::error!("Failed to retrieve deploy hash");
-        process::exit(1)
+        exit(1)
     }
 
-    if !contract_vesting_hash.is_empty() {
+    record_submitted(JournalEntry {
+        session_wasm: Some(path.to_string()),
+        wasm_hash: Some(wasm_hash),
+        ..JournalEntry::new(
+            &deploy_hash_as_string,
+            &key_pair.public_key.to_string(),
+            payment_amount,
+        )
+    });
+
+    if upgrade {
         log::info!(
             "Wait deploy_hash for vesting upgrade {}",
             deploy_hash_as_string
@@ -401,41 +385,15 @@ pub async fn deploy_vesting_contract() -> Result<(), Error> {
             deploy_hash_as_string
         );
     }
-    let event_parse_result: EventParseResult = sdk()
-        .wait_deploy(&EVENTS_ADDRESS, &deploy_hash_as_string, None)
-        .await
-        .unwrap();
-    let motes = event_parse_result
-        .clone()
-        .body
-        .unwrap()
-        .deploy_processed
-        .unwrap()
-        .execution_result
-        .success
-        .unwrap_or_else(|| {
-            log::error!("Could not retrieved cost for deploy hash {deploy_hash_as_string}");
-            log::error!("{:?}", &event_parse_result);
-            process::exit(1)
-        })
-        .cost;
-
-    let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap());
-
-    let finalized_approvals = true;
-    let get_deploy = sdk()
-        .get_deploy(deploy_hash, Some(finalized_approvals), None, None)
-        .await;
-    let get_deploy = get_deploy.unwrap();
-    let result = DeployHash::from(get_deploy.result.deploy.hash).to_string();
-    log::info!("Processed deploy hash {result}");
-    log::info!("Cost {cost} CSPR ({motes} motes)");
+    confirm_deploy(&deploy_hash_as_string).await;
+    // The contract hashes of the Installer changed with the install or upgrade
+    registry::clear_latest();
     let (contract_vesting_hash, contract_vesting_package_hash) =
         match get_contract_vesting_hash_keys().await {
             Some((hash, package_hash)) => (hash, package_hash),
             None => {
                 log::error!("Failed to retrieve contract vesting keys");
-                process::exit(1)
+                exit(1)
             }
         };
     log::info!("contract_vesting_hash {contract_vesting_hash}");
@@ -444,26 +402,14 @@ pub async fn deploy_vesting_contract() -> Result<(), Error> {
 }
 
 pub async fn deploy_swap_contract() -> Result<(), Error> {
-    let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
-
-    let (cowl_cep18_token_contract_hash, cowl_cep18_token_package_hash) =
-        match get_contract_cep18_hash_keys().await {
-            Some((hash, package_hash)) => (hash, package_hash),
-            None => (String::from(""), String::from("")),
-        };
-
-    if cowl_cep18_token_contract_hash.is_empty() {
-        log::error!("Token contract does not exist in installer named keys");
-        process::exit(1)
-    }
-
     let (contract_swap_hash, contract_swap_package_hash) = match get_contract_swap_hash_keys().await
     {
         Some((hash, package_hash)) => (hash, package_hash),
         None => (String::from(""), String::from("")),
     };
 
-    if !contract_swap_hash.is_empty() {
+    let upgrade = !contract_swap_hash.is_empty();
+    if upgrade {
         let answer = prompt_yes_no(&format!(
             "Swap contract already exists at\ncontract {}\npackage {}\ndo you want to upgrade?",
             contract_swap_hash, contract_swap_package_hash
@@ -474,14 +420,6 @@ pub async fn deploy_swap_contract() -> Result<(), Error> {
                 "You chose to upgrade swap contract at {}",
                 contract_swap_hash
             );
-            let mut args_swap_json = ARGS_SWAP_JSON.lock().await;
-            if let Some(array) = args_swap_json.as_array_mut() {
-                array.push(json!({
-                    "name": ARG_UPGRADE_FLAG.to_string(),
-                    "type": "Bool",
-                    "value": true
-                }));
-            }
         } else {
             log::info!(
                 "You chose not to upgrade swap contract at {}",
@@ -491,6 +429,56 @@ pub async fn deploy_swap_contract() -> Result<(), Error> {
         }
     }
 
+    install_swap_contract(&SwapManifest::default(), upgrade).await
+}
+
+/// Installs the swap contract with the swap window of the manifest, or upgrades it.
+pub async fn install_swap_contract(swap: &SwapManifest, upgrade: bool) -> Result<(), Error> {
+    let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
+
+    let (cowl_cep18_token_contract_hash, cowl_cep18_token_package_hash) =
+        match get_contract_cep18_hash_keys().await {
+            Some((hash, package_hash)) => (hash, package_hash),
+            None => (String::from(""), String::from("")),
+        };
+
+    if cowl_cep18_token_contract_hash.is_empty() {
+        log::error!("Token contract does not exist in installer named keys");
+        exit(1)
+    }
+
+    let mut args = vec![
+        json!({
+            "name": ARG_NAME,
+            "type": "String",
+            "value": *COWL_SWAP_NAME
+        }),
+        json!({
+            "name": ARG_EVENTS_MODE,
+            "type": "U8",
+            "value": swap.events_mode
+        }),
+        json!({
+            "name": ARG_START_TIME,
+            "type": "U64",
+            "value": swap.start_time
+        }),
+        json!({
+            "name": ARG_DURATION,
+            "type": "U64",
+            "value": swap.duration
+        }),
+    ];
+    if upgrade {
+        args.push(upgrade_flag_arg());
+    } else {
+        args.push(json!({
+            "name": ARG_COWL_CEP18_CONTRACT_PACKAGE,
+            "type": "Key",
+            "value": cowl_cep18_token_package_hash
+        }));
+    }
+
     let deploy_params = DeployStrParams::new(
         &CHAIN_NAME,
         &key_pair.public_key.to_string(),
@@ -502,7 +490,7 @@ pub async fn deploy_swap_contract() -> Result<(), Error> {
     );
 
     let session_params = SessionStrParams::default();
-    let path = &format!("{}{}.wasm", WASM_PATH, DEFAULT_SWAP_NAME);
+    let path = &wasm_file_path(DEFAULT_SWAP_NAME);
     let module_bytes = match read_wasm_file(path) {
         Ok(module_bytes) => module_bytes,
         Err(err) => {
@@ -510,20 +498,17 @@ pub async fn deploy_swap_contract() -> Result<(), Error> {
             return Err(err);
         }
     };
-    session_params.set_session_bytes(module_bytes.into());
-
-    let mut args_swap_json = ARGS_SWAP_JSON.lock().await;
-    if contract_swap_hash.is_empty() {
-        if let Some(array) = args_swap_json.as_array_mut() {
-            array.push(json!({
-                "name": ARG_COWL_CEP18_CONTRACT_PACKAGE,
-                "type": "Key",
-                "value": cowl_cep18_token_package_hash
-            }));
-        }
+    let wasm_hash = check_wasm_hash(DEFAULT_SWAP_NAME, &module_bytes);
+    if upgrade {
+        preflight_upgrade(
+            CONTRACT_SWAP,
+            &module_bytes,
+            upgrade_payment_amount(CONTRACT_SWAP),
+        )
+        .await?;
     }
-
-    session_params.set_session_args_json(&args_swap_json.to_string());
+    session_params.set_session_bytes(module_bytes.into());
+    session_params.set_session_args_json(&Value::Array(args).to_string());
 
     let install = sdk()
         .install(
@@ -538,7 +523,7 @@ pub async fn deploy_swap_contract() -> Result<(), Error> {
 
     if api_version.is_empty() {
         log::error!("Failed to retrieve contract API version");
-        process::exit(1)
+        exit(1)
     }
 
     let deploy_hash = DeployHash::from(
@@ -552,10 +537,20 @@ pub async fn deploy_swap_contract() -> Result<(), Error> {
 
     if deploy_hash_as_string.is_empty() {
         log::error!("Failed to retrieve deploy hash");
-        process::exit(1)
+        exit(1)
     }
 
-    if !contract_swap_hash.is_empty() {
+    record_submitted(JournalEntry {
+        session_wasm: Some(path.to_string()),
+        wasm_hash: Some(wasm_hash),
+        ..JournalEntry::new(
+            &deploy_hash_as_string,
+            &key_pair.public_key.to_string(),
+            &COWL_SWAP_INSTALL_PAYMENT_AMOUNT,
+        )
+    });
+
+    if upgrade {
         log::info!(
             "Wait deploy_hash for swap upgrade {}",
             deploy_hash_as_string
@@ -566,41 +561,15 @@ pub async fn deploy_swap_contract() -> Result<(), Error> {
             deploy_hash_as_string
         );
     }
-    let event_parse_result: EventParseResult = sdk()
-        .wait_deploy(&EVENTS_ADDRESS, &deploy_hash_as_string, None)
-        .await
-        .unwrap();
-    let motes = event_parse_result
-        .clone()
-        .body
-        .unwrap()
-        .deploy_processed
-        .unwrap()
-        .execution_result
-        .success
-        .unwrap_or_else(|| {
-            log::error!("Could not retrieved cost for deploy hash {deploy_hash_as_string}");
-            log::error!("{:?}", &event_parse_result);
-            process::exit(1)
-        })
-        .cost;
-
-    let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap());
-
-    let finalized_approvals = true;
-    let get_deploy = sdk()
-        .get_deploy(deploy_hash, Some(finalized_approvals), None, None)
-        .await;
-    let get_deploy = get_deploy.unwrap();
-    let result = DeployHash::from(get_deploy.result.deploy.hash).to_string();
-    log::info!("Processed deploy hash {result}");
-    log::info!("Cost {cost} CSPR ({motes} motes)");
+    confirm_deploy(&deploy_hash_as_string).await;
+    // The contract hashes of the Installer changed with the install or upgrade
+    registry::clear_latest();
     let (contract_swap_hash, contract_swap_package_hash) = match get_contract_swap_hash_keys().await
     {
         Some((hash, package_hash)) => (hash, package_hash),
         None => {
             log::error!("Failed to retrieve contract swap keys");
-            process::exit(1)
+            exit(1)
         }
     };
     log::info!("contract_swap_hash {contract_swap_hash}");
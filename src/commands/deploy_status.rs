@@ -0,0 +1,45 @@
+use crate::utils::{
+    exit,
+    receipt::{get_deploy_receipt, DEPLOY_STATUS_PENDING},
+};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+pub async fn deploy_status(
+    deploy_hash: &str,
+    wait: bool,
+    interval: u64,
+    timeout: u64,
+) -> Result<IndexMap<String, Value>, String> {
+    let started = Instant::now();
+    loop {
+        let receipt = get_deploy_receipt(deploy_hash).await?;
+        let pending = receipt["status"] == json!(DEPLOY_STATUS_PENDING);
+
+        if !pending || !wait {
+            return Ok(receipt);
+        }
+
+        if started.elapsed() >= Duration::from_secs(timeout) {
+            log::warn!("Deploy {deploy_hash} still pending after {timeout} seconds");
+            return Ok(receipt);
+        }
+
+        log::info!("Deploy {deploy_hash} pending, polling again in {interval} seconds");
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+    }
+}
+
+pub async fn print_deploy_status(deploy_hash: String, wait: bool, interval: u64, timeout: u64) {
+    match deploy_status(&deploy_hash, wait, interval, timeout).await {
+        Ok(receipt) => {
+            let json_output = serde_json::to_string_pretty(&receipt).unwrap();
+            log::info!("\n{}", json_output);
+        }
+        Err(err) => {
+            log::error!("{err}");
+            exit(1);
+        }
+    }
+}
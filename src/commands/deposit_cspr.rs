@@ -1,18 +1,23 @@
 use crate::{
-    commands::balance::print_balance,
+    commands::{
+        balance::print_balance,
+        verification::{BalanceCheck, BalanceSnapshot, ExpectedDelta},
+    },
     utils::{
         config::get_key_pair_from_vesting,
+        confirmation::confirm_deploy,
         constants::{
             CHAIN_NAME, COWL_DEPOSIT_CSPR_CALL_PAYMENT_AMOUNT, DEFAULT_SWAP_DEPOSIT_CSPR_SESSION,
-            EVENTS_ADDRESS, INSTALLER, TTL, WASM_PATH,
+            INSTALLER, TTL,
         },
-        format_with_thousands_separator, get_contract_swap_hash_keys,
+        exit, format_with_thousands_separator, get_contract_swap_hash_keys,
+        journal::{record_submitted, JournalEntry},
         keys::format_base64_to_pem,
         prompt_yes_no, read_wasm_file, sdk,
+        wasm::{check_wasm_hash, wasm_file_path},
     },
 };
 use casper_rust_wasm_sdk::{
-    deploy_watcher::watcher::EventParseResult,
     helpers::motes_to_cspr,
     types::{
         deploy_hash::DeployHash,
@@ -23,7 +28,6 @@ use casper_rust_wasm_sdk::{
 use cowl_swap::constants::ARG_COWL_SWAP_CONTRACT_PACKAGE;
 use cowl_vesting::constants::ARG_AMOUNT;
 use serde_json::json;
-use std::process;
 
 pub async fn deposit_cspr(amount: String) {
     let (_, cowl_swap_contract_package_hash) = match get_contract_swap_hash_keys().await {
@@ -33,7 +37,7 @@ pub async fn deposit_cspr(amount: String) {
 
     if cowl_swap_contract_package_hash.is_empty() {
         log::error!("Swap contract package does not exist in installer named keys");
-        process::exit(1)
+        exit(1)
     }
 
     let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
@@ -51,6 +55,22 @@ pub async fn deposit_cspr(amount: String) {
         return;
     }
 
+    let installer = Key::from_account(key_pair.public_key.to_account_hash());
+    let deposited = ExpectedDelta::amount(&amount);
+    let mut checks = vec![BalanceCheck::cspr(
+        "installer",
+        &installer,
+        ExpectedDelta::signer(&deposited, &COWL_DEPOSIT_CSPR_CALL_PAYMENT_AMOUNT),
+    )];
+    if let Ok(swap_key) = Key::from_formatted_str(&cowl_swap_contract_package_hash) {
+        checks.push(BalanceCheck::cspr(
+            "swap contract",
+            &swap_key,
+            ExpectedDelta::Exact(deposited),
+        ));
+    }
+    let snapshot = BalanceSnapshot::take(checks).await;
+
     let deploy_params = DeployStrParams::new(
         &CHAIN_NAME,
         &key_pair.public_key.to_string(),
@@ -62,7 +82,7 @@ pub async fn deposit_cspr(amount: String) {
     );
 
     let session_params = SessionStrParams::default();
-    let path = &format!("{}{}.wasm", WASM_PATH, DEFAULT_SWAP_DEPOSIT_CSPR_SESSION);
+    let path = &wasm_file_path(DEFAULT_SWAP_DEPOSIT_CSPR_SESSION);
     let module_bytes = match read_wasm_file(path) {
         Ok(module_bytes) => module_bytes,
         Err(err) => {
@@ -70,6 +90,7 @@ pub async fn deposit_cspr(amount: String) {
             return;
         }
     };
+    let wasm_hash = check_wasm_hash(DEFAULT_SWAP_DEPOSIT_CSPR_SESSION, &module_bytes);
     session_params.set_session_bytes(module_bytes.into());
 
     let args_deposit_cspr_json = json!([
@@ -105,7 +126,7 @@ pub async fn deposit_cspr(amount: String) {
 
     if api_version.is_empty() {
         log::error!("Failed to retrieve contract API version");
-        process::exit(1)
+        exit(1)
     }
 
     let deploy_hash = DeployHash::from(
@@ -119,55 +140,40 @@ pub async fn deposit_cspr(amount: String) {
 
     if deploy_hash_as_string.is_empty() {
         log::error!("Failed to retrieve deploy hash");
-        process::exit(1)
+        exit(1)
     }
 
+    record_submitted(JournalEntry {
+        contract_package: Some(cowl_swap_contract_package_hash.clone()),
+        session_wasm: Some(path.to_string()),
+        wasm_hash: Some(wasm_hash),
+        ..JournalEntry::new(
+            &deploy_hash_as_string,
+            &key_pair.public_key.to_string(),
+            &COWL_DEPOSIT_CSPR_CALL_PAYMENT_AMOUNT,
+        )
+    });
+
     log::info!(
         "Wait deploy_hash for deposit {} {}",
         "CSPR",
         deploy_hash_as_string,
     );
 
-    let event_parse_result: EventParseResult = sdk()
-        .wait_deploy(&EVENTS_ADDRESS, &deploy_hash_as_string, None)
-        .await
-        .unwrap();
-    let motes = event_parse_result
-        .clone()
-        .body
-        .unwrap()
-        .deploy_processed
-        .unwrap()
-        .execution_result
-        .success
-        .unwrap_or_else(|| {
-            log::error!("Could not retrieved cost for deploy hash {deploy_hash_as_string}");
-            log::error!("{:?}", &event_parse_result);
-            process::exit(1)
-        })
-        .cost;
-
-    let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap());
-
-    let finalized_approvals = true;
-    let get_deploy = sdk()
-        .get_deploy(deploy_hash, Some(finalized_approvals), None, None)
-        .await;
-    let get_deploy = get_deploy.unwrap();
-    let result = DeployHash::from(get_deploy.result.deploy.hash).to_string();
-    log::info!("Processed deploy hash {result}");
-    log::info!("Cost {cost} CSPR ({motes} motes)");
+    confirm_deploy(&deploy_hash_as_string).await;
+
+    snapshot.verify().await;
 
     let key = Key::from_account(key_pair.public_key.to_account_hash());
     log::info!("Balance for {}", key_pair.public_key.to_string());
-    print_balance(None, Some(key.clone()), None).await;
+    print_balance(None, Some(key.clone()), None, None).await;
 
     let key = Key::from_formatted_str(&cowl_swap_contract_package_hash).ok();
     log::info!(
         "Balance for Swap Contract Package {}",
         cowl_swap_contract_package_hash
     );
-    print_balance(None, None, key).await;
+    print_balance(None, None, key, None).await;
 }
 
 pub async fn print_deposit_cspr(amount: String) {
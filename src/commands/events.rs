@@ -0,0 +1,184 @@
+use crate::utils::{
+    constants::{DEPLOY_POLL_INTERVAL, EVENTS_ADDRESS},
+    events::{extract_events, get_event_sources, EventSource},
+    exit,
+};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::{
+    error::Error,
+    fs::{File, OpenOptions},
+    io::Write,
+    time::Duration,
+};
+
+// Server-sent events are separated by an empty line
+const SSE_MESSAGE_SEPARATOR: &[u8] = b"\n\n";
+
+/// A message of the events stream, with its event ID and its data lines joined.
+#[derive(Debug)]
+pub struct SseMessage {
+    pub id: Option<u64>,
+    pub data: String,
+}
+
+pub fn parse_sse_message(message: &str) -> SseMessage {
+    let mut id = None;
+    let mut data = String::new();
+    for line in message.lines() {
+        if let Some(value) = line.strip_prefix("id:") {
+            id = value.trim().parse().ok();
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push_str(value.trim_start());
+        }
+    }
+    SseMessage { id, data }
+}
+
+/// Decodes the COWL events of a `DeployProcessed` message, with the deploy and event stream
+/// context of each event.
+fn decode_deploy_processed(
+    event_id: Option<u64>,
+    data: &Value,
+    sources: &[EventSource],
+) -> Vec<IndexMap<String, Value>> {
+    let deploy_processed = match data.get("DeployProcessed") {
+        Some(deploy_processed) => deploy_processed,
+        None => return vec![],
+    };
+
+    extract_events(&deploy_processed["execution_result"], sources)
+        .into_iter()
+        .map(|event| {
+            let mut record = IndexMap::new();
+            record.insert("event_id".to_string(), json!(event_id));
+            record.insert(
+                "deploy_hash".to_string(),
+                deploy_processed["deploy_hash"].clone(),
+            );
+            record.insert(
+                "block_hash".to_string(),
+                deploy_processed["block_hash"].clone(),
+            );
+            record.insert(
+                "timestamp".to_string(),
+                deploy_processed["timestamp"].clone(),
+            );
+            record.insert("source".to_string(), json!(event.source));
+            record.insert("contract_hash".to_string(), json!(event.contract_hash));
+            record.insert("name".to_string(), json!(event.name));
+            record.insert("fields".to_string(), json!(event.fields));
+            record
+        })
+        .collect()
+}
+
+fn handle_message(
+    message: &str,
+    sources: &[EventSource],
+    last_event_id: &mut Option<u64>,
+    output: &mut Option<File>,
+) -> Result<(), Box<dyn Error>> {
+    let message = parse_sse_message(message);
+    if message.id.is_some() {
+        *last_event_id = message.id;
+    }
+
+    // Keep-alive comments and the initial ApiVersion message carry no deploy
+    let data: Value = match serde_json::from_str(&message.data) {
+        Ok(data) => data,
+        Err(_) => return Ok(()),
+    };
+
+    for record in decode_deploy_processed(message.id, &data, sources) {
+        let json_output = serde_json::to_string_pretty(&record)?;
+        log::info!("\n{}", json_output);
+        if let Some(file) = output.as_mut() {
+            writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        }
+    }
+    Ok(())
+}
+
+async fn stream_events(
+    start_from: Option<u64>,
+    sources: &[EventSource],
+    last_event_id: &mut Option<u64>,
+    output: &mut Option<File>,
+) -> Result<(), Box<dyn Error>> {
+    let url = match start_from {
+        Some(event_id) => format!("{}?start_from={}", *EVENTS_ADDRESS, event_id),
+        None => EVENTS_ADDRESS.to_string(),
+    };
+
+    let mut response = reqwest::get(&url).await?.error_for_status()?;
+    log::info!("Listening to {url}");
+
+    let mut buffer: Vec<u8> = vec![];
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer
+            .windows(SSE_MESSAGE_SEPARATOR.len())
+            .position(|window| window == SSE_MESSAGE_SEPARATOR)
+        {
+            let message: Vec<u8> = buffer.drain(..end + SSE_MESSAGE_SEPARATOR.len()).collect();
+            handle_message(
+                &String::from_utf8_lossy(&message),
+                sources,
+                last_event_id,
+                output,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn watch_events(start_from: Option<u64>, output: Option<String>) {
+    if EVENTS_ADDRESS.is_empty() {
+        log::error!("EVENTS_ADDRESS is not set");
+        exit(1)
+    }
+
+    let sources = get_event_sources().await;
+    if sources.is_empty() {
+        log::error!("No installed contract with events enabled");
+        exit(1)
+    }
+    for source in &sources {
+        log::info!(
+            "Watching {} contract {}",
+            source.label,
+            source.contract_hash
+        );
+    }
+
+    let mut output = match output {
+        Some(path) => match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => Some(file),
+            Err(err) => {
+                log::error!("Failed to open {path}: {err}");
+                exit(1)
+            }
+        },
+        None => None,
+    };
+
+    // Resume from the event following the last one seen on reconnection
+    let mut next_event_id = start_from;
+    loop {
+        let mut last_event_id = None;
+        if let Err(err) =
+            stream_events(next_event_id, &sources, &mut last_event_id, &mut output).await
+        {
+            log::warn!("Events stream {} error: {}", *EVENTS_ADDRESS, err);
+        }
+        if let Some(event_id) = last_event_id {
+            next_event_id = Some(event_id + 1);
+        }
+        log::warn!(
+            "Events stream closed, reconnecting in {} seconds",
+            *DEPLOY_POLL_INTERVAL
+        );
+        tokio::time::sleep(Duration::from_secs(*DEPLOY_POLL_INTERVAL)).await;
+    }
+}
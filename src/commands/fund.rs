@@ -1,21 +1,28 @@
-use std::{process, str::FromStr};
+use std::{fs, str::FromStr};
 
 use crate::{
-    commands::balance::print_balance,
+    commands::{
+        balance::{
+            get_cspr_account_balance, get_cspr_contract_balance, print_balance, query_cspr_balance,
+        },
+        verification::{BalanceCheck, BalanceSnapshot, ExpectedDelta},
+    },
     utils::{
         config::get_key_pair_from_vesting,
-        constants::{
-            CHAIN_NAME, EVENTS_ADDRESS, INSTALLER, MINIMUM_TRANSFER_AMOUNT,
-            PAYMENT_TRANSFER_AMOUNT, TTL,
-        },
-        format_with_thousands_separator,
-        keys::format_base64_to_pem,
+        confirmation::confirm_deploy,
+        constants::{CHAIN_NAME, INSTALLER, MINIMUM_TRANSFER_AMOUNT, PAYMENT_TRANSFER_AMOUNT, TTL},
+        exit, format_with_thousands_separator, get_contract_purse,
+        journal::{record_submitted, JournalEntry},
+        key_ref::parse_key_ref,
+        keys::{format_base64_to_pem, KeyPair},
         prompt_yes_no, sdk,
     },
 };
-use bigdecimal::BigDecimal;
+use bigdecimal::{
+    num_bigint::{BigInt, BigUint},
+    BigDecimal,
+};
 use casper_rust_wasm_sdk::{
-    deploy_watcher::watcher::EventParseResult,
     helpers::motes_to_cspr,
     types::{
         deploy_hash::DeployHash,
@@ -23,52 +30,37 @@ use casper_rust_wasm_sdk::{
         key::Key,
     },
 };
+use chrono::Utc;
 use cowl_vesting::enums::VestingType;
+use strum::IntoEnumIterator;
 
-pub async fn fund_addresses(
-    maybe_vesting_type: Option<VestingType>,
-    maybe_key: Option<Key>,
-    amount: String,
-) {
-    let target_account_hash = if let Some(vesting_type) = maybe_vesting_type {
-        let key_pair = get_key_pair_from_vesting(&vesting_type.to_string())
-            .await
-            .unwrap();
-        key_pair.public_key.to_account_hash().to_formatted_string()
-    } else if let Some(key) = maybe_key.clone() {
-        key.clone().into_account().unwrap().to_formatted_string()
-    } else {
-        log::error!("Both vesting_type and vesting_key are missing.");
-        return;
-    };
-
-    // Retrieve the private key of Installer
-    let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
-
-    let to = match maybe_vesting_type {
-        Some(vesting_type) => vesting_type.to_string(),
-        None => maybe_key
-            .clone()
-            .map(|key| key.to_formatted_string())
-            .unwrap_or_else(|| "Failed to retrieve account hash".to_string()),
-    };
+/// Generates a transfer id from the current time, distinct across runs.
+pub fn generate_transfer_id() -> u64 {
+    Utc::now().timestamp_millis() as u64
+}
 
-    let answer = prompt_yes_no(&format!(
-        "Please confirm funding of {} CSPR ({} motes) to {}?",
-        format_with_thousands_separator(&motes_to_cspr(&amount).unwrap()),
-        amount,
-        &to
-    ));
-    if !answer {
-        log::info!("You chose not to fund");
-        process::exit(0);
+/// Resolves the native transfer target of a key: its account hash, or the purse of a contract.
+async fn transfer_target(key: &Key) -> Option<String> {
+    match key.clone().into_account() {
+        Some(account_hash) => Some(account_hash.to_formatted_string()),
+        // Contracts are funded through the purse they hold
+        None => get_contract_purse(key, None)
+            .await
+            .map(|purse_uref| purse_uref.to_formatted_string()),
     }
+}
 
+async fn send_native_transfer(
+    key_pair: &KeyPair,
+    target: &str,
+    amount: &str,
+    maybe_transfer_id: Option<u64>,
+) -> String {
     let deploy_params = DeployStrParams::new(
         &CHAIN_NAME,
         &key_pair.public_key.to_string(),
         Some(format_base64_to_pem(
-            &key_pair.private_key_base64.unwrap().clone(),
+            &key_pair.private_key_base64.clone().unwrap(),
         )),
         None,
         Some(TTL.to_string()),
@@ -78,9 +70,9 @@ pub async fn fund_addresses(
 
     let transfer = sdk()
         .transfer(
-            &amount,
-            &target_account_hash,
-            None,
+            amount,
+            target,
+            maybe_transfer_id.map(|transfer_id| transfer_id.to_string()),
             deploy_params,
             payment_params,
             None,
@@ -92,7 +84,7 @@ pub async fn fund_addresses(
 
     if api_version.is_empty() {
         log::error!("Failed to retrieve contract API version");
-        process::exit(1)
+        exit(1)
     }
 
     let deploy_hash = DeployHash::from(
@@ -106,46 +98,110 @@ pub async fn fund_addresses(
 
     if deploy_hash_as_string.is_empty() {
         log::error!("Failed to retrieve deploy hash");
-        process::exit(1)
+        exit(1)
     }
 
-    log::info!("Wait deploy_hash for funding {}", deploy_hash_as_string);
+    if let Some(transfer_id) = maybe_transfer_id {
+        log::info!("Transfer id {transfer_id} for {target}");
+    }
 
-    let event_parse_result: EventParseResult = sdk()
-        .wait_deploy(&EVENTS_ADDRESS, &deploy_hash_as_string, None)
-        .await
-        .unwrap();
-    let motes = event_parse_result
-        .clone()
-        .body
-        .unwrap()
-        .deploy_processed
-        .unwrap()
-        .execution_result
-        .success
-        .unwrap_or_else(|| {
-            log::error!("Could not retrieved cost for deploy hash {deploy_hash_as_string}");
-            log::error!("{:?}", &event_parse_result);
-            process::exit(1)
-        })
-        .cost;
+    record_submitted(JournalEntry {
+        recipient: Some(target.to_string()),
+        transfer_id: maybe_transfer_id.map(|transfer_id| transfer_id.to_string()),
+        ..JournalEntry::new(
+            &deploy_hash_as_string,
+            &key_pair.public_key.to_string(),
+            PAYMENT_TRANSFER_AMOUNT,
+        )
+    });
 
-    let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap());
+    deploy_hash_as_string
+}
 
-    let finalized_approvals = true;
-    let get_deploy = sdk()
-        .get_deploy(deploy_hash, Some(finalized_approvals), None, None)
-        .await;
-    let get_deploy = get_deploy.unwrap();
-    let result = DeployHash::from(get_deploy.result.deploy.hash).to_string();
-    log::info!("Processed deploy hash {result}");
-    log::info!("Cost {cost} CSPR ({motes} motes)");
+pub async fn fund_addresses(
+    maybe_vesting_type: Option<VestingType>,
+    maybe_key: Option<Key>,
+    amount: String,
+    maybe_transfer_id: Option<u64>,
+) {
+    let (target_account_hash, target_key) = if let Some(vesting_type) = maybe_vesting_type {
+        let key_pair = get_key_pair_from_vesting(&vesting_type.to_string())
+            .await
+            .unwrap();
+        let account_hash = key_pair.public_key.to_account_hash();
+        (
+            account_hash.to_formatted_string(),
+            Key::from_account(account_hash),
+        )
+    } else if let Some(key) = maybe_key.clone() {
+        match transfer_target(&key).await {
+            Some(target) => (target, key),
+            None => {
+                log::error!(
+                    "Contract {} has no purse to fund",
+                    key.to_formatted_string()
+                );
+                exit(1)
+            }
+        }
+    } else {
+        log::error!("Both vesting_type and vesting_key are missing.");
+        return;
+    };
+
+    // Retrieve the private key of Installer
+    let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
+
+    let to = match maybe_vesting_type {
+        Some(vesting_type) => vesting_type.to_string(),
+        None => maybe_key
+            .clone()
+            .map(|key| key.to_formatted_string())
+            .unwrap_or_else(|| "Failed to retrieve account hash".to_string()),
+    };
+
+    let answer = prompt_yes_no(&format!(
+        "Please confirm funding of {} CSPR ({} motes) to {}?",
+        format_with_thousands_separator(&motes_to_cspr(&amount).unwrap()),
+        amount,
+        &to
+    ));
+    if !answer {
+        log::info!("You chose not to fund");
+        exit(0);
+    }
+
+    let funded = ExpectedDelta::amount(&amount);
+    let installer = Key::from_account(key_pair.public_key.to_account_hash());
+    let snapshot = BalanceSnapshot::take(vec![
+        BalanceCheck::cspr(
+            "recipient",
+            &target_key,
+            ExpectedDelta::Exact(funded.clone()),
+        ),
+        BalanceCheck::cspr(
+            "installer",
+            &installer,
+            ExpectedDelta::signer(&funded, PAYMENT_TRANSFER_AMOUNT),
+        ),
+    ])
+    .await;
+
+    let deploy_hash_as_string =
+        send_native_transfer(&key_pair, &target_account_hash, &amount, maybe_transfer_id).await;
+
+    log::info!("Wait deploy_hash for funding {}", deploy_hash_as_string);
+
+    confirm_deploy(&deploy_hash_as_string).await;
+
+    snapshot.verify().await;
 }
 
 pub async fn print_fund_addresses(
     vesting_type: Option<VestingType>,
     key: Option<Key>,
     amount: String,
+    maybe_transfer_id: Option<u64>,
 ) {
     if !check_amount(&amount) {
         log::error!(
@@ -155,14 +211,14 @@ pub async fn print_fund_addresses(
             format_with_thousands_separator(&motes_to_cspr(MINIMUM_TRANSFER_AMOUNT).unwrap()),
             MINIMUM_TRANSFER_AMOUNT
         );
-        process::exit(1)
+        exit(1)
     }
 
-    print_balance(vesting_type, key.clone(), None).await;
+    print_balance(vesting_type, key.clone(), None, None).await;
 
-    fund_addresses(vesting_type, key.clone(), amount).await;
+    fund_addresses(vesting_type, key.clone(), amount, maybe_transfer_id).await;
 
-    print_balance(vesting_type, key.clone(), None).await;
+    print_balance(vesting_type, key.clone(), None, None).await;
 }
 
 fn check_amount(amount_str: &str) -> bool {
@@ -173,3 +229,274 @@ fn check_amount(amount_str: &str) -> bool {
     // Compare the amounts
     amount >= threshold
 }
+
+/// An account or contract to fund, with the label it was given.
+#[derive(Debug, Clone)]
+pub struct FundTarget {
+    pub label: String,
+    pub key: Key,
+}
+
+/// Targets for every configured vesting account.
+pub async fn vesting_fund_targets() -> Vec<FundTarget> {
+    let mut targets = vec![];
+    for vesting_type in VestingType::iter() {
+        let label = vesting_type.to_string();
+        match get_key_pair_from_vesting(&label).await {
+            Some(key_pair) => targets.push(FundTarget {
+                key: Key::from_account(key_pair.public_key.to_account_hash()),
+                label,
+            }),
+            None => log::warn!("No key configured for {label}, skipped"),
+        }
+    }
+    targets
+}
+
+/// Reads targets from a file holding one key per line: a vesting type, an account name, a
+/// contact alias, a public key or a hash. Blank lines and `#` comments are ignored.
+pub async fn read_fund_targets(path: &str) -> Result<Vec<FundTarget>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("Failed to read {path}: {err}"))?;
+
+    let mut targets = vec![];
+    for (index, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let target = parse_fund_target(line)
+            .await
+            .map_err(|err| format!("{path}:{}: invalid target {line}\n{err}", index + 1))?;
+        targets.push(target);
+    }
+    Ok(targets)
+}
+
+/// Parses and resolves a target given as a vesting type, an account name, a contact alias, a
+/// public key or a hash.
+pub async fn parse_fund_target(input: &str) -> Result<FundTarget, String> {
+    let key_ref = parse_key_ref(input)?;
+    Ok(FundTarget {
+        key: key_ref.to_key().await?,
+        label: key_ref.to_string(),
+    })
+}
+
+async fn get_target_balance_motes(target: &FundTarget) -> String {
+    let (_, balance_motes) = if target.key.clone().into_account().is_some() {
+        get_cspr_account_balance(
+            None,
+            Some(target.label.clone()),
+            Some(target.key.clone()),
+            None,
+        )
+        .await
+    } else {
+        get_cspr_contract_balance(&target.key, None).await
+    };
+    balance_motes
+}
+
+fn parse_motes(amount: &str) -> BigUint {
+    BigUint::from_str(amount).unwrap_or_else(|_| {
+        log::error!("Invalid amount {amount}");
+        exit(1)
+    })
+}
+
+fn format_cspr(motes: &str) -> String {
+    format_with_thousands_separator(&motes_to_cspr(motes).unwrap_or_default())
+}
+
+// Funding planned for a target, `maybe_account` being `None` for a contract without purse
+struct Funding {
+    target: FundTarget,
+    maybe_account: Option<String>,
+    before: String,
+    amount: BigUint,
+    transfer_id: u64,
+}
+
+/// Funds several targets in sequence from the Installer, either with a fixed amount or with
+/// the deficit of each target up to `top_up_to`, and prints their balances before and after.
+/// Transfer ids are consecutive from `first_transfer_id`, one per target. Contracts without a
+/// purse are skipped. Returns the number of targets funded.
+pub async fn print_fund_targets(
+    targets: Vec<FundTarget>,
+    maybe_amount: Option<String>,
+    maybe_top_up_to: Option<String>,
+    first_transfer_id: u64,
+) -> usize {
+    if targets.is_empty() {
+        log::error!("No target to fund");
+        exit(1)
+    }
+
+    let minimum = parse_motes(MINIMUM_TRANSFER_AMOUNT);
+    if let Some(amount) = &maybe_amount {
+        if !check_amount(amount) {
+            log::error!(
+                "Amount {} CSPR ({} motes) is less than minimum {} CSPR ({} motes)",
+                format_cspr(amount),
+                amount,
+                format_cspr(MINIMUM_TRANSFER_AMOUNT),
+                MINIMUM_TRANSFER_AMOUNT
+            );
+            exit(1)
+        }
+    }
+
+    // Amount to send to each target, native transfers below the minimum are raised to it
+    let mut fundings = vec![];
+    for (index, target) in targets.into_iter().enumerate() {
+        // Each target gets the next transfer id
+        let Some(transfer_id) = first_transfer_id.checked_add(index as u64) else {
+            log::error!("Transfer id {first_transfer_id} leaves no room for {index} more ids");
+            exit(1)
+        };
+        let maybe_account = transfer_target(&target.key).await;
+        if maybe_account.is_none() {
+            log::error!(
+                "Contract {} has no purse to fund, skipped",
+                target.key.to_formatted_string()
+            );
+        }
+        let before = get_target_balance_motes(&target).await;
+        let amount = match (&maybe_top_up_to, &maybe_amount) {
+            _ if maybe_account.is_none() => BigUint::default(),
+            (Some(top_up_to), _) => {
+                let top_up_to = parse_motes(top_up_to);
+                // A balance that cannot be read is not topped up as if it were empty
+                let balance = query_cspr_balance(&target.key, None)
+                    .await
+                    .unwrap_or_else(|err| {
+                        log::error!("Could not read the balance of {}: {err}", target.label);
+                        exit(1)
+                    });
+                let before = parse_motes(&balance);
+                if before >= top_up_to {
+                    BigUint::default()
+                } else {
+                    (top_up_to - before).max(minimum.clone())
+                }
+            }
+            (None, Some(amount)) => parse_motes(amount),
+            (None, None) => {
+                log::error!("Either an amount or a top up amount is required");
+                exit(1)
+            }
+        };
+        fundings.push(Funding {
+            target,
+            maybe_account,
+            before,
+            amount,
+            transfer_id,
+        });
+    }
+
+    let total: BigUint = fundings.iter().map(|funding| &funding.amount).sum();
+    if total == BigUint::default() {
+        log::info!("All targets are already funded");
+        return 0;
+    }
+
+    for funding in &fundings {
+        match funding.maybe_account {
+            Some(_) => log::info!(
+                "{}: {} CSPR + {} CSPR",
+                funding.target.label,
+                format_cspr(&funding.before),
+                format_cspr(&funding.amount.to_string())
+            ),
+            None => log::info!("{}: skipped", funding.target.label),
+        }
+    }
+    let transfers: Vec<&Funding> = fundings
+        .iter()
+        .filter(|funding| funding.amount > BigUint::default())
+        .collect();
+    let answer = prompt_yes_no(&format!(
+        "Please confirm funding of {} CSPR ({} motes) to {} targets?",
+        format_cspr(&total.to_string()),
+        total,
+        transfers.len()
+    ));
+    if !answer {
+        log::info!("You chose not to fund");
+        exit(0);
+    }
+
+    // Retrieve the private key of Installer
+    let Some(key_pair) = get_key_pair_from_vesting(INSTALLER).await else {
+        log::error!("No key configured for the {INSTALLER}");
+        exit(1)
+    };
+
+    let installer = Key::from_account(key_pair.public_key.to_account_hash());
+    let sent = BigInt::from(total);
+    let payment = ExpectedDelta::amount(PAYMENT_TRANSFER_AMOUNT) * transfers.len();
+    let mut checks: Vec<BalanceCheck> = transfers
+        .iter()
+        .map(|funding| {
+            BalanceCheck::cspr(
+                &funding.target.label,
+                &funding.target.key,
+                ExpectedDelta::Exact(BigInt::from(funding.amount.clone())),
+            )
+        })
+        .collect();
+    checks.push(BalanceCheck::cspr(
+        "installer",
+        &installer,
+        ExpectedDelta::signer(&sent, &payment.to_string()),
+    ));
+    let snapshot = BalanceSnapshot::take(checks).await;
+
+    let mut funded = 0;
+    for funding in &transfers {
+        let Some(target_account) = &funding.maybe_account else {
+            continue;
+        };
+        let deploy_hash = send_native_transfer(
+            &key_pair,
+            target_account,
+            &funding.amount.to_string(),
+            Some(funding.transfer_id),
+        )
+        .await;
+        log::info!(
+            "Wait deploy_hash for funding {} {}",
+            funding.target.label,
+            deploy_hash
+        );
+        confirm_deploy(&deploy_hash).await;
+        funded += 1;
+    }
+
+    log::info!(
+        "\n{:<32} {:>24} {:>24} {:>24}",
+        "target",
+        "before (CSPR)",
+        "sent (CSPR)",
+        "after (CSPR)"
+    );
+    for funding in &fundings {
+        let sent = match funding.maybe_account {
+            Some(_) => format_cspr(&funding.amount.to_string()),
+            None => "skipped".to_string(),
+        };
+        let after = get_target_balance_motes(&funding.target).await;
+        log::info!(
+            "{:<32} {:>24} {:>24} {:>24}",
+            funding.target.label,
+            format_cspr(&funding.before),
+            sent,
+            format_cspr(&after)
+        );
+    }
+
+    snapshot.verify().await;
+    funded
+}
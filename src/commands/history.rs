@@ -0,0 +1,180 @@
+use crate::utils::{
+    constants::JOURNAL_FILE_PATH,
+    exit,
+    journal::{load_journal, record_status, JournalEntry},
+    receipt::{get_deploy_receipt, DEPLOY_STATUS_FAILURE, DEPLOY_STATUS_PENDING},
+};
+use std::fs;
+
+pub const EXPORT_FORMAT_JSON: &str = "json";
+pub const EXPORT_FORMAT_CSV: &str = "csv";
+
+const CSV_HEADER: &str = "timestamp,command,arguments,signer,contract_package,entry_point,session_wasm,wasm_hash,recipient,transfer_id,memo,payment,deploy_hash,status,cost,error_message";
+
+/// Filters applied to journal entries, all optional.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub status: Option<String>,
+    pub command: Option<String>,
+    pub signer: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, entry: &JournalEntry) -> bool {
+        self.status
+            .as_ref()
+            .is_none_or(|status| entry.status.eq_ignore_ascii_case(status))
+            && self
+                .command
+                .as_ref()
+                .is_none_or(|command| entry.command == *command)
+            && self
+                .signer
+                .as_ref()
+                .is_none_or(|signer| entry.signer == *signer)
+    }
+}
+
+fn load_filtered_journal(filter: &HistoryFilter) -> Vec<JournalEntry> {
+    let entries = match load_journal() {
+        Ok(entries) => entries,
+        Err(err) => {
+            log::error!("Failed to read journal {}: {}", *JOURNAL_FILE_PATH, err);
+            exit(1)
+        }
+    };
+
+    let mut entries: Vec<JournalEntry> = entries
+        .into_iter()
+        .filter(|entry| filter.matches(entry))
+        .collect();
+
+    // Keep the most recent entries
+    if let Some(limit) = filter.limit {
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+    }
+    entries
+}
+
+pub async fn print_history(filter: HistoryFilter) {
+    let entries = load_filtered_journal(&filter);
+    let json_output = serde_json::to_string_pretty(&entries).unwrap();
+    log::info!("\n{}", json_output);
+}
+
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn entries_to_csv(entries: &[JournalEntry]) -> String {
+    let mut csv = format!("{CSV_HEADER}\n");
+    for entry in entries {
+        let fields = [
+            entry.timestamp.clone(),
+            entry.command.clone(),
+            entry.arguments.join(" "),
+            entry.signer.clone(),
+            entry.contract_package.clone().unwrap_or_default(),
+            entry.entry_point.clone().unwrap_or_default(),
+            entry.session_wasm.clone().unwrap_or_default(),
+            entry.wasm_hash.clone().unwrap_or_default(),
+            entry.recipient.clone().unwrap_or_default(),
+            entry.transfer_id.clone().unwrap_or_default(),
+            entry.memo.clone().unwrap_or_default(),
+            entry.payment.clone(),
+            entry.deploy_hash.clone(),
+            entry.status.clone(),
+            entry.cost.clone().unwrap_or_default(),
+            entry.error_message.clone().unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub async fn export_history(filter: HistoryFilter, format: String, output: Option<String>) {
+    let entries = load_filtered_journal(&filter);
+
+    let content = match format.as_str() {
+        EXPORT_FORMAT_JSON => serde_json::to_string_pretty(&entries).unwrap(),
+        EXPORT_FORMAT_CSV => entries_to_csv(&entries),
+        _ => {
+            log::error!(
+                "Unknown export format {format}, expected {EXPORT_FORMAT_JSON} or {EXPORT_FORMAT_CSV}"
+            );
+            exit(1)
+        }
+    };
+
+    match output {
+        Some(path) => {
+            if let Err(err) = fs::write(&path, content) {
+                log::error!("Failed to write {path}: {err}");
+                exit(1)
+            }
+            log::info!("Exported {} journal entries to {}", entries.len(), path);
+        }
+        None => println!("{content}"),
+    }
+}
+
+pub async fn reconcile() {
+    let filter = HistoryFilter {
+        status: Some(DEPLOY_STATUS_PENDING.to_string()),
+        ..Default::default()
+    };
+    let pending_entries = load_filtered_journal(&filter);
+
+    if pending_entries.is_empty() {
+        log::info!("No pending deploy in {}", *JOURNAL_FILE_PATH);
+        return;
+    }
+
+    let mut updated = 0;
+    for entry in &pending_entries {
+        let receipt = match get_deploy_receipt(&entry.deploy_hash).await {
+            Ok(receipt) => receipt,
+            Err(err) => {
+                log::warn!("{err}");
+                continue;
+            }
+        };
+
+        let status = receipt["status"].as_str().unwrap_or(DEPLOY_STATUS_PENDING);
+        if status == DEPLOY_STATUS_PENDING {
+            log::info!("Deploy {} still pending", entry.deploy_hash);
+            continue;
+        }
+
+        let cost = receipt
+            .get("cost_motes")
+            .and_then(|cost| cost.as_str())
+            .map(str::to_string);
+        let error_message = if status == DEPLOY_STATUS_FAILURE {
+            receipt
+                .get("error_message")
+                .and_then(|error_message| error_message.as_str())
+                .map(str::to_string)
+        } else {
+            None
+        };
+
+        record_status(&entry.deploy_hash, status, cost, error_message);
+        log::info!("Deploy {} {}", entry.deploy_hash, status);
+        updated += 1;
+    }
+
+    log::info!(
+        "Reconciled {} of {} pending deploys",
+        updated,
+        pending_entries.len()
+    );
+}
@@ -0,0 +1,390 @@
+use crate::{
+    commands::{
+        deploy::{install_cep18_token, install_swap_contract, install_vesting_contract},
+        info::vesting_info,
+        update_times::{get_swap_times, update_times},
+    },
+    utils::{
+        assume_yes,
+        config::get_key_pair_from_vesting,
+        constants::{
+            COWL_CEP_18_TOKEN_DECIMALS, COWL_CEP_18_TOKEN_SYMBOL, DEFAULT_CEP_18_TOKEN_DECIMALS,
+        },
+        exit, get_contract_cep18_hash_keys, get_contract_swap_hash_keys,
+        get_contract_vesting_hash_keys,
+        key_ref::{parse_key_ref, parse_vesting_type},
+        prompt_yes_no, query_contract_named_value,
+    },
+};
+use casper_rust_wasm_sdk::types::key::Key;
+use cowl_vesting::enums::{EventsMode, VestingType};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{fmt, fs};
+use strum::IntoEnumIterator;
+
+/// Install arguments of the token contract. Its name comes from `COWL_CEP_18_TOKEN_NAME`, as
+/// the contract is found by name in the Installer named keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenManifest {
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: String,
+    pub events_mode: u8,
+    pub enable_mint_burn: bool,
+    pub upgrade: bool,
+}
+
+impl Default for TokenManifest {
+    fn default() -> Self {
+        Self {
+            symbol: COWL_CEP_18_TOKEN_SYMBOL.to_string(),
+            decimals: COWL_CEP_18_TOKEN_DECIMALS
+                .parse::<u8>()
+                .unwrap_or(DEFAULT_CEP_18_TOKEN_DECIMALS),
+            total_supply: "0".to_string(),
+            events_mode: EventsMode::CES as u8,
+            enable_mint_burn: true,
+            upgrade: false,
+        }
+    }
+}
+
+/// Install arguments of the vesting contract. `addresses` maps vesting types to the key
+/// receiving their allocation, the configured key of the vesting type by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VestingManifest {
+    pub events_mode: u8,
+    pub addresses: IndexMap<String, String>,
+    pub upgrade: bool,
+}
+
+impl Default for VestingManifest {
+    fn default() -> Self {
+        Self {
+            events_mode: EventsMode::CES as u8,
+            addresses: IndexMap::new(),
+            upgrade: false,
+        }
+    }
+}
+
+/// Install arguments of the swap contract, with the swap window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SwapManifest {
+    pub events_mode: u8,
+    pub start_time: u64,
+    pub duration: u64,
+    pub upgrade: bool,
+}
+
+impl Default for SwapManifest {
+    fn default() -> Self {
+        Self {
+            events_mode: EventsMode::CES as u8,
+            start_time: 0,
+            duration: 1,
+            upgrade: false,
+        }
+    }
+}
+
+/// Desired state of the contracts, a missing section leaves its contract untouched.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Manifest {
+    pub token: Option<TokenManifest>,
+    pub vesting: Option<VestingManifest>,
+    pub swap: Option<SwapManifest>,
+}
+
+pub fn load_manifest(path: &str) -> Result<Manifest, String> {
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("Failed to read {path}: {err}"))?;
+    serde_json::from_str(&content).map_err(|err| format!("Invalid manifest {path}: {err}"))
+}
+
+/// Resolves the vesting address of every vesting type, as formatted account hashes.
+pub async fn resolve_vesting_addresses(
+    vesting: &VestingManifest,
+) -> Result<IndexMap<String, String>, String> {
+    // Vesting types are matched case-insensitively
+    let mut overrides = IndexMap::new();
+    for (vesting_type, address) in &vesting.addresses {
+        overrides.insert(parse_vesting_type(vesting_type)?, address);
+    }
+
+    let mut addresses = IndexMap::new();
+    for vesting_type in VestingType::iter().map(|vesting_type| vesting_type.to_string()) {
+        let key = match overrides.get(&vesting_type) {
+            Some(address) => parse_key_ref(address)?.to_key().await?,
+            None => match get_key_pair_from_vesting(&vesting_type).await {
+                Some(key_pair) => Key::from_account(key_pair.public_key.to_account_hash()),
+                None => continue,
+            },
+        };
+        addresses.insert(vesting_type, key.to_formatted_string());
+    }
+    Ok(addresses)
+}
+
+/// A change needed to bring the contracts to the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanAction {
+    InstallToken,
+    UpgradeToken,
+    InstallVesting,
+    UpgradeVesting,
+    InstallSwap,
+    UpgradeSwap,
+    UpdateSwapTimes { start_time: u64, duration: u64 },
+}
+
+impl fmt::Display for PlanAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanAction::InstallToken => write!(f, "install token contract"),
+            PlanAction::UpgradeToken => write!(f, "upgrade token contract"),
+            PlanAction::InstallVesting => write!(f, "install vesting contract"),
+            PlanAction::UpgradeVesting => write!(f, "upgrade vesting contract"),
+            PlanAction::InstallSwap => write!(f, "install swap contract"),
+            PlanAction::UpgradeSwap => write!(f, "upgrade swap contract"),
+            PlanAction::UpdateSwapTimes {
+                start_time,
+                duration,
+            } => write!(
+                f,
+                "call update_times with start time {start_time} and duration {duration}"
+            ),
+        }
+    }
+}
+
+/// Actions to apply in order, and differences an upgrade or a call cannot change.
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub actions: Vec<PlanAction>,
+    pub drifts: Vec<String>,
+}
+
+// Parsed named value as text, flags being stored as 0 or 1 and large numbers as strings
+fn named_value_to_string(value: &Value) -> String {
+    match value {
+        Value::Bool(flag) => u8::from(*flag).to_string(),
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Compares named values of a contract with the manifest, each difference being a drift.
+async fn plan_named_values(
+    contract: &str,
+    contract_hash: &str,
+    expected: &[(&str, Value)],
+    plan: &mut Plan,
+) {
+    for (name, expected_value) in expected {
+        let expected_value = named_value_to_string(expected_value);
+        match query_contract_named_value(contract_hash, name).await {
+            Some(value) if named_value_to_string(&value) == expected_value => {}
+            Some(value) => plan.drifts.push(format!(
+                "{contract} {name} is {} on chain, {expected_value} in the manifest",
+                named_value_to_string(&value)
+            )),
+            None => plan
+                .drifts
+                .push(format!("{contract} {name} could not be read on chain")),
+        }
+    }
+}
+
+async fn plan_token(token: &TokenManifest, plan: &mut Plan) {
+    let Some((contract_hash, _)) = get_contract_cep18_hash_keys().await else {
+        plan.actions.push(PlanAction::InstallToken);
+        return;
+    };
+    if token.upgrade {
+        plan.actions.push(PlanAction::UpgradeToken);
+    }
+
+    // Metadata and modalities are set at install, an upgrade keeps them. The total supply
+    // also drifts once tokens are minted or burnt.
+    let expected = [
+        ("symbol", json!(token.symbol)),
+        ("decimals", json!(token.decimals)),
+        ("total_supply", json!(token.total_supply)),
+        ("events_mode", json!(token.events_mode)),
+        ("enable_mint_burn", json!(token.enable_mint_burn)),
+    ];
+    plan_named_values("token", &contract_hash, &expected, plan).await;
+}
+
+async fn plan_vesting(vesting: &VestingManifest, plan: &mut Plan) -> Result<(), String> {
+    let addresses = resolve_vesting_addresses(vesting).await?;
+    let Some((contract_hash, _)) = get_contract_vesting_hash_keys().await else {
+        plan.actions.push(PlanAction::InstallVesting);
+        return Ok(());
+    };
+    if vesting.upgrade {
+        plan.actions.push(PlanAction::UpgradeVesting);
+    }
+
+    let expected = [("events_mode", json!(vesting.events_mode))];
+    plan_named_values("vesting", &contract_hash, &expected, plan).await;
+
+    // Vesting addresses are set at install, the vesting info shows the address of each type
+    for (vesting_type, address) in addresses {
+        let Ok(vesting_type_enum) = VestingType::try_from(vesting_type.as_str()) else {
+            continue;
+        };
+        let Some(info) = vesting_info(vesting_type_enum, false, None).await else {
+            plan.drifts.push(format!(
+                "{vesting_type} vesting info could not be read on chain"
+            ));
+            continue;
+        };
+        let on_chain_address = info
+            .vesting_address_key
+            .map(|key| key.to_formatted_string())
+            .unwrap_or_default();
+        if on_chain_address != address {
+            plan.drifts.push(format!(
+                "{vesting_type} vesting address is {on_chain_address} on chain, {address} in the manifest, it can only be set at install"
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn plan_swap(swap: &SwapManifest, plan: &mut Plan) {
+    let Some((contract_hash, _)) = get_contract_swap_hash_keys().await else {
+        plan.actions.push(PlanAction::InstallSwap);
+        return;
+    };
+    if swap.upgrade {
+        plan.actions.push(PlanAction::UpgradeSwap);
+    }
+
+    let expected = [("events_mode", json!(swap.events_mode))];
+    plan_named_values("swap", &contract_hash, &expected, plan).await;
+
+    let expected_times = (swap.start_time, swap.start_time + swap.duration);
+    if get_swap_times().await != Some(expected_times) {
+        plan.actions.push(PlanAction::UpdateSwapTimes {
+            start_time: swap.start_time,
+            duration: swap.duration,
+        });
+    }
+}
+
+/// Diffs the manifest against the contracts installed by the Installer.
+pub async fn plan(manifest: &Manifest) -> Result<Plan, String> {
+    let mut plan = Plan::default();
+    if let Some(token) = &manifest.token {
+        plan_token(token, &mut plan).await;
+    }
+    if let Some(vesting) = &manifest.vesting {
+        plan_vesting(vesting, &mut plan).await?;
+    }
+    if let Some(swap) = &manifest.swap {
+        plan_swap(swap, &mut plan).await;
+    }
+    Ok(plan)
+}
+
+fn plan_to_json(manifest_path: &str, plan: &Plan) -> IndexMap<String, Value> {
+    let mut output = IndexMap::new();
+    output.insert("manifest".to_string(), json!(manifest_path));
+    output.insert(
+        "actions".to_string(),
+        json!(plan
+            .actions
+            .iter()
+            .map(|action| action.to_string())
+            .collect::<Vec<_>>()),
+    );
+    output.insert("drifts".to_string(), json!(plan.drifts));
+    output
+}
+
+async fn plan_or_exit(manifest_path: &str) -> (Manifest, Plan) {
+    let result = match load_manifest(manifest_path) {
+        Ok(manifest) => plan(&manifest).await.map(|plan| (manifest, plan)),
+        Err(err) => Err(err),
+    };
+    result.unwrap_or_else(|err| {
+        log::error!("{err}");
+        exit(1)
+    })
+}
+
+pub async fn print_plan(manifest_path: String) {
+    let (_, plan) = plan_or_exit(&manifest_path).await;
+    for drift in &plan.drifts {
+        log::warn!("{drift}");
+    }
+    let json_output = serde_json::to_string_pretty(&plan_to_json(&manifest_path, &plan)).unwrap();
+    log::info!("\n{}", json_output);
+}
+
+/// Applies the actions of the plan in order, after a single confirmation.
+pub async fn apply(manifest_path: String) {
+    let (manifest, plan) = plan_or_exit(&manifest_path).await;
+    for drift in &plan.drifts {
+        log::warn!("{drift}");
+    }
+    if plan.actions.is_empty() {
+        log::info!("Contracts are up to date with {manifest_path}");
+        return;
+    }
+
+    let json_output = serde_json::to_string_pretty(&plan_to_json(&manifest_path, &plan)).unwrap();
+    log::info!("\n{}", json_output);
+    if !prompt_yes_no(&format!(
+        "Please confirm applying {} actions?",
+        plan.actions.len()
+    )) {
+        log::warn!("Apply aborted.");
+        return;
+    }
+    // The actions are confirmed as a whole, until the end of the apply
+    let _assume_yes_guard = assume_yes();
+
+    for action in &plan.actions {
+        log::info!("Applying: {action}");
+        let result = match action {
+            PlanAction::InstallToken | PlanAction::UpgradeToken => {
+                let token = manifest.token.clone().unwrap_or_default();
+                install_cep18_token(&token, *action == PlanAction::UpgradeToken).await
+            }
+            PlanAction::InstallVesting | PlanAction::UpgradeVesting => {
+                let vesting = manifest.vesting.clone().unwrap_or_default();
+                install_vesting_contract(&vesting, *action == PlanAction::UpgradeVesting).await
+            }
+            PlanAction::InstallSwap | PlanAction::UpgradeSwap => {
+                let swap = manifest.swap.clone().unwrap_or_default();
+                install_swap_contract(&swap, *action == PlanAction::UpgradeSwap).await
+            }
+            PlanAction::UpdateSwapTimes {
+                start_time,
+                duration,
+            } => {
+                update_times(start_time.to_string(), duration.to_string()).await;
+                Ok(())
+            }
+        };
+        if let Err(err) = result {
+            log::error!("Failed to {action}: {err}");
+            exit(1)
+        }
+    }
+
+    log::info!(
+        "Applied {} actions from {manifest_path}",
+        plan.actions.len()
+    );
+}
@@ -1,18 +1,34 @@
 pub mod addresses;
 pub mod allowance;
+pub mod allowances;
 pub mod balance;
+pub mod bootstrap;
+pub mod completions;
+pub mod contacts;
+pub mod contracts;
 pub mod cowl_to_cspr;
 pub mod cspr_to_cowl;
 pub mod deploy;
+pub mod deploy_status;
 pub mod deposit_cowl;
 pub mod deposit_cspr;
+pub mod events;
 pub mod fund;
+pub mod history;
 pub mod info;
+pub mod manifest;
+pub mod modalities;
+pub mod preflight;
+pub mod shell;
+pub mod snapshot;
 pub mod status;
 pub mod swap_balance;
 pub mod transfer;
 pub mod transfer_from;
+pub mod transfer_history;
 pub mod update_times;
 pub mod upgrade_events;
+pub mod verification;
+pub mod wasm;
 pub mod withdraw_cowl;
 pub mod withdraw_cspr;
@@ -0,0 +1,127 @@
+use crate::{
+    commands::contracts::{
+        get_contract_hash_keys_of, CONTRACT_SWAP, CONTRACT_TOKEN, CONTRACT_VESTING,
+    },
+    utils::{
+        call_set_modalities_entry_point, constants::ARG_ENABLE_MINT_BURN, exit, prompt_yes_no,
+        query_contract_named_value,
+    },
+};
+use cowl_vesting::{constants::ARG_EVENTS_MODE, enums::EventsMode};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+
+pub const EVENTS_MODE_NO_EVENTS: &str = "NoEvents";
+pub const EVENTS_MODE_CES: &str = "CES";
+
+fn events_mode_from_name(name: &str) -> EventsMode {
+    if name.eq_ignore_ascii_case(EVENTS_MODE_NO_EVENTS) {
+        EventsMode::NoEvents
+    } else {
+        EventsMode::CES
+    }
+}
+
+fn events_mode_name(value: &Value) -> Value {
+    match value.as_u64() {
+        Some(mode) if mode == EventsMode::NoEvents as u64 => json!(EVENTS_MODE_NO_EVENTS),
+        Some(mode) if mode == EventsMode::CES as u64 => json!(EVENTS_MODE_CES),
+        _ => value.clone(),
+    }
+}
+
+/// Reads the modalities of a contract from its named keys.
+pub async fn get_modalities(contract: &str) -> Option<IndexMap<String, Value>> {
+    let (contract_hash, _) = get_contract_hash_keys_of(contract).await?;
+
+    let mut modalities = IndexMap::new();
+    let events_mode = query_contract_named_value(&contract_hash, ARG_EVENTS_MODE)
+        .await
+        .unwrap_or(Value::Null);
+    modalities.insert(ARG_EVENTS_MODE.to_string(), events_mode_name(&events_mode));
+    if contract == CONTRACT_TOKEN {
+        let enable_mint_burn = query_contract_named_value(&contract_hash, ARG_ENABLE_MINT_BURN)
+            .await
+            .unwrap_or(Value::Null);
+        modalities.insert(ARG_ENABLE_MINT_BURN.to_string(), enable_mint_burn);
+    }
+    Some(modalities)
+}
+
+pub async fn print_modalities(maybe_contract: Option<String>) {
+    let contracts = match &maybe_contract {
+        Some(contract) => vec![contract.as_str()],
+        None => vec![CONTRACT_TOKEN, CONTRACT_VESTING, CONTRACT_SWAP],
+    };
+
+    let mut modalities_map: IndexMap<String, Value> = IndexMap::new();
+    for contract in contracts {
+        let modalities = match get_modalities(contract).await {
+            Some(modalities) => json!(modalities),
+            None => {
+                log::warn!("The {contract} contract does not exist in installer named keys");
+                Value::Null
+            }
+        };
+        modalities_map.insert(contract.to_string(), modalities);
+    }
+
+    let json_output = serde_json::to_string_pretty(&modalities_map).unwrap();
+    log::info!("\n{}", json_output);
+}
+
+/// Calls `set_modalities` on a contract without redeploying it.
+pub async fn set_modalities(
+    contract: String,
+    maybe_events_mode: Option<String>,
+    maybe_enable_mint_burn: Option<bool>,
+) {
+    if maybe_enable_mint_burn.is_some() && contract != CONTRACT_TOKEN {
+        log::error!("Only the token contract supports {ARG_ENABLE_MINT_BURN}");
+        exit(1)
+    }
+    let Some((contract_hash, contract_package_hash)) = get_contract_hash_keys_of(&contract).await
+    else {
+        log::error!("The {contract} contract does not exist in installer named keys");
+        exit(1)
+    };
+
+    let mut changes = vec![];
+    if let Some(events_mode) = &maybe_events_mode {
+        changes.push(format!("{ARG_EVENTS_MODE} to {events_mode}"));
+    }
+    if let Some(enable_mint_burn) = maybe_enable_mint_burn {
+        changes.push(format!("{ARG_ENABLE_MINT_BURN} to {enable_mint_burn}"));
+    }
+    let answer = prompt_yes_no(&format!(
+        "Please confirm setting {} on the {} contract {}?",
+        changes.join(" and "),
+        contract,
+        contract_hash
+    ));
+    if !answer {
+        log::warn!("Set modalities aborted.");
+        return;
+    }
+
+    let maybe_events_mode = maybe_events_mode.as_deref().map(events_mode_from_name);
+    match call_set_modalities_entry_point(
+        &contract_package_hash,
+        maybe_events_mode,
+        maybe_enable_mint_burn,
+    )
+    .await
+    {
+        (success_message, _) if !success_message.is_empty() => {
+            log::info!("Modalities of the {contract} contract set");
+            if let Some(modalities) = get_modalities(&contract).await {
+                let json_output = serde_json::to_string_pretty(&modalities).unwrap();
+                log::info!("\n{}", json_output);
+            }
+        }
+        _ => {
+            log::error!("Failed to set modalities of the {contract} contract");
+            exit(1)
+        }
+    }
+}
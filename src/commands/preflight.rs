@@ -0,0 +1,425 @@
+use crate::{
+    commands::{
+        allowance::query_allowance,
+        balance::{query_balance, query_cspr_balance},
+        contracts::{
+            contract_wasm_name, get_contract_hash_keys_of, CONTRACT_TOKEN, CONTRACT_VESTING,
+        },
+    },
+    utils::{
+        config::get_key_pair_from_vesting,
+        constants::{
+            COWL_CEP_18_INSTALL_PAYMENT_AMOUNT, COWL_CEP_18_TOKEN_SYMBOL,
+            COWL_SWAP_INSTALL_PAYMENT_AMOUNT, COWL_VESTING_UPDATE_PAYMENT_AMOUNT, INSTALLER,
+        },
+        contacts::format_key_with_alias,
+        exit, format_with_thousands_separator, get_contract, get_contract_cep18_hash_keys,
+        prompt_acknowledge, read_wasm_file,
+        wasm::{exported_functions, wasm_file_path},
+    },
+};
+use bigdecimal::num_bigint::BigUint;
+use casper_rust_wasm_sdk::{
+    helpers::motes_to_cspr,
+    types::{key::Key, public_key::PublicKey},
+};
+use cowl_vesting::constants::ARG_COWL_CEP18_CONTRACT_PACKAGE;
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::{
+    io::{Error, ErrorKind},
+    str::FromStr,
+};
+
+// Entry point of the install session, not installed on the contract
+const INSTALL_ENTRY_POINT: &str = "call";
+// Speculative execution is not exposed by the node RPC used here
+const SIMULATION_UNAVAILABLE: &str =
+    "unavailable: speculative execution is not exposed by the node RPC, only static checks were run";
+
+/// Outcome of the checks run before upgrading a contract.
+#[derive(Debug, Default)]
+pub struct PreflightReport {
+    pub report: IndexMap<String, Value>,
+    pub breaking: Vec<String>,
+    pub warnings: Vec<String>,
+    pub blocking: Vec<String>,
+}
+
+pub fn upgrade_payment_amount(contract: &str) -> &'static str {
+    match contract {
+        CONTRACT_TOKEN => COWL_CEP_18_INSTALL_PAYMENT_AMOUNT.as_str(),
+        CONTRACT_VESTING => COWL_VESTING_UPDATE_PAYMENT_AMOUNT.as_str(),
+        _ => COWL_SWAP_INSTALL_PAYMENT_AMOUNT.as_str(),
+    }
+}
+
+fn names(values: &Value) -> Vec<String> {
+    values
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value["name"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn hash_hex(formatted_hash: &str) -> String {
+    formatted_hash
+        .rsplit('-')
+        .next()
+        .unwrap_or(formatted_hash)
+        .to_lowercase()
+}
+
+fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    !needle.is_empty()
+        && haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+}
+
+/// Compares the installed contract with the wasm about to upgrade it, checks the token
+/// package it points at and the Installer balance against the payment.
+pub async fn preflight_report(
+    contract: &str,
+    module_bytes: &[u8],
+    payment_amount: &str,
+) -> PreflightReport {
+    let mut preflight = PreflightReport::default();
+    preflight
+        .report
+        .insert("contract".to_string(), json!(contract));
+    // Stated in every report, so that an acknowledgement is not given for a dry-run upgrade
+    preflight
+        .report
+        .insert("simulation".to_string(), json!(SIMULATION_UNAVAILABLE));
+    preflight
+        .warnings
+        .push(format!("Upgrade simulation is {SIMULATION_UNAVAILABLE}"));
+
+    let Some((contract_hash, contract_package_hash)) = get_contract_hash_keys_of(contract).await
+    else {
+        preflight
+            .blocking
+            .push(format!("The {contract} contract is not installed"));
+        return preflight;
+    };
+    preflight
+        .report
+        .insert("contract_hash".to_string(), json!(contract_hash));
+    let current_contract = match Key::from_formatted_str(&contract_package_hash) {
+        Ok(key) => get_contract(&key, None).await,
+        Err(_) => None,
+    };
+    let Some(current_contract) = current_contract else {
+        preflight.blocking.push(format!(
+            "Failed to retrieve contract package {contract_package_hash}"
+        ));
+        return preflight;
+    };
+
+    // Entry points are the functions exported by the new wasm
+    let current_entry_points = names(&current_contract["entry_points"]);
+    match exported_functions(module_bytes) {
+        Ok(exported) => {
+            let new_entry_points: Vec<String> = exported
+                .into_iter()
+                .filter(|name| name != INSTALL_ENTRY_POINT)
+                .collect();
+            let removed: Vec<&String> = current_entry_points
+                .iter()
+                .filter(|name| !new_entry_points.contains(name))
+                .collect();
+            let added: Vec<&String> = new_entry_points
+                .iter()
+                .filter(|name| !current_entry_points.contains(name))
+                .collect();
+            for name in &removed {
+                preflight
+                    .breaking
+                    .push(format!("Entry point {name} is removed by the new wasm"));
+            }
+            preflight.report.insert(
+                "entry_points".to_string(),
+                json!({ "removed": removed, "added": added }),
+            );
+        }
+        Err(err) => preflight
+            .blocking
+            .push(format!("Failed to parse the new wasm: {err}")),
+    }
+
+    // Named keys are created at runtime, their names are only searched in the bytes of the new
+    // wasm: a best-effort check, a name built at runtime is reported as missing
+    let unreferenced: Vec<String> = names(&current_contract["named_keys"])
+        .into_iter()
+        .filter(|name| !contains_bytes(module_bytes, name.as_bytes()))
+        .collect();
+    for name in &unreferenced {
+        preflight.warnings.push(format!(
+            "Named key {name} is not found in the bytes of the new wasm (best-effort check)"
+        ));
+    }
+    preflight.report.insert(
+        "named_keys".to_string(),
+        json!({ "not_found_in_wasm": unreferenced }),
+    );
+
+    if contract != CONTRACT_TOKEN {
+        let live_token_package = get_contract_cep18_hash_keys()
+            .await
+            .map(|(_, package_hash)| package_hash);
+        let token_package = current_contract["named_keys"]
+            .as_array()
+            .and_then(|named_keys| {
+                named_keys
+                    .iter()
+                    .find(|named_key| named_key["name"] == ARG_COWL_CEP18_CONTRACT_PACKAGE)
+            })
+            .and_then(|named_key| named_key["key"].as_str())
+            .map(str::to_string);
+        match (&token_package, &live_token_package) {
+            (Some(token_package), Some(live_token_package))
+                if hash_hex(token_package) == hash_hex(live_token_package) => {}
+            (Some(token_package), Some(live_token_package)) => preflight.breaking.push(format!(
+                "{ARG_COWL_CEP18_CONTRACT_PACKAGE} is {token_package}, the live token package is {live_token_package}"
+            )),
+            (None, _) => preflight.warnings.push(format!(
+                "{ARG_COWL_CEP18_CONTRACT_PACKAGE} is not a named key of the {contract} contract"
+            )),
+            (_, None) => preflight
+                .blocking
+                .push("The token contract is not installed".to_string()),
+        }
+        preflight.report.insert(
+            "token_package".to_string(),
+            json!({ "contract": token_package, "live": live_token_package }),
+        );
+    }
+
+    let Some(key_pair) = get_key_pair_from_vesting(INSTALLER).await else {
+        preflight
+            .blocking
+            .push(format!("No key configured for the {INSTALLER}"));
+        return preflight;
+    };
+    let installer = Key::from_account(key_pair.public_key.to_account_hash());
+    match query_cspr_balance(&installer, None).await {
+        Ok(balance_motes) => {
+            let balance = BigUint::from_str(&balance_motes).unwrap_or_default();
+            let payment = BigUint::from_str(payment_amount).unwrap_or_default();
+            if balance < payment {
+                preflight.blocking.push(format!(
+                    "Installer balance of {balance_motes} motes does not cover the payment of {payment_amount} motes"
+                ));
+            }
+            preflight.report.insert(
+                "installer_balance".to_string(),
+                json!({ "balance_motes": balance_motes, "payment_motes": payment_amount }),
+            );
+        }
+        Err(err) => {
+            preflight
+                .blocking
+                .push(format!("Could not read the Installer balance: {err}"));
+            preflight.report.insert(
+                "installer_balance".to_string(),
+                json!({ "error": err, "payment_motes": payment_amount }),
+            );
+        }
+    }
+
+    preflight
+}
+
+fn print_preflight_report(preflight: &mut PreflightReport) {
+    preflight
+        .report
+        .insert("breaking".to_string(), json!(preflight.breaking));
+    preflight
+        .report
+        .insert("blocking".to_string(), json!(preflight.blocking));
+    for warning in &preflight.warnings {
+        log::warn!("{warning}");
+    }
+    let json_output = serde_json::to_string_pretty(&preflight.report).unwrap();
+    log::info!("\n{}", json_output);
+}
+
+/// Runs the pre-flight checks before an upgrade. Breaking differences must be acknowledged,
+/// even within a confirmed plan, and blocking issues stop the upgrade. A declined
+/// acknowledgement is an `Interrupted` error, so that callers can tell it from a blocking issue.
+pub async fn preflight_upgrade(
+    contract: &str,
+    module_bytes: &[u8],
+    payment_amount: &str,
+) -> Result<(), Error> {
+    let mut preflight = preflight_report(contract, module_bytes, payment_amount).await;
+    print_preflight_report(&mut preflight);
+
+    if !preflight.blocking.is_empty() {
+        for issue in &preflight.blocking {
+            log::error!("{issue}");
+        }
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!(
+                "The {contract} upgrade is blocked by {} pre-flight issues",
+                preflight.blocking.len()
+            ),
+        ));
+    }
+    if preflight.breaking.is_empty() {
+        return Ok(());
+    }
+    for difference in &preflight.breaking {
+        log::warn!("Breaking: {difference}");
+    }
+    let acknowledged = prompt_acknowledge(
+        &format!(
+            "The {contract} upgrade has {} breaking differences.",
+            preflight.breaking.len()
+        ),
+        contract,
+    );
+    if !acknowledged {
+        return Err(Error::new(
+            ErrorKind::Interrupted,
+            format!("The breaking differences of the {contract} upgrade were not acknowledged"),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn print_preflight(contract: &str) {
+    let path = wasm_file_path(contract_wasm_name(contract));
+    let module_bytes = match read_wasm_file(&path) {
+        Ok(module_bytes) => module_bytes,
+        Err(err) => {
+            log::error!("Error reading file {}: {:?}", path, err);
+            exit(1)
+        }
+    };
+
+    let mut preflight =
+        preflight_report(contract, &module_bytes, upgrade_payment_amount(contract)).await;
+    print_preflight_report(&mut preflight);
+    if !preflight.blocking.is_empty() || !preflight.breaking.is_empty() {
+        exit(1)
+    }
+}
+
+fn format_token_amount(amount: &str) -> String {
+    format!(
+        "{} {}",
+        format_with_thousands_separator(&motes_to_cspr(amount).unwrap_or_default()),
+        *COWL_CEP_18_TOKEN_SYMBOL
+    )
+}
+
+fn format_cspr_amount(motes: &str) -> String {
+    format!(
+        "{} CSPR",
+        format_with_thousands_separator(&motes_to_cspr(motes).unwrap_or_default())
+    )
+}
+
+fn parse_amount(amount: &str) -> Result<BigUint, String> {
+    BigUint::from_str(amount).map_err(|_| format!("Invalid amount {amount}"))
+}
+
+/// Checks that an owner holds the tokens about to be moved.
+pub async fn check_token_balance(owner: &Key, amount: &str) -> Result<(), String> {
+    let required = parse_amount(amount)?;
+    let balance = query_balance(None, Some(owner.clone()), None)
+        .await
+        .map_err(|err| {
+            format!(
+                "Could not read the balance of {}: {err}",
+                format_key_with_alias(owner)
+            )
+        })?;
+    if BigUint::from_str(&balance).unwrap_or_default() < required {
+        return Err(format!(
+            "{} holds {}, {} are required",
+            format_key_with_alias(owner),
+            format_token_amount(&balance),
+            format_token_amount(amount)
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the allowance of a spender over an owner's tokens covers an amount.
+pub async fn check_allowance(owner: &Key, spender: &Key, amount: &str) -> Result<(), String> {
+    let required = parse_amount(amount)?;
+    // Only an allowance never set reads as zero, any other error fails the check
+    let allowance = query_allowance(owner, spender, None).await.map_err(|err| {
+        format!(
+            "Could not read the allowance of {} over the tokens of {}: {err}",
+            format_key_with_alias(spender),
+            format_key_with_alias(owner)
+        )
+    })?;
+    if BigUint::from_str(&allowance).unwrap_or_default() < required {
+        return Err(format!(
+            "Allowance of {} over the tokens of {} is {}, {} are required",
+            format_key_with_alias(spender),
+            format_key_with_alias(owner),
+            format_token_amount(&allowance),
+            format_token_amount(amount)
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the signer of a deploy can pay for it, and for the motes it sends along.
+pub async fn check_cspr_balance(
+    signer: &PublicKey,
+    payment_amount: &str,
+    maybe_value: Option<&str>,
+) -> Result<(), String> {
+    let mut required = parse_amount(payment_amount)?;
+    if let Some(value) = maybe_value {
+        required += parse_amount(value)?;
+    }
+    let key = Key::from_account(signer.to_account_hash());
+    let balance_motes = query_cspr_balance(&key, None).await.map_err(|err| {
+        format!(
+            "Could not read the CSPR balance of {}: {err}",
+            format_key_with_alias(&key)
+        )
+    })?;
+    if BigUint::from_str(&balance_motes).unwrap_or_default() < required {
+        let purpose = match maybe_value {
+            Some(value) => format!(
+                "the payment of {} and the {} sent",
+                format_cspr_amount(payment_amount),
+                format_cspr_amount(value)
+            ),
+            None => format!("the payment of {}", format_cspr_amount(payment_amount)),
+        };
+        return Err(format!(
+            "{} holds {}, not enough for {}",
+            format_key_with_alias(&key),
+            format_cspr_amount(&balance_motes),
+            purpose
+        ));
+    }
+    Ok(())
+}
+
+/// Stops before signing when a check failed, logging the reason of every failure.
+pub fn abort_on_failed_checks(checks: Vec<Result<(), String>>) {
+    let failures: Vec<String> = checks.into_iter().filter_map(Result::err).collect();
+    if failures.is_empty() {
+        return;
+    }
+    for failure in &failures {
+        log::error!("Pre-flight check failed: {failure}");
+    }
+    exit(1)
+}
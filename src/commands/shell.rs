@@ -0,0 +1,282 @@
+use crate::{
+    cli::{run_command, Cli, Commands},
+    commands::completions::{key_ref_names, FLAGS_KEY_REF, FLAG_VESTING_TYPE},
+    utils::{
+        config, constants::SHELL_HISTORY_FILE_PATH, journal, registry, set_assume_yes,
+        set_in_shell, CommandExit,
+    },
+};
+use clap::{Arg, CommandFactory, Parser};
+use cowl_vesting::enums::VestingType;
+use futures::FutureExt;
+use rustyline::{
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context, Editor, Helper,
+};
+use std::{iter, panic::AssertUnwindSafe};
+use strum::IntoEnumIterator;
+
+const SHELL_PROMPT: &str = "cowl> ";
+const SHELL_BINARY_NAME: &str = "cowl-cli";
+const SHELL_RELOAD: &str = "reload";
+const SHELL_EXIT: [&str; 2] = ["exit", "quit"];
+
+/// Completes the subcommands and flags of the command typed so far, and the values of its
+/// flags: vesting types, account names, contact aliases or the accepted values.
+struct ShellHelper {
+    command: clap::Command,
+    key_refs: Vec<String>,
+}
+
+impl ShellHelper {
+    fn new() -> Self {
+        let mut command = Cli::command();
+        // Global flags are only propagated to the subcommands once built
+        command.build();
+        Self {
+            command,
+            key_refs: key_ref_names(),
+        }
+    }
+
+    fn flag_values(&self, argument: &Arg) -> Vec<String> {
+        match argument.get_long() {
+            Some(FLAG_VESTING_TYPE) => VestingType::iter()
+                .map(|vesting_type| vesting_type.to_string())
+                .collect(),
+            Some(long) if FLAGS_KEY_REF.contains(&long) => self.key_refs.clone(),
+            _ => argument
+                .get_possible_values()
+                .iter()
+                .map(|value| value.get_name().to_string())
+                .collect(),
+        }
+    }
+
+    fn candidates(&self, arguments: &[String]) -> Vec<String> {
+        let mut command = &self.command;
+        for argument in arguments {
+            if let Some(subcommand) = command.find_subcommand(argument) {
+                command = subcommand;
+            }
+        }
+
+        // The word after a flag taking a value is its value
+        let previous_flag = arguments
+            .last()
+            .and_then(|argument| argument.strip_prefix("--"))
+            .and_then(|long| {
+                command
+                    .get_arguments()
+                    .find(|argument| argument.get_long() == Some(long))
+            });
+        if let Some(flag) = previous_flag.filter(|flag| flag.get_action().takes_values()) {
+            return self.flag_values(flag);
+        }
+
+        let mut words = vec![];
+        if arguments.is_empty() {
+            words.push(SHELL_RELOAD.to_string());
+            words.extend(SHELL_EXIT.iter().map(|word| word.to_string()));
+        }
+        words.extend(
+            command
+                .get_subcommands()
+                .map(|subcommand| subcommand.get_name().to_string()),
+        );
+        words.extend(
+            command
+                .get_arguments()
+                .filter_map(|argument| argument.get_long())
+                .map(|long| format!("--{long}")),
+        );
+        words
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos]
+            .rfind(char::is_whitespace)
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let prefix = &line[start..pos];
+        // An unclosed quote is completed like the words before it
+        let arguments = split_line(&line[..start]).unwrap_or_else(|_| {
+            line[..start]
+                .split_whitespace()
+                .map(str::to_string)
+                .collect()
+        });
+        let mut words = self.candidates(&arguments);
+        words.sort();
+        words.dedup();
+        let candidates = words
+            .into_iter()
+            .filter(|word| word.starts_with(prefix))
+            .map(|word| Pair {
+                display: word.clone(),
+                replacement: word,
+            })
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Splits a line into arguments, keeping quoted arguments together.
+fn split_line(line: &str) -> Result<Vec<String>, String> {
+    let mut arguments = vec![];
+    let mut argument = String::new();
+    let mut in_argument = false;
+    let mut quote: Option<char> = None;
+    for character in line.chars() {
+        match (quote, character) {
+            (Some(open), character) if character == open => quote = None,
+            (Some(_), character) => argument.push(character),
+            (None, '"' | '\'') => {
+                quote = Some(character);
+                in_argument = true;
+            }
+            (None, character) if character.is_whitespace() => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut argument));
+                    in_argument = false;
+                }
+            }
+            (None, character) => {
+                argument.push(character);
+                in_argument = true;
+            }
+        }
+    }
+    if let Some(open) = quote {
+        return Err(format!("Missing closing {open}"));
+    }
+    if in_argument {
+        arguments.push(argument);
+    }
+    Ok(arguments)
+}
+
+async fn reload() {
+    config::init().await;
+    registry::clear_latest();
+    log::info!("Keys and contract hashes reloaded");
+}
+
+async fn run_line(line: &str) {
+    let arguments = match split_line(line) {
+        Ok(arguments) => arguments,
+        Err(err) => {
+            log::error!("{err}");
+            return;
+        }
+    };
+    let cli = match Cli::try_parse_from(
+        iter::once(SHELL_BINARY_NAME.to_string()).chain(arguments.iter().cloned()),
+    ) {
+        Ok(cli) => cli,
+        Err(err) => {
+            let _ = err.print();
+            return;
+        }
+    };
+    if matches!(cli.command, Commands::Shell) {
+        log::warn!("Already in the shell");
+        return;
+    }
+
+    // State set by a previous command must not leak into this one
+    journal::set_current_command(arguments);
+    journal::set_memo(None);
+    set_assume_yes(false);
+
+    // A command ending early unwinds to here instead of ending the shell
+    let result = AssertUnwindSafe(Box::pin(run_command(cli)))
+        .catch_unwind()
+        .await;
+    if let Err(payload) = result {
+        match payload.downcast::<CommandExit>() {
+            Ok(command_exit) if command_exit.0 == 0 => {}
+            Ok(command_exit) => log::error!("Command failed with exit code {}", command_exit.0),
+            Err(_) => log::error!("Command panicked"),
+        }
+    }
+}
+
+/// Runs commands read from a prompt with history and completion. Keys, the SDK and contract
+/// hashes stay cached until `reload`. A command failing with an error is reported and the
+/// shell reads the next one.
+pub async fn shell() {
+    let mut editor: Editor<ShellHelper, DefaultHistory> = match Editor::new() {
+        Ok(editor) => editor,
+        Err(err) => {
+            log::error!("Failed to start the shell: {err}");
+            return;
+        }
+    };
+    editor.set_helper(Some(ShellHelper::new()));
+    let _ = editor.load_history(SHELL_HISTORY_FILE_PATH.as_str());
+    set_in_shell(true);
+    log::info!(
+        "Type commands without {SHELL_BINARY_NAME}, `{SHELL_RELOAD}` to refresh keys and contract hashes, `exit` to quit"
+    );
+
+    loop {
+        let line = match editor.readline(SHELL_PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                log::error!("Failed to read the command: {err}");
+                break;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+        if let Err(err) = editor.save_history(SHELL_HISTORY_FILE_PATH.as_str()) {
+            log::warn!(
+                "Failed to save the shell history {}: {}",
+                *SHELL_HISTORY_FILE_PATH,
+                err
+            );
+        }
+
+        match line {
+            _ if SHELL_EXIT.contains(&line) => break,
+            SHELL_RELOAD => reload().await,
+            _ => run_line(line).await,
+        }
+
+        // Contacts may have been added or removed
+        if let Some(helper) = editor.helper_mut() {
+            helper.key_refs = key_ref_names();
+        }
+    }
+    set_in_shell(false);
+}
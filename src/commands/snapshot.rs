@@ -0,0 +1,254 @@
+use crate::{
+    commands::{
+        balance::query_balance,
+        history::{csv_field, EXPORT_FORMAT_CSV, EXPORT_FORMAT_JSON},
+    },
+    utils::{
+        constants::{BALANCE_QUERY_CONCURRENCY, COWL_CEP_18_TOKEN_SYMBOL},
+        events::{events_start_height, get_event_sources, scan_events, EVENT_SOURCE_TOKEN},
+        exit, format_with_thousands_separator, get_block, get_contract_cep18_hash_keys_at, sdk,
+        stored_value_to_parsed_string,
+    },
+};
+use bigdecimal::num_bigint::BigUint;
+use casper_rust_wasm_sdk::{
+    helpers::motes_to_cspr,
+    rpcs::query_global_state::{KeyIdentifierInput, PathIdentifierInput, QueryGlobalStateParams},
+    types::key::Key,
+};
+use futures::{stream, StreamExt};
+use indexmap::IndexMap;
+use serde_json::{json, to_string, Value};
+use std::{collections::BTreeSet, fs, str::FromStr};
+
+const NAMED_KEY_TOTAL_SUPPLY: &str = "total_supply";
+
+// Event fields holding an account that received or held tokens
+const HOLDER_FIELDS: [&str; 3] = ["recipient", "sender", "owner"];
+
+async fn get_total_supply(contract_hash: &str, state_root_hash: &str) -> Option<String> {
+    let query_params = QueryGlobalStateParams {
+        key: KeyIdentifierInput::String(contract_hash.to_string()),
+        path: Some(PathIdentifierInput::String(
+            NAMED_KEY_TOTAL_SUPPLY.to_string(),
+        )),
+        maybe_global_state_identifier: None,
+        state_root_hash: Some(state_root_hash.to_string()),
+        maybe_block_id: None,
+        node_address: None,
+        verbosity: None,
+    };
+
+    let query_contract_key = match sdk().query_contract_key(query_params).await {
+        Ok(query_contract_key) => query_contract_key,
+        Err(err) => {
+            log::error!("Failed to query total supply: {:?}", err);
+            return None;
+        }
+    };
+    let json_string = to_string(&query_contract_key.result.stored_value).ok()?;
+    stored_value_to_parsed_string(&json_string)
+}
+
+/// Lists the accounts that ever held tokens up to a block, with their balance and the total
+/// supply at the state root hash of that block. At a given state root hash, the accounts are
+/// found up to the latest block, those holding no tokens at that state being left out.
+pub async fn get_holders_snapshot(
+    maybe_block_identifier: Option<&str>,
+    maybe_state_root_hash: Option<&str>,
+) -> Result<IndexMap<String, Value>, String> {
+    let block = match maybe_state_root_hash {
+        Some(_) => get_block(None).await,
+        None => get_block(maybe_block_identifier).await,
+    }
+    .ok_or_else(|| "Failed to retrieve the snapshot block".to_string())?;
+
+    let block_height = block["header"]["height"]
+        .as_u64()
+        .ok_or_else(|| "Missing block height".to_string())?;
+    let state_root_hash = match maybe_state_root_hash {
+        Some(state_root_hash) => state_root_hash.to_string(),
+        None => block["header"]["state_root_hash"]
+            .as_str()
+            .ok_or_else(|| "Missing state root hash".to_string())?
+            .to_string(),
+    };
+
+    let (cowl_cep18_token_contract_hash, _) =
+        get_contract_cep18_hash_keys_at(Some(&state_root_hash))
+            .await
+            .ok_or_else(|| "Failed to retrieve contract token hash.".to_string())?;
+
+    let event_sources: Vec<_> = get_event_sources()
+        .await
+        .into_iter()
+        .filter(|source| source.label == EVENT_SOURCE_TOKEN)
+        .collect();
+    if event_sources.is_empty() {
+        return Err("Events are not enabled on the token contract".to_string());
+    }
+
+    let from_height = events_start_height(&event_sources).await?;
+    let mut holders = BTreeSet::new();
+    for block_event in scan_events(from_height, block_height, &event_sources).await? {
+        for field in HOLDER_FIELDS {
+            if let Some(key) = block_event.event.fields.get(field) {
+                holders.insert(key.clone());
+            }
+        }
+    }
+    log::info!("Reading the balances of {} accounts", holders.len());
+
+    // Holder balances are fetched concurrently, in holder order
+    let holder_balances: Vec<(String, Result<String, String>)> = stream::iter(holders)
+        .filter_map(|holder| async move {
+            match Key::from_formatted_str(&holder) {
+                Ok(key) => Some((holder, key)),
+                Err(_) => {
+                    log::warn!("Skipping invalid key {holder}");
+                    None
+                }
+            }
+        })
+        .map(|(holder, key)| {
+            let state_root_hash = state_root_hash.as_str();
+            async move {
+                let balance = query_balance(None, Some(key), Some(state_root_hash)).await;
+                (holder, balance)
+            }
+        })
+        .buffered(BALANCE_QUERY_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut balances = vec![];
+    let mut errors = vec![];
+    let mut holders_total = BigUint::default();
+    for (holder, balance) in holder_balances {
+        // A holder whose balance cannot be read is reported rather than counted as empty
+        let balance = match balance {
+            Ok(balance) => balance,
+            Err(err) => {
+                log::error!("Failed to read the balance of {holder}: {err}");
+                let mut error = IndexMap::new();
+                error.insert("key".to_string(), holder);
+                error.insert("error".to_string(), err);
+                errors.push(error);
+                continue;
+            }
+        };
+        let amount = BigUint::from_str(&balance).unwrap_or_default();
+        if amount == BigUint::default() {
+            continue;
+        }
+        holders_total += &amount;
+
+        let mut entry = IndexMap::new();
+        entry.insert("key".to_string(), holder);
+        entry.insert("balance".to_string(), balance.clone());
+        entry.insert(
+            format!("balance_{}", *COWL_CEP_18_TOKEN_SYMBOL),
+            format_with_thousands_separator(&motes_to_cspr(&balance).unwrap_or_default()),
+        );
+        balances.push(entry);
+    }
+
+    let total_supply = get_total_supply(&cowl_cep18_token_contract_hash, &state_root_hash)
+        .await
+        .unwrap_or_default();
+    let matches_total_supply = holders_total.to_string() == total_supply;
+    if !matches_total_supply {
+        log::warn!(
+            "Sum of holder balances {} differs from total supply {}",
+            holders_total,
+            total_supply
+        );
+    }
+
+    let mut snapshot = IndexMap::new();
+    match maybe_state_root_hash {
+        Some(_) => snapshot.insert("scanned_to_block_height".to_string(), json!(block_height)),
+        None => snapshot.insert("block_height".to_string(), json!(block_height)),
+    };
+    snapshot.insert("state_root_hash".to_string(), json!(state_root_hash));
+    snapshot.insert("holders_count".to_string(), json!(balances.len()));
+    snapshot.insert(
+        "holders_total".to_string(),
+        json!(holders_total.to_string()),
+    );
+    snapshot.insert("total_supply".to_string(), json!(total_supply));
+    snapshot.insert(
+        "matches_total_supply".to_string(),
+        json!(matches_total_supply),
+    );
+    snapshot.insert("errors".to_string(), json!(errors));
+    snapshot.insert("holders".to_string(), json!(balances));
+    Ok(snapshot)
+}
+
+fn holders_to_csv(snapshot: &IndexMap<String, Value>) -> String {
+    let balance_column = format!("balance_{}", *COWL_CEP_18_TOKEN_SYMBOL);
+    let mut csv = format!("key,balance,{balance_column}\n");
+    for holder in snapshot["holders"].as_array().into_iter().flatten() {
+        let fields: Vec<String> = ["key", "balance", balance_column.as_str()]
+            .iter()
+            .map(|column| csv_field(holder[column].as_str().unwrap_or_default()))
+            .collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub async fn print_holders_snapshot(
+    maybe_block_identifier: Option<String>,
+    maybe_state_root_hash: Option<String>,
+    format: String,
+    output: Option<String>,
+) {
+    let snapshot = match get_holders_snapshot(
+        maybe_block_identifier.as_deref(),
+        maybe_state_root_hash.as_deref(),
+    )
+    .await
+    {
+        Ok(snapshot) => snapshot,
+        Err(err) => {
+            log::error!("{err}");
+            exit(1)
+        }
+    };
+
+    let content = match format.as_str() {
+        EXPORT_FORMAT_JSON => serde_json::to_string_pretty(&snapshot).unwrap(),
+        EXPORT_FORMAT_CSV => holders_to_csv(&snapshot),
+        _ => {
+            log::error!(
+                "Unknown export format {format}, expected {EXPORT_FORMAT_JSON} or {EXPORT_FORMAT_CSV}"
+            );
+            exit(1)
+        }
+    };
+
+    // Totals are always reported, the holders list goes to the export
+    let mut totals = snapshot.clone();
+    totals.shift_remove("holders");
+    log::info!("\n{}", serde_json::to_string_pretty(&totals).unwrap());
+
+    match output {
+        Some(path) => {
+            if let Err(err) = fs::write(&path, content) {
+                log::error!("Failed to write {path}: {err}");
+                exit(1)
+            }
+            log::info!("Exported {} holders to {}", snapshot["holders_count"], path);
+        }
+        None => println!("{content}"),
+    }
+
+    let errors_count = snapshot["errors"].as_array().map_or(0, Vec::len);
+    if errors_count > 0 {
+        log::error!("Snapshot is incomplete, {errors_count} balances could not be read");
+        exit(1)
+    }
+}
@@ -1,5 +1,5 @@
 use crate::utils::{
-    call_vesting_entry_point, get_contract_vesting_hash_keys, get_dictionary_item_params, sdk,
+    call_vesting_entry_point, get_contract_vesting_hash_keys_at, get_dictionary_item_params, sdk,
     stored_value_to_vesting_data,
 };
 use cowl_vesting::{
@@ -12,10 +12,11 @@ use serde_json::to_string;
 pub async fn vesting_status(
     vesting_type: VestingType,
     call_entry_point: bool,
+    maybe_state_root_hash: Option<&str>,
 ) -> Option<VestingStatus> {
     // Retrieve contract vesting hash and package hash
     let (contract_vesting_hash, contract_vesting_package) =
-        match get_contract_vesting_hash_keys().await {
+        match get_contract_vesting_hash_keys_at(maybe_state_root_hash).await {
             Some((hash, package_hash)) => (hash, package_hash),
             None => {
                 log::error!("Failed to retrieve contract vesting hash and package hash.");
@@ -44,7 +45,7 @@ pub async fn vesting_status(
 
     // Query the contract dictionary for the vesting status
     let vesting_status_result = sdk()
-        .query_contract_dict(dictionary_item, None::<&str>, None, None)
+        .query_contract_dict(dictionary_item, maybe_state_root_hash, None, None)
         .await;
 
     // Handle query result and extract stored value
@@ -67,8 +68,17 @@ pub async fn vesting_status(
     stored_value_to_vesting_data(&json_string)
 }
 
-pub async fn print_vesting_status(vesting_type: VestingType, call_entry_point: bool) {
-    if let Some(vesting_status) = vesting_status(vesting_type, call_entry_point).await {
+pub async fn print_vesting_status(
+    vesting_type: VestingType,
+    call_entry_point: bool,
+    maybe_state_root_hash: Option<&str>,
+) {
+    if let Some(vesting_status) =
+        vesting_status(vesting_type, call_entry_point, maybe_state_root_hash).await
+    {
+        if let Some(state_root_hash) = maybe_state_root_hash {
+            log::info!("State root hash {}", state_root_hash);
+        }
         let json_output = serde_json::to_string_pretty(&vesting_status.to_string()).unwrap();
         log::info!("{}", json_output);
     }
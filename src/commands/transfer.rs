@@ -1,10 +1,17 @@
-use super::balance::get_balance;
+use super::{
+    balance::get_balance,
+    preflight::{abort_on_failed_checks, check_cspr_balance, check_token_balance},
+    verification::{BalanceCheck, BalanceSnapshot, ExpectedDelta},
+};
 use crate::utils::{
     call_token_transfer_entry_point,
-    constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL},
+    constants::{
+        COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL, COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT,
+    },
+    contacts::format_key_with_alias,
     format_with_thousands_separator, get_contract_cep18_hash_keys,
     keys::retrieve_private_key,
-    prompt_yes_no,
+    prompt_yes_no, warn_if_contract_cannot_spend,
 };
 use casper_rust_wasm_sdk::{
     helpers::motes_to_cspr,
@@ -21,6 +28,13 @@ pub async fn transfer(from: PublicKey, to: Key, amount: String) -> Option<String
         }
     };
 
+    warn_if_contract_cannot_spend(&to).await;
+
+    abort_on_failed_checks(vec![
+        check_token_balance(&Key::from_account(from.to_account_hash()), &amount).await,
+        check_cspr_balance(&from, &COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT, None).await,
+    ]);
+
     // Retrieve the private key
     let secret_key = retrieve_private_key(&from).await;
 
@@ -38,6 +52,19 @@ pub async fn transfer(from: PublicKey, to: Key, amount: String) -> Option<String
         return None;
     }
 
+    let sender = Key::from_account(from.to_account_hash());
+    let sent = ExpectedDelta::amount(&amount);
+    let snapshot = BalanceSnapshot::take(vec![
+        BalanceCheck::token("sender", &sender, ExpectedDelta::Exact(-&sent)),
+        BalanceCheck::token("recipient", &to, ExpectedDelta::Exact(sent)),
+        BalanceCheck::cspr(
+            "signer",
+            &sender,
+            ExpectedDelta::paying(&COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT),
+        ),
+    ])
+    .await;
+
     // Call the token transfer entry point
     call_token_transfer_entry_point(
         &cowl_cep18_token_contract_package_hash,
@@ -49,13 +76,15 @@ pub async fn transfer(from: PublicKey, to: Key, amount: String) -> Option<String
     )
     .await;
 
-    let to_balance = get_balance(None, Some(to)).await;
+    snapshot.verify().await;
+
+    let to_balance = get_balance(None, Some(to), None).await;
     Some(to_balance)
 }
 
 pub async fn print_transfer(from: PublicKey, to: Key, amount: String) {
     if let Some(balance) = transfer(from, to.clone(), amount).await {
-        log::info!("Balance for {}", to.to_formatted_string());
+        log::info!("Balance for {}", format_key_with_alias(&to));
         log::info!(
             "{} {}",
             format_with_thousands_separator(&motes_to_cspr(&balance).unwrap()),
@@ -1,10 +1,17 @@
-use super::balance::get_balance;
+use super::{
+    balance::get_balance,
+    preflight::{abort_on_failed_checks, check_allowance, check_cspr_balance, check_token_balance},
+    verification::{BalanceCheck, BalanceSnapshot, ExpectedDelta},
+};
 use crate::utils::{
     call_token_transfer_entry_point,
-    constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL},
+    constants::{
+        COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL, COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT,
+    },
+    contacts::format_key_with_alias,
     format_with_thousands_separator, get_contract_cep18_hash_keys,
     keys::retrieve_private_key,
-    prompt_yes_no,
+    prompt_yes_no, warn_if_contract_cannot_spend,
 };
 use casper_rust_wasm_sdk::{
     helpers::motes_to_cspr,
@@ -26,6 +33,19 @@ pub async fn transfer_from(
         }
     };
 
+    warn_if_contract_cannot_spend(&to).await;
+
+    abort_on_failed_checks(vec![
+        check_token_balance(&from, &amount).await,
+        check_allowance(
+            &from,
+            &Key::from_account(operator.to_account_hash()),
+            &amount,
+        )
+        .await,
+        check_cspr_balance(&operator, &COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT, None).await,
+    ]);
+
     // Retrieve the private key
     let secret_key = retrieve_private_key(&operator).await;
 
@@ -43,6 +63,20 @@ pub async fn transfer_from(
         return None;
     }
 
+    let operator_key = Key::from_account(operator.to_account_hash());
+    let sent = ExpectedDelta::amount(&amount);
+    let snapshot = BalanceSnapshot::take(vec![
+        BalanceCheck::token("owner", &from, ExpectedDelta::Exact(-&sent)),
+        BalanceCheck::token("recipient", &to, ExpectedDelta::Exact(sent.clone())),
+        BalanceCheck::allowance(&from, &operator_key, ExpectedDelta::Exact(-&sent)),
+        BalanceCheck::cspr(
+            "operator",
+            &operator_key,
+            ExpectedDelta::paying(&COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT),
+        ),
+    ])
+    .await;
+
     // Call the token transfer_from entry point
     call_token_transfer_entry_point(
         &cowl_cep18_token_contract_package_hash,
@@ -54,13 +88,15 @@ pub async fn transfer_from(
     )
     .await;
 
-    let to_balance = get_balance(None, Some(to)).await;
+    snapshot.verify().await;
+
+    let to_balance = get_balance(None, Some(to), None).await;
     Some(to_balance)
 }
 
 pub async fn print_transfer_from(operator: PublicKey, from: Key, to: Key, amount: String) {
     if let Some(balance) = transfer_from(operator, from.clone(), to.clone(), amount).await {
-        log::info!("Balance for {}", to.to_formatted_string());
+        log::info!("Balance for {}", format_key_with_alias(&to));
         log::info!(
             "{} {}",
             format_with_thousands_separator(&motes_to_cspr(&balance).unwrap()),
@@ -0,0 +1,178 @@
+use crate::{
+    commands::balance::get_balance,
+    utils::{
+        constants::COWL_CEP_18_TOKEN_SYMBOL,
+        events::{
+            events_start_height, get_event_sources, scan_events, CesEvent, EVENT_SOURCE_SWAP,
+            EVENT_SOURCE_TOKEN,
+        },
+        exit, format_with_thousands_separator, get_block,
+    },
+};
+use bigdecimal::num_bigint::BigInt;
+use casper_rust_wasm_sdk::{
+    helpers::motes_to_cspr,
+    types::{key::Key, public_key::PublicKey},
+};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// Signed balance change of `account` carried by a token event.
+fn event_delta(event: &CesEvent, account: &str) -> BigInt {
+    let amount = event
+        .fields
+        .get("amount")
+        .and_then(|amount| BigInt::from_str(amount).ok())
+        .unwrap_or_default();
+    let is_account = |field: &str| event.fields.get(field).is_some_and(|key| key == account);
+
+    let mut delta = BigInt::default();
+    match event.name.as_str() {
+        "Mint" if is_account("recipient") => delta += &amount,
+        "Burn" if is_account("owner") => delta -= &amount,
+        "Transfer" => {
+            if is_account("sender") {
+                delta -= &amount;
+            }
+            if is_account("recipient") {
+                delta += &amount;
+            }
+        }
+        "TransferFrom" => {
+            if is_account("owner") {
+                delta -= &amount;
+            }
+            if is_account("recipient") {
+                delta += &amount;
+            }
+        }
+        _ => {}
+    }
+    delta
+}
+
+fn account_hash(public_key: &str) -> Option<String> {
+    PublicKey::new(public_key)
+        .ok()
+        .map(|public_key| public_key.to_account_hash().to_formatted_string())
+}
+
+/// Reconstructs the COWL transfers, allowances and swaps of `key` between two block heights,
+/// from the install of the contracts by default, with the running balance after each event.
+pub async fn get_transfer_history(
+    key: &Key,
+    maybe_from_height: Option<u64>,
+    maybe_to_height: Option<u64>,
+) -> Result<IndexMap<String, Value>, String> {
+    let account = key.to_formatted_string();
+
+    let latest_height = get_block(None)
+        .await
+        .and_then(|block| block["header"]["height"].as_u64())
+        .ok_or_else(|| "Failed to retrieve the latest block".to_string())?;
+
+    let event_sources: Vec<_> = get_event_sources()
+        .await
+        .into_iter()
+        .filter(|source| source.label == EVENT_SOURCE_TOKEN || source.label == EVENT_SOURCE_SWAP)
+        .collect();
+    if event_sources.is_empty() {
+        return Err("No token or swap contract with events enabled".to_string());
+    }
+
+    // No event is emitted before the contracts are installed
+    let start_height = events_start_height(&event_sources).await?;
+    let from_height = maybe_from_height.unwrap_or(start_height).max(start_height);
+    let to_height = maybe_to_height.unwrap_or(latest_height).min(latest_height);
+    if from_height > to_height {
+        return Err(format!("Invalid block range {from_height} to {to_height}"));
+    }
+
+    let mut ledger = vec![];
+    let mut running_balance = BigInt::default();
+
+    for block_event in scan_events(from_height, to_height, &event_sources).await? {
+        let event = &block_event.event;
+        let involves_account = event.fields.values().any(|value| *value == account);
+        // Swap events are attributed to the account that signed the swap
+        let signed_by_account = block_event
+            .deploy_account
+            .as_deref()
+            .and_then(account_hash)
+            .is_some_and(|account_hash| account_hash == account);
+        let is_account_swap = event.source == EVENT_SOURCE_SWAP && signed_by_account;
+        if !involves_account && !is_account_swap {
+            continue;
+        }
+
+        let delta = if event.source == EVENT_SOURCE_TOKEN {
+            event_delta(event, &account)
+        } else {
+            BigInt::default()
+        };
+        running_balance += &delta;
+
+        let mut entry = IndexMap::new();
+        entry.insert("block_height".to_string(), json!(block_event.block_height));
+        entry.insert("timestamp".to_string(), block_event.timestamp.clone());
+        entry.insert("deploy_hash".to_string(), json!(block_event.deploy_hash));
+        entry.insert("source".to_string(), json!(event.source));
+        entry.insert("event".to_string(), json!(event.name));
+        entry.insert("fields".to_string(), json!(event.fields));
+        entry.insert("delta".to_string(), json!(delta.to_string()));
+        entry.insert(
+            "running_balance".to_string(),
+            json!(running_balance.to_string()),
+        );
+        ledger.push(entry);
+    }
+
+    let current_balance = get_balance(None, Some(key.clone()), None).await;
+
+    let mut history = IndexMap::new();
+    history.insert("key".to_string(), json!(account));
+    history.insert("from_block_height".to_string(), json!(from_height));
+    history.insert("to_block_height".to_string(), json!(to_height));
+    history.insert("ledger".to_string(), json!(ledger));
+    history.insert(
+        "computed_balance".to_string(),
+        json!(running_balance.to_string()),
+    );
+    history.insert("current_balance".to_string(), json!(current_balance));
+    history.insert(
+        format!("current_balance_{}", *COWL_CEP_18_TOKEN_SYMBOL),
+        json!(format_with_thousands_separator(
+            &motes_to_cspr(&current_balance).unwrap_or_default()
+        )),
+    );
+
+    // The running balance only starts from zero when scanning from the install of the contracts
+    if from_height == start_height && to_height == latest_height {
+        let consistent = running_balance.to_string() == current_balance;
+        if !consistent {
+            log::warn!(
+                "Computed balance {} differs from current balance {} for {}",
+                running_balance,
+                current_balance,
+                account
+            );
+        }
+        history.insert("consistent".to_string(), json!(consistent));
+    }
+
+    Ok(history)
+}
+
+pub async fn print_transfer_history(key: Key, from_height: Option<u64>, to_height: Option<u64>) {
+    match get_transfer_history(&key, from_height, to_height).await {
+        Ok(history) => {
+            let json_output = serde_json::to_string_pretty(&history).unwrap();
+            log::info!("\n{}", json_output);
+        }
+        Err(err) => {
+            log::error!("{err}");
+            exit(1);
+        }
+    }
+}
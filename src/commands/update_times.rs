@@ -1,5 +1,3 @@
-use std::process;
-
 use casper_rust_wasm_sdk::rpcs::query_global_state::{
     KeyIdentifierInput, PathIdentifierInput, QueryGlobalStateParams,
 };
@@ -8,7 +6,7 @@ use cowl_swap::constants::{ARG_END_TIME, ARG_START_TIME};
 use serde_json::{to_string, Value};
 
 use crate::utils::{
-    call_update_times, config::get_key_pair_from_vesting, constants::INSTALLER,
+    call_update_times, config::get_key_pair_from_vesting, constants::INSTALLER, exit,
     get_contract_swap_hash_keys, prompt_yes_no, sdk,
 };
 
@@ -21,7 +19,7 @@ pub async fn update_times(start_time: String, duration: String) -> (u64, u64) {
 
     if cowl_swap_contract_package_hash.is_empty() {
         log::error!("Swap contract package does not exist in installer named keys");
-        process::exit(1)
+        exit(1)
     }
 
     let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
@@ -81,6 +79,26 @@ pub async fn update_times(start_time: String, duration: String) -> (u64, u64) {
     (actual_start_time, actual_end_time)
 }
 
+/// Retrieves the current start and end times of the swap window, if the swap contract exists.
+pub async fn get_swap_times() -> Option<(u64, u64)> {
+    let (cowl_swap_contract_hash, _) = get_contract_swap_hash_keys().await?;
+
+    let mut times = vec![];
+    for name in [ARG_START_TIME, ARG_END_TIME] {
+        let query_params = QueryGlobalStateParams {
+            key: KeyIdentifierInput::String(cowl_swap_contract_hash.clone()),
+            path: Some(PathIdentifierInput::String(name.to_string())),
+            maybe_global_state_identifier: None,
+            state_root_hash: None,
+            maybe_block_id: None,
+            node_address: None,
+            verbosity: None,
+        };
+        times.push(query_contract_key_as_u64(query_params).await);
+    }
+    Some((times[0], times[1]))
+}
+
 pub async fn print_update_times(start_time: String, duration: String) {
     log::info!("Update Times");
     let (actual_start_time, actual_end_time) = update_times(start_time, duration).await;
@@ -19,7 +19,12 @@ pub async fn upgrade_events() -> Option<String> {
         );
         log::info!("Calling set_modalities to enable events.");
 
-        match call_set_modalities_entry_point(&contract_vesting_package_hash, EventsMode::CES).await
+        match call_set_modalities_entry_point(
+            &contract_vesting_package_hash,
+            Some(EventsMode::CES),
+            None,
+        )
+        .await
         {
             (success_message, _) if !success_message.is_empty() => {
                 Some("Events mode enabled".to_string())
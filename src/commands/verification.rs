@@ -0,0 +1,292 @@
+use super::{
+    allowance::query_allowance,
+    balance::{query_balance, query_cspr_balance},
+};
+use crate::utils::{
+    constants::{BALANCE_QUERY_CONCURRENCY, COWL_CEP_18_COOL_SYMBOL},
+    contacts::format_key_with_alias,
+    exit,
+};
+use bigdecimal::num_bigint::BigInt;
+use casper_rust_wasm_sdk::types::key::Key;
+use futures::{stream, StreamExt};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+const UNIT_MOTES: &str = "motes";
+
+/// A balance read before and after a deploy.
+#[derive(Debug, Clone)]
+pub enum Tracked {
+    /// Token balance of an account or contract.
+    Token(Key),
+    /// CSPR balance of an account main purse or of a contract `purse`.
+    Cspr(Key),
+    /// Allowance of a spender (second key) over the tokens of an owner (first key).
+    Allowance(Key, Key),
+}
+
+impl Tracked {
+    fn kind(&self) -> &'static str {
+        match self {
+            Tracked::Token(_) => "token",
+            Tracked::Cspr(_) => "cspr",
+            Tracked::Allowance(..) => "allowance",
+        }
+    }
+
+    fn unit(&self) -> &str {
+        match self {
+            Tracked::Cspr(_) => UNIT_MOTES,
+            Tracked::Token(_) | Tracked::Allowance(..) => COWL_CEP_18_COOL_SYMBOL.as_str(),
+        }
+    }
+
+    fn id(&self) -> String {
+        match self {
+            Tracked::Token(key) | Tracked::Cspr(key) => {
+                format!("{} {}", self.kind(), key.to_formatted_string())
+            }
+            Tracked::Allowance(owner, spender) => format!(
+                "{} {} {}",
+                self.kind(),
+                owner.to_formatted_string(),
+                spender.to_formatted_string()
+            ),
+        }
+    }
+
+    /// Reads the balance, failing when it cannot be read rather than reading it as zero.
+    async fn read(&self) -> Result<BigInt, String> {
+        let value = match self {
+            Tracked::Token(key) => query_balance(None, Some(key.clone()), None).await?,
+            Tracked::Cspr(key) => query_cspr_balance(key, None).await?,
+            Tracked::Allowance(owner, spender) => query_allowance(owner, spender, None).await?,
+        };
+        BigInt::from_str(&value).map_err(|_| format!("Invalid {} value {value}", self.kind()))
+    }
+}
+
+/// Change expected on a tracked balance.
+#[derive(Debug, Clone)]
+pub enum ExpectedDelta {
+    Exact(BigInt),
+    /// Inclusive bounds, for CSPR balances also paying the gas of the deploy.
+    Between(BigInt, BigInt),
+    /// A decrease floored at zero, like an allowance decreased by more than it holds.
+    SaturatingDecrease(BigInt),
+    Increase,
+    Decrease,
+    /// Reported without being checked, like a balance set by the swap rate.
+    Any,
+}
+
+impl ExpectedDelta {
+    /// Parses an amount, an invalid amount being expected as zero.
+    pub fn amount(amount: &str) -> BigInt {
+        BigInt::from_str(amount).unwrap_or_default()
+    }
+
+    /// Delta of the signer sending `sent` motes with a deploy paying up to `payment` motes.
+    pub fn signer(sent: &BigInt, payment: &str) -> Self {
+        ExpectedDelta::Between(-sent - Self::amount(payment), -sent)
+    }
+
+    /// Delta of the signer of a deploy paying up to `payment` motes.
+    pub fn paying(payment: &str) -> Self {
+        Self::signer(&BigInt::default(), payment)
+    }
+
+    /// Delta of the signer receiving `received` motes with a deploy paying up to `payment`.
+    pub fn signer_receiving(received: &BigInt, payment: &str) -> Self {
+        ExpectedDelta::Between(received - Self::amount(payment), received.clone())
+    }
+
+    /// Expected change of a balance tracked twice, when both changes can be added up.
+    fn combine(&self, other: &ExpectedDelta) -> Option<ExpectedDelta> {
+        match (self, other) {
+            (ExpectedDelta::Exact(a), ExpectedDelta::Exact(b)) => Some(ExpectedDelta::Exact(a + b)),
+            (ExpectedDelta::Exact(delta), ExpectedDelta::Between(min, max))
+            | (ExpectedDelta::Between(min, max), ExpectedDelta::Exact(delta)) => {
+                Some(ExpectedDelta::Between(min + delta, max + delta))
+            }
+            (ExpectedDelta::Between(min_a, max_a), ExpectedDelta::Between(min_b, max_b)) => {
+                Some(ExpectedDelta::Between(min_a + min_b, max_a + max_b))
+            }
+            (ExpectedDelta::Any, _) | (_, ExpectedDelta::Any) => Some(ExpectedDelta::Any),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, before: &BigInt, delta: &BigInt) -> bool {
+        let zero = BigInt::default();
+        match self {
+            ExpectedDelta::Exact(expected) => delta == expected,
+            ExpectedDelta::Between(min, max) => min <= delta && delta <= max,
+            ExpectedDelta::SaturatingDecrease(amount) => *delta == -(amount.min(before)),
+            ExpectedDelta::Increase => *delta > zero,
+            ExpectedDelta::Decrease => *delta < zero,
+            ExpectedDelta::Any => true,
+        }
+    }
+
+    fn to_json(&self, before: &BigInt) -> Value {
+        match self {
+            ExpectedDelta::Exact(expected) => json!(expected.to_string()),
+            ExpectedDelta::Between(min, max) => {
+                json!({ "min": min.to_string(), "max": max.to_string() })
+            }
+            ExpectedDelta::SaturatingDecrease(amount) => {
+                json!((-(amount.min(before))).to_string())
+            }
+            ExpectedDelta::Increase => json!("increase"),
+            ExpectedDelta::Decrease => json!("decrease"),
+            ExpectedDelta::Any => Value::Null,
+        }
+    }
+}
+
+/// A tracked balance with its label and expected change.
+#[derive(Debug, Clone)]
+pub struct BalanceCheck {
+    pub label: String,
+    pub tracked: Tracked,
+    pub expected: ExpectedDelta,
+}
+
+impl BalanceCheck {
+    pub fn token(label: &str, key: &Key, expected: ExpectedDelta) -> Self {
+        BalanceCheck {
+            label: label.to_string(),
+            tracked: Tracked::Token(key.clone()),
+            expected,
+        }
+    }
+
+    pub fn cspr(label: &str, key: &Key, expected: ExpectedDelta) -> Self {
+        BalanceCheck {
+            label: label.to_string(),
+            tracked: Tracked::Cspr(key.clone()),
+            expected,
+        }
+    }
+
+    pub fn allowance(owner: &Key, spender: &Key, expected: ExpectedDelta) -> Self {
+        BalanceCheck {
+            label: format!("allowance of {}", format_key_with_alias(spender)),
+            tracked: Tracked::Allowance(owner.clone(), spender.clone()),
+            expected,
+        }
+    }
+}
+
+async fn read_all(checks: &[BalanceCheck]) -> Vec<Result<BigInt, String>> {
+    stream::iter(checks)
+        .map(|check| check.tracked.read())
+        .buffered(BALANCE_QUERY_CONCURRENCY)
+        .collect()
+        .await
+}
+
+/// Balances read before a deploy, compared with the expected changes once it is processed.
+#[derive(Debug)]
+pub struct BalanceSnapshot {
+    checks: Vec<BalanceCheck>,
+    before: Vec<Result<BigInt, String>>,
+}
+
+impl BalanceSnapshot {
+    /// Reads the tracked balances. A balance tracked twice, like a transfer to oneself, is
+    /// checked once against the sum of the expected changes. When they cannot be added up, its
+    /// change is reported without being checked.
+    pub async fn take(checks: Vec<BalanceCheck>) -> Self {
+        let mut merged: Vec<BalanceCheck> = vec![];
+        for check in checks {
+            match merged
+                .iter_mut()
+                .find(|existing| existing.tracked.id() == check.tracked.id())
+            {
+                Some(existing) => {
+                    existing.expected = match existing.expected.combine(&check.expected) {
+                        Some(expected) => expected,
+                        None => {
+                            log::warn!(
+                                "Changes expected on {} ({}) and {} cannot be combined, not checked",
+                                existing.label,
+                                existing.tracked.id(),
+                                check.label
+                            );
+                            ExpectedDelta::Any
+                        }
+                    };
+                }
+                None => merged.push(check),
+            }
+        }
+        let before = read_all(&merged).await;
+        BalanceSnapshot {
+            checks: merged,
+            before,
+        }
+    }
+
+    /// Reads the tracked balances again and reports their changes. Unexpected changes are
+    /// logged as warnings and end the command with a non-zero exit code. A balance that could
+    /// not be read before or after is reported as unverified without failing the command.
+    pub async fn verify(self) {
+        let after = read_all(&self.checks).await;
+
+        let mut report = vec![];
+        let mut unexpected = 0;
+        for ((check, before), after) in self.checks.iter().zip(&self.before).zip(&after) {
+            let mut entry: IndexMap<String, Value> = IndexMap::new();
+            entry.insert("label".to_string(), json!(check.label));
+            entry.insert("kind".to_string(), json!(check.tracked.kind()));
+            entry.insert("unit".to_string(), json!(check.tracked.unit()));
+
+            let (before, after) = match (before, after) {
+                (Ok(before), Ok(after)) => (before, after),
+                (Err(err), _) | (_, Err(err)) => {
+                    log::warn!(
+                        "Could not verify the change of {} ({}): {err}",
+                        check.label,
+                        check.tracked.id()
+                    );
+                    entry.insert("unverified".to_string(), json!(err));
+                    report.push(entry);
+                    continue;
+                }
+            };
+            let delta = after - before;
+            let matches = check.expected.matches(before, &delta);
+            if !matches {
+                unexpected += 1;
+                log::warn!(
+                    "Unexpected change of {} ({}): {} {}, expected {}",
+                    check.label,
+                    check.tracked.id(),
+                    delta,
+                    check.tracked.unit(),
+                    check.expected.to_json(before)
+                );
+            }
+
+            entry.insert("before".to_string(), json!(before.to_string()));
+            entry.insert("after".to_string(), json!(after.to_string()));
+            entry.insert("delta".to_string(), json!(delta.to_string()));
+            entry.insert("expected".to_string(), check.expected.to_json(before));
+            entry.insert("ok".to_string(), json!(matches));
+            report.push(entry);
+        }
+
+        let json_output =
+            serde_json::to_string_pretty(&json!({ "balance_changes": report })).unwrap();
+        log::info!("\n{}", json_output);
+
+        if unexpected > 0 {
+            log::error!("{unexpected} balance changes differ from the expected ones");
+            exit(1)
+        }
+    }
+}
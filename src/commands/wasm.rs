@@ -0,0 +1,129 @@
+use crate::utils::{
+    constants::{WASM_LOCK_FILE_PATH, WASM_NAMES, WASM_PATH_FILE_PATH},
+    exit, read_wasm_file,
+    wasm::{
+        exported_functions, load_wasm_lock, save_wasm_lock, set_wasm_dir, wasm_dir, wasm_file_path,
+        wasm_hash, WasmLock,
+    },
+};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+
+const WASM_STATUS_OK: &str = "ok";
+const WASM_STATUS_MISMATCH: &str = "mismatch";
+const WASM_STATUS_MISSING: &str = "missing";
+const WASM_STATUS_UNLOCKED: &str = "unlocked";
+
+fn load_wasm_lock_or_exit() -> WasmLock {
+    load_wasm_lock().unwrap_or_else(|err| {
+        log::error!("Failed to read {}: {}", *WASM_LOCK_FILE_PATH, err);
+        exit(1)
+    })
+}
+
+pub async fn print_wasm_path(maybe_wasm_path: Option<String>) {
+    if let Some(wasm_path) = maybe_wasm_path {
+        if let Err(err) = set_wasm_dir(&wasm_path) {
+            log::error!("Failed to set wasm path: {err}");
+            exit(1)
+        }
+        log::info!("Wasm path set to {wasm_path} in {}", *WASM_PATH_FILE_PATH);
+    }
+    log::info!("{}", wasm_dir());
+}
+
+/// Records the hashes of the current wasm files in the lockfile.
+pub async fn print_wasm_lock() {
+    let mut wasm_lock = load_wasm_lock_or_exit();
+    for name in WASM_NAMES {
+        let path = wasm_file_path(name);
+        match read_wasm_file(&path) {
+            Ok(module_bytes) => {
+                wasm_lock.insert(name.to_string(), wasm_hash(&module_bytes));
+            }
+            Err(err) => log::warn!("Skipping {path}: {err}"),
+        }
+    }
+    if let Err(err) = save_wasm_lock(&wasm_lock) {
+        log::error!("Failed to write {}: {}", *WASM_LOCK_FILE_PATH, err);
+        exit(1)
+    }
+
+    let json_output = serde_json::to_string_pretty(&wasm_lock).unwrap();
+    log::info!("\n{}", json_output);
+    log::info!("Wasm hashes written to {}", *WASM_LOCK_FILE_PATH);
+}
+
+/// Checks the SHA-256 hash of every wasm file against the lockfile.
+pub async fn print_wasm_verify() {
+    let wasm_lock = load_wasm_lock_or_exit();
+    if wasm_lock.is_empty() {
+        log::error!(
+            "No wasm hashes in {}, run `wasm lock` first",
+            *WASM_LOCK_FILE_PATH
+        );
+        exit(1)
+    }
+
+    let mut verified = true;
+    let mut wasm_info_map: IndexMap<String, Value> = IndexMap::new();
+    for name in WASM_NAMES {
+        let path = wasm_file_path(name);
+        let locked_hash = wasm_lock.get(name);
+        let maybe_hash = read_wasm_file(&path)
+            .ok()
+            .map(|module_bytes| wasm_hash(&module_bytes));
+        let status = match (&maybe_hash, locked_hash) {
+            (None, _) => WASM_STATUS_MISSING,
+            (Some(_), None) => WASM_STATUS_UNLOCKED,
+            (Some(hash), Some(locked_hash)) if hash == locked_hash => WASM_STATUS_OK,
+            (Some(_), Some(_)) => WASM_STATUS_MISMATCH,
+        };
+        verified &= status == WASM_STATUS_OK;
+        wasm_info_map.insert(
+            name.to_string(),
+            json!({
+                "path": path,
+                "sha256": maybe_hash,
+                "locked": locked_hash,
+                "status": status
+            }),
+        );
+    }
+
+    let json_output = serde_json::to_string_pretty(&wasm_info_map).unwrap();
+    log::info!("\n{}", json_output);
+    if !verified {
+        log::error!("Wasm files do not match {}", *WASM_LOCK_FILE_PATH);
+        exit(1)
+    }
+    log::info!("Wasm files match {}", *WASM_LOCK_FILE_PATH);
+}
+
+/// Shows the hash and the exported entry points of a wasm file.
+pub async fn print_wasm_info(name: String) {
+    let path = wasm_file_path(&name);
+    let module_bytes = match read_wasm_file(&path) {
+        Ok(module_bytes) => module_bytes,
+        Err(err) => {
+            log::error!("Error reading file {}: {:?}", path, err);
+            exit(1)
+        }
+    };
+    let entry_points = match exported_functions(&module_bytes) {
+        Ok(entry_points) => entry_points,
+        Err(err) => {
+            log::error!("Failed to parse {path}: {err}");
+            exit(1)
+        }
+    };
+
+    let mut wasm_info_map = IndexMap::new();
+    wasm_info_map.insert("path", json!(path));
+    wasm_info_map.insert("size", json!(module_bytes.len()));
+    wasm_info_map.insert("sha256", json!(wasm_hash(&module_bytes)));
+    wasm_info_map.insert("entry_points", json!(entry_points));
+
+    let json_output = serde_json::to_string_pretty(&wasm_info_map).unwrap();
+    log::info!("\n{}", json_output);
+}
@@ -1,12 +1,16 @@
 use crate::{
-    commands::balance::print_balance,
+    commands::{
+        balance::print_balance,
+        verification::{BalanceCheck, BalanceSnapshot, ExpectedDelta},
+    },
     utils::{
-        call_withdraw_cspr_entry_point, config::get_key_pair_from_vesting, constants::INSTALLER,
-        format_with_thousands_separator, get_contract_swap_hash_keys, prompt_yes_no,
+        call_withdraw_cspr_entry_point,
+        config::get_key_pair_from_vesting,
+        constants::{COWL_WITHDRAW_CSPR_CALL_PAYMENT_AMOUNT, INSTALLER},
+        exit, format_with_thousands_separator, get_contract_swap_hash_keys, prompt_yes_no,
     },
 };
 use casper_rust_wasm_sdk::{helpers::motes_to_cspr, types::key::Key};
-use std::process;
 
 pub async fn withdraw_cspr(amount: String) {
     let (_, cowl_swap_contract_package_hash) = match get_contract_swap_hash_keys().await {
@@ -16,7 +20,7 @@ pub async fn withdraw_cspr(amount: String) {
 
     if cowl_swap_contract_package_hash.is_empty() {
         log::error!("Swap contract package does not exist in installer named keys");
-        process::exit(1)
+        exit(1)
     }
 
     let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
@@ -29,23 +33,41 @@ pub async fn withdraw_cspr(amount: String) {
         "motes",
     ));
 
-    call_withdraw_cspr_entry_point(&key_pair, &cowl_swap_contract_package_hash, amount).await;
-
     if !answer {
         log::warn!("Withdraw aborted.");
         return;
     }
 
+    let installer = Key::from_account(key_pair.public_key.to_account_hash());
+    let withdrawn = ExpectedDelta::amount(&amount);
+    let mut checks = vec![BalanceCheck::cspr(
+        "installer",
+        &installer,
+        ExpectedDelta::signer_receiving(&withdrawn, &COWL_WITHDRAW_CSPR_CALL_PAYMENT_AMOUNT),
+    )];
+    if let Ok(swap_key) = Key::from_formatted_str(&cowl_swap_contract_package_hash) {
+        checks.push(BalanceCheck::cspr(
+            "swap contract",
+            &swap_key,
+            ExpectedDelta::Exact(-withdrawn),
+        ));
+    }
+    let snapshot = BalanceSnapshot::take(checks).await;
+
+    call_withdraw_cspr_entry_point(&key_pair, &cowl_swap_contract_package_hash, amount).await;
+
+    snapshot.verify().await;
+
     let key = Key::from_account(key_pair.public_key.to_account_hash());
     log::info!("Balance for {}", key_pair.public_key.to_string());
-    print_balance(None, Some(key.clone()), None).await;
+    print_balance(None, Some(key.clone()), None, None).await;
 
     let key = Key::from_formatted_str(&cowl_swap_contract_package_hash).ok();
     log::info!(
         "Balance for Swap Contract Package {}",
         cowl_swap_contract_package_hash
     );
-    print_balance(None, None, key).await;
+    print_balance(None, None, key, None).await;
 }
 
 pub async fn print_withdraw_cspr(amount: String) {
@@ -1,4 +1,6 @@
 use chrono::Local;
+use clap::CommandFactory;
+use clap_complete::CompleteEnv;
 use cowl_cli::{cli, utils::config};
 use env_logger::Builder;
 use log::LevelFilter;
@@ -10,6 +12,9 @@ use std::{
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
+    // Answer the completion requests of the shell, before any log output
+    CompleteEnv::with_factory(cli::Cli::command).complete();
+
     // Initialize logger
     init_logger();
 
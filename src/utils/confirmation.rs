@@ -0,0 +1,143 @@
+use super::{
+    constants::{DEPLOY_POLL_INTERVAL, DEPLOY_TIMEOUT, EVENTS_ADDRESS},
+    exit, format_with_thousands_separator,
+    journal::record_status,
+    receipt::{
+        get_deploy_receipt, DEPLOY_STATUS_FAILURE, DEPLOY_STATUS_PENDING, DEPLOY_STATUS_SUCCESS,
+    },
+    sdk,
+};
+use casper_rust_wasm_sdk::helpers::motes_to_cspr;
+use std::time::{Duration, Instant};
+
+/// Outcome of waiting for a submitted deploy.
+#[derive(Debug, Clone)]
+pub enum DeployConfirmation {
+    /// The deploy was executed successfully, with its cost in motes.
+    Success { cost: String },
+    /// The deploy was executed but reverted.
+    Failure { cost: String, error_message: String },
+    /// The deploy was not seen as processed before the timeout.
+    Unconfirmed,
+}
+
+/// Waits for a deploy to be processed, listening to the events stream when available and
+/// polling `get_deploy` otherwise, within `DEPLOY_TIMEOUT` seconds.
+pub async fn wait_deploy_processed(deploy_hash: &str) -> DeployConfirmation {
+    let started = Instant::now();
+    let timeout = Duration::from_secs(*DEPLOY_TIMEOUT);
+
+    if !EVENTS_ADDRESS.is_empty() {
+        let timeout_ms = timeout.as_millis() as u64;
+        match sdk()
+            .wait_deploy(&EVENTS_ADDRESS, deploy_hash, Some(timeout_ms))
+            .await
+        {
+            Ok(event_parse_result) => {
+                let success = event_parse_result
+                    .body
+                    .and_then(|body| body.deploy_processed)
+                    .and_then(|deploy_processed| deploy_processed.execution_result.success);
+                if let Some(success) = success {
+                    return DeployConfirmation::Success { cost: success.cost };
+                }
+                // Failures and unparsable events are resolved through the node below
+                log::debug!("Deploy {deploy_hash} not processed successfully on events stream");
+            }
+            Err(err) => {
+                log::warn!(
+                    "Events stream {} unavailable ({:?}), polling node for deploy {}",
+                    *EVENTS_ADDRESS,
+                    err,
+                    deploy_hash
+                );
+            }
+        }
+    }
+
+    poll_deploy_processed(deploy_hash, started, timeout).await
+}
+
+async fn poll_deploy_processed(
+    deploy_hash: &str,
+    started: Instant,
+    timeout: Duration,
+) -> DeployConfirmation {
+    loop {
+        match get_deploy_receipt(deploy_hash).await {
+            Ok(receipt) if receipt["status"] != DEPLOY_STATUS_PENDING => {
+                let cost = receipt
+                    .get("cost_motes")
+                    .and_then(|cost| cost.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                if receipt["status"] == DEPLOY_STATUS_FAILURE {
+                    let error_message = receipt
+                        .get("error_message")
+                        .and_then(|error_message| error_message.as_str())
+                        .unwrap_or_default()
+                        .to_string();
+                    return DeployConfirmation::Failure {
+                        cost,
+                        error_message,
+                    };
+                }
+                return DeployConfirmation::Success { cost };
+            }
+            Ok(_) => log::debug!("Deploy {deploy_hash} pending"),
+            // The node may not know the deploy yet right after submission
+            Err(err) => log::debug!("{err}"),
+        }
+
+        if started.elapsed() >= timeout {
+            return DeployConfirmation::Unconfirmed;
+        }
+        tokio::time::sleep(Duration::from_secs(*DEPLOY_POLL_INTERVAL)).await;
+    }
+}
+
+/// Waits for a deploy and logs its cost, exiting if it failed or could not be confirmed.
+/// The final status is recorded in the deploy journal.
+///
+/// Returns the processed deploy hash and the formatted cost in CSPR.
+pub async fn confirm_deploy(deploy_hash: &str) -> (String, String) {
+    match wait_deploy_processed(deploy_hash).await {
+        DeployConfirmation::Success { cost: motes } => {
+            record_status(
+                deploy_hash,
+                DEPLOY_STATUS_SUCCESS,
+                Some(motes.clone()),
+                None,
+            );
+            let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap_or_default());
+            log::info!("Processed deploy hash {deploy_hash}");
+            log::info!("Cost {cost} CSPR ({motes} motes)");
+            (deploy_hash.to_string(), cost)
+        }
+        DeployConfirmation::Failure {
+            cost: motes,
+            error_message,
+        } => {
+            record_status(
+                deploy_hash,
+                DEPLOY_STATUS_FAILURE,
+                Some(motes.clone()),
+                Some(error_message.clone()),
+            );
+            let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap_or_default());
+            log::error!("Deploy {deploy_hash} failed: {error_message}");
+            log::error!("Cost {cost} CSPR ({motes} motes)");
+            exit(1)
+        }
+        DeployConfirmation::Unconfirmed => {
+            log::error!(
+                "Deploy {} was submitted but not confirmed within {} seconds.\n\
+                Do not resubmit, check it with: deploy-status {} --wait",
+                deploy_hash,
+                *DEPLOY_TIMEOUT,
+                deploy_hash
+            );
+            exit(1)
+        }
+    }
+}
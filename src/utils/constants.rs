@@ -6,6 +6,8 @@ const DEFAULT_RPC_ADDRESS: &str = "http://127.0.0.1:7777";
 const DEFAULT_EVENTS_ADDRESS: &str = "http://127.0.0.1:9999/events/main";
 const DEFAULT_CHAIN_NAME: &str = "casper-net-1";
 const DEFAULT_TTL: &str = "30m";
+const DEFAULT_DEPLOY_TIMEOUT: u64 = 300; // seconds
+const DEFAULT_DEPLOY_POLL_INTERVAL: u64 = 5; // seconds
 const DEFAULT_CEP_18_TOKEN_SYMBOL: &str = "COWL";
 const DEFAULT_CEP_18_COOL_SYMBOL: &str = "cool";
 pub const DEFAULT_CEP_18_TOKEN_DECIMALS: u8 = 9;
@@ -32,11 +34,29 @@ pub const PAYMENT_TRANSFER_AMOUNT: &str = "100000000"; // 0.10 CSPR
 pub const MINIMUM_TRANSFER_AMOUNT: &str = "2500000000"; // 2.5 CSPR
 pub const DEFAULT_BALANCE: &str = "0";
 
+const DEFAULT_JOURNAL_FILE_PATH: &str = "deploys.jsonl";
+const DEFAULT_CONTACTS_FILE_PATH: &str = "contacts.json";
+const DEFAULT_SHELL_HISTORY_FILE_PATH: &str = ".cowl_cli_history";
+const DEFAULT_WASM_LOCK_FILE_PATH: &str = "wasm.lock";
+const DEFAULT_WASM_PATH_FILE_PATH: &str = ".wasm_path";
+const DEFAULT_EVENTS_CACHE_FILE_PATH: &str = ".cowl_events.json";
+pub const DEFAULT_WASM_PATH: &str = "./wasm/";
+
 pub const DEFAULT_SWAP_DEPOSIT_COWL_SESSION: &str = "deposit_cowl_session";
 pub const DEFAULT_SWAP_DEPOSIT_CSPR_SESSION: &str = "deposit_cspr_session";
 pub const DEFAULT_SWAP_COWL_TO_CSPR_SESSION: &str = "cowl_to_cspr_session";
 pub const DEFAULT_SWAP_CSPR_TO_COWL_SESSION: &str = "cspr_to_cowl_session";
 
+pub const WASM_NAMES: [&str; 7] = [
+    DEFAULT_CEP_18_TOKEN_NAME,
+    DEFAULT_VESTING_NAME,
+    DEFAULT_SWAP_NAME,
+    DEFAULT_SWAP_DEPOSIT_COWL_SESSION,
+    DEFAULT_SWAP_DEPOSIT_CSPR_SESSION,
+    DEFAULT_SWAP_COWL_TO_CSPR_SESSION,
+    DEFAULT_SWAP_CSPR_TO_COWL_SESSION,
+];
+
 pub static RPC_ADDRESS: Lazy<String> =
     Lazy::new(|| env::var("RPC_ADDRESS").unwrap_or_else(|_| DEFAULT_RPC_ADDRESS.to_string()));
 pub static EVENTS_ADDRESS: Lazy<String> =
@@ -45,6 +65,18 @@ pub static CHAIN_NAME: Lazy<String> =
     Lazy::new(|| env::var("CHAIN_NAME").unwrap_or_else(|_| DEFAULT_CHAIN_NAME.to_string()));
 pub static TTL: Lazy<String> =
     Lazy::new(|| env::var("TTL").unwrap_or_else(|_| DEFAULT_TTL.to_string()));
+pub static DEPLOY_TIMEOUT: Lazy<u64> = Lazy::new(|| {
+    env::var("DEPLOY_TIMEOUT")
+        .ok()
+        .and_then(|timeout| timeout.parse().ok())
+        .unwrap_or(DEFAULT_DEPLOY_TIMEOUT)
+});
+pub static DEPLOY_POLL_INTERVAL: Lazy<u64> = Lazy::new(|| {
+    env::var("DEPLOY_POLL_INTERVAL")
+        .ok()
+        .and_then(|interval| interval.parse().ok())
+        .unwrap_or(DEFAULT_DEPLOY_POLL_INTERVAL)
+});
 
 pub static COWL_CEP_18_INSTALL_PAYMENT_AMOUNT: Lazy<String> = Lazy::new(|| {
     env::var("COWL_CEP_18_INSTALL_PAYMENT_AMOUNT")
@@ -140,11 +172,45 @@ pub static COWL_UPDATE_TIMES_CALL_PAYMENT_AMOUNT: Lazy<String> = Lazy::new(|| {
 pub static COWL_SWAP_NAME: Lazy<String> =
     Lazy::new(|| env::var("COWL_SWAP_NAME").unwrap_or_else(|_| DEFAULT_SWAP_NAME.to_string()));
 
-pub const WASM_PATH: &str = "./wasm/";
+// Set by `wasm path`, a WASM_PATH variable takes precedence
+pub static WASM_PATH_FILE_PATH: Lazy<String> = Lazy::new(|| {
+    env::var("WASM_PATH_FILE_PATH").unwrap_or_else(|_| DEFAULT_WASM_PATH_FILE_PATH.to_string())
+});
+// Optional on-disk cache of the contract hashes resolved at a state root hash
+pub static REGISTRY_CACHE_FILE_PATH: Lazy<Option<String>> = Lazy::new(|| {
+    env::var("REGISTRY_CACHE_FILE_PATH")
+        .ok()
+        .filter(|path| !path.is_empty())
+});
+pub const BALANCE_QUERY_CONCURRENCY: usize = 16;
+// On-disk cache of the scanned blocks, disabled when set empty
+pub static EVENTS_CACHE_FILE_PATH: Lazy<Option<String>> = Lazy::new(|| {
+    Some(
+        env::var("EVENTS_CACHE_FILE_PATH")
+            .unwrap_or_else(|_| DEFAULT_EVENTS_CACHE_FILE_PATH.to_string()),
+    )
+    .filter(|path| !path.is_empty())
+});
+pub const BLOCK_SCAN_CONCURRENCY: usize = 16;
+pub static SHELL_HISTORY_FILE_PATH: Lazy<String> = Lazy::new(|| {
+    env::var("SHELL_HISTORY_FILE_PATH")
+        .unwrap_or_else(|_| DEFAULT_SHELL_HISTORY_FILE_PATH.to_string())
+});
+pub static WASM_LOCK_FILE_PATH: Lazy<String> = Lazy::new(|| {
+    env::var("WASM_LOCK_FILE_PATH").unwrap_or_else(|_| DEFAULT_WASM_LOCK_FILE_PATH.to_string())
+});
 
 pub const FUNDED_KEYS_URL: &str =
     "https://raw.githubusercontent.com/casper-network/casper-node-launcher-js/main/src/config.ts";
 pub const FUNDED_KEYS_JSON_FILE_PATH: &str = "funded_keys.json";
+pub static JOURNAL_FILE_PATH: Lazy<String> = Lazy::new(|| {
+    env::var("JOURNAL_FILE_PATH").unwrap_or_else(|_| DEFAULT_JOURNAL_FILE_PATH.to_string())
+});
+pub static CONTACTS_FILE_PATH: Lazy<String> = Lazy::new(|| {
+    env::var("CONTACTS_FILE_PATH").unwrap_or_else(|_| DEFAULT_CONTACTS_FILE_PATH.to_string())
+});
+
+pub const ARG_ENABLE_MINT_BURN: &str = "enable_mint_burn";
 
 pub const INSTALLER: &str = "Installer";
 pub const USER_1: &str = "User_1";
@@ -0,0 +1,84 @@
+use super::{
+    constants::CONTACTS_FILE_PATH,
+    key_ref::{parse_key, parse_key_ref, KeyRef},
+};
+use casper_rust_wasm_sdk::types::key::Key;
+use indexmap::IndexMap;
+use std::{error::Error, fs, path::Path};
+
+/// Aliases of public keys, account hashes or contract package hashes.
+pub type Contacts = IndexMap<String, String>;
+
+pub fn load_contacts() -> Result<Contacts, Box<dyn Error>> {
+    if !Path::new(CONTACTS_FILE_PATH.as_str()).exists() {
+        return Ok(Contacts::new());
+    }
+    let content = fs::read_to_string(CONTACTS_FILE_PATH.as_str())?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn save_contacts(contacts: &Contacts) -> Result<(), Box<dyn Error>> {
+    fs::write(
+        CONTACTS_FILE_PATH.as_str(),
+        serde_json::to_string_pretty(contacts)?,
+    )?;
+    Ok(())
+}
+
+fn load_contacts_or_default() -> Contacts {
+    load_contacts().unwrap_or_else(|err| {
+        log::warn!("Failed to read contacts {}: {}", *CONTACTS_FILE_PATH, err);
+        Contacts::new()
+    })
+}
+
+pub fn add_contact(alias: &str, key: &str) -> Result<(), String> {
+    if alias.is_empty()
+        || !alias
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "Invalid alias {alias}, only letters, digits, '_' and '-' are allowed"
+        ));
+    }
+    // Aliases cannot shadow account names or keys, existing aliases are replaced
+    if !matches!(parse_key_ref(alias), Ok(KeyRef::Alias(_)) | Err(_)) {
+        return Err(format!("Alias {alias} is already a key or an account name"));
+    }
+    parse_key(key)?;
+
+    let mut contacts = load_contacts().map_err(|err| err.to_string())?;
+    contacts.insert(alias.to_string(), key.to_string());
+    save_contacts(&contacts).map_err(|err| err.to_string())
+}
+
+pub fn remove_contact(alias: &str) -> Result<String, String> {
+    let mut contacts = load_contacts().map_err(|err| err.to_string())?;
+    let key = contacts
+        .shift_remove(alias)
+        .ok_or_else(|| format!("No contact {alias}"))?;
+    save_contacts(&contacts).map_err(|err| err.to_string())?;
+    Ok(key)
+}
+
+/// Finds the alias of a key in the contacts.
+pub fn contact_alias(key: &Key) -> Option<String> {
+    let formatted_key = key.to_formatted_string();
+    load_contacts_or_default()
+        .into_iter()
+        .find(|(_, value)| {
+            parse_key(value)
+                .is_ok_and(|contact_key| contact_key.to_formatted_string() == formatted_key)
+        })
+        .map(|(alias, _)| alias)
+}
+
+/// Formats a key followed by its contact alias, if any.
+pub fn format_key_with_alias(key: &Key) -> String {
+    let formatted_key = key.to_formatted_string();
+    match contact_alias(key) {
+        Some(alias) => format!("{formatted_key} ({alias})"),
+        None => formatted_key,
+    }
+}
@@ -0,0 +1,804 @@
+use super::{
+    constants::{BLOCK_SCAN_CONCURRENCY, CHAIN_NAME, EVENTS_CACHE_FILE_PATH},
+    get_block, get_contract_cep18_hash_keys, get_contract_swap_hash_keys,
+    get_contract_vesting_hash_keys, get_state_root_hash, is_value_not_found,
+    receipt::get_deploy_json,
+    sdk,
+};
+use bigdecimal::num_bigint::BigUint;
+use casper_rust_wasm_sdk::rpcs::query_global_state::{KeyIdentifierInput, QueryGlobalStateParams};
+use futures::{stream, StreamExt};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, to_string, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+const EVENTS_NAMED_KEY: &str = "__events";
+const EVENTS_SCHEMA_NAMED_KEY: &str = "__events_schema";
+const EVENT_PREFIX: &str = "event_";
+
+// Blocks are scanned by chunks of so many blocks, logging and caching progress after each one
+const BLOCKS_PROGRESS_INTERVAL: u64 = 1000;
+
+pub const EVENT_SOURCE_TOKEN: &str = "token";
+pub const EVENT_SOURCE_VESTING: &str = "vesting";
+pub const EVENT_SOURCE_SWAP: &str = "swap";
+
+/// Field layouts of the events of a contract by event name, as stored in `__events_schema`.
+pub type EventSchemas = IndexMap<String, Vec<(String, FieldType)>>;
+
+/// A contract emitting CES events, identified by the seed of its `__events` dictionary.
+#[derive(Debug, Clone)]
+pub struct EventSource {
+    pub label: String,
+    pub contract_hash: String,
+    pub contract_package_hash: String,
+    pub seed_uref_addr: String,
+    /// Schemas of the contract, the token layouts being used when they cannot be read.
+    pub schemas: Option<EventSchemas>,
+}
+
+/// A decoded CES event.
+#[derive(Debug, Clone, Serialize)]
+pub struct CesEvent {
+    pub source: String,
+    pub contract_hash: String,
+    pub name: String,
+    pub fields: IndexMap<String, String>,
+}
+
+/// A decoded CES event with the block and deploy that emitted it.
+#[derive(Debug, Clone)]
+pub struct BlockEvent {
+    pub block_height: u64,
+    pub timestamp: Value,
+    pub deploy_hash: String,
+    /// Public key of the account that signed the deploy.
+    pub deploy_account: Option<String>,
+    pub event: CesEvent,
+}
+
+/// CLType of an event field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    I32,
+    I64,
+    U8,
+    U32,
+    U64,
+    U128,
+    U256,
+    U512,
+    Unit,
+    String,
+    Key,
+    URef,
+    Option(Box<FieldType>),
+    List(Box<FieldType>),
+    ByteArray(u32),
+    Result(Box<FieldType>, Box<FieldType>),
+    Map(Box<FieldType>, Box<FieldType>),
+    Tuple(Vec<FieldType>),
+    Any,
+    PublicKey,
+}
+
+// Field layouts of the events emitted by the COWL CEP-18 token, used when the schemas of the
+// contract cannot be read
+fn token_event_schema(name: &str) -> Option<Vec<(String, FieldType)>> {
+    use FieldType::{Key, U256};
+    let schema = match name {
+        "Mint" => vec![("recipient", Key), ("amount", U256)],
+        "Burn" => vec![("owner", Key), ("amount", U256)],
+        "SetAllowance" => vec![("owner", Key), ("spender", Key), ("allowance", U256)],
+        "IncreaseAllowance" => vec![
+            ("owner", Key),
+            ("spender", Key),
+            ("allowance", U256),
+            ("inc_by", U256),
+        ],
+        "DecreaseAllowance" => vec![
+            ("owner", Key),
+            ("spender", Key),
+            ("allowance", U256),
+            ("decr_by", U256),
+        ],
+        "Transfer" => vec![("sender", Key), ("recipient", Key), ("amount", U256)],
+        "TransferFrom" => vec![
+            ("spender", Key),
+            ("owner", Key),
+            ("recipient", Key),
+            ("amount", U256),
+        ],
+        _ => return None,
+    };
+    Some(
+        schema
+            .into_iter()
+            .map(|(field, field_type)| (field.to_string(), field_type))
+            .collect(),
+    )
+}
+
+struct BytesReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BytesReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let end = self.position.checked_add(length)?;
+        let slice = self.bytes.get(self.position..end)?;
+        self.position = end;
+        Some(slice)
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.position..]
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|bytes| bytes[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let length = self.u32()? as usize;
+        self.take(length)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.bytes()
+            .and_then(|bytes| String::from_utf8(bytes.to_vec()).ok())
+    }
+
+    fn big_uint(&mut self) -> Option<String> {
+        let length = self.u8()? as usize;
+        let bytes = self.take(length)?;
+        Some(BigUint::from_bytes_le(bytes).to_string())
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.take(4)
+            .map(|bytes| i32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        self.take(8)
+            .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn public_key(&mut self) -> Option<String> {
+        let tag = self.u8()?;
+        let length = match tag {
+            0 => 0,
+            1 => 32,
+            2 => 33,
+            _ => return None,
+        };
+        Some(format!("{tag:02x}{}", hex::encode(self.take(length)?)))
+    }
+
+    fn cl_type(&mut self) -> Option<FieldType> {
+        let field_type = match self.u8()? {
+            0 => FieldType::Bool,
+            1 => FieldType::I32,
+            2 => FieldType::I64,
+            3 => FieldType::U8,
+            4 => FieldType::U32,
+            5 => FieldType::U64,
+            6 => FieldType::U128,
+            7 => FieldType::U256,
+            8 => FieldType::U512,
+            9 => FieldType::Unit,
+            10 => FieldType::String,
+            11 => FieldType::Key,
+            12 => FieldType::URef,
+            13 => FieldType::Option(Box::new(self.cl_type()?)),
+            14 => FieldType::List(Box::new(self.cl_type()?)),
+            15 => FieldType::ByteArray(self.u32()?),
+            16 => FieldType::Result(Box::new(self.cl_type()?), Box::new(self.cl_type()?)),
+            17 => FieldType::Map(Box::new(self.cl_type()?), Box::new(self.cl_type()?)),
+            tag @ 18..=20 => FieldType::Tuple(
+                (0..tag - 17)
+                    .map(|_| self.cl_type())
+                    .collect::<Option<_>>()?,
+            ),
+            21 => FieldType::Any,
+            22 => FieldType::PublicKey,
+            _ => return None,
+        };
+        Some(field_type)
+    }
+
+    fn list(&mut self, item_type: &FieldType) -> Option<Vec<Value>> {
+        (0..self.u32()?).map(|_| self.value(item_type)).collect()
+    }
+
+    /// Reads a value of a CLType, numbers beyond 64 bits and keys being read as strings.
+    fn value(&mut self, field_type: &FieldType) -> Option<Value> {
+        let value = match field_type {
+            FieldType::Bool => json!(self.u8()? != 0),
+            FieldType::I32 => json!(self.i32()?),
+            FieldType::I64 => json!(self.i64()?),
+            FieldType::U8 => json!(self.u8()?),
+            FieldType::U32 => json!(self.u32()?),
+            FieldType::U64 => json!(self.u64()?),
+            FieldType::U128 | FieldType::U256 | FieldType::U512 => json!(self.big_uint()?),
+            FieldType::Unit => Value::Null,
+            FieldType::String => json!(self.string()?),
+            FieldType::Key => json!(self.key()?),
+            FieldType::URef => {
+                let addr = hex::encode(self.take(32)?);
+                json!(format!("uref-{addr}-{:03o}", self.u8()?))
+            }
+            FieldType::Option(inner) => match self.u8()? {
+                0 => Value::Null,
+                _ => self.value(inner)?,
+            },
+            FieldType::List(item_type) => json!(self.list(item_type)?),
+            FieldType::ByteArray(length) => json!(hex::encode(self.take(*length as usize)?)),
+            FieldType::Result(ok_type, err_type) => match self.u8()? {
+                0 => json!({ "Err": self.value(err_type)? }),
+                _ => json!({ "Ok": self.value(ok_type)? }),
+            },
+            FieldType::Map(key_type, value_type) => {
+                let mut map = serde_json::Map::new();
+                for _ in 0..self.u32()? {
+                    let key = match self.value(key_type)? {
+                        Value::String(key) => key,
+                        key => key.to_string(),
+                    };
+                    map.insert(key, self.value(value_type)?);
+                }
+                Value::Object(map)
+            }
+            FieldType::Tuple(types) => json!(types
+                .iter()
+                .map(|item_type| self.value(item_type))
+                .collect::<Option<Vec<_>>>()?),
+            // Values of unknown type cannot be delimited
+            FieldType::Any => return None,
+            FieldType::PublicKey => json!(self.public_key()?),
+        };
+        Some(value)
+    }
+
+    fn key(&mut self) -> Option<String> {
+        let tag = self.u8()?;
+        match tag {
+            0 => Some(format!("account-hash-{}", hex::encode(self.take(32)?))),
+            1 => Some(format!("hash-{}", hex::encode(self.take(32)?))),
+            2 => {
+                let addr = hex::encode(self.take(32)?);
+                let access_rights = self.u8()?;
+                Some(format!("uref-{addr}-{access_rights:03o}"))
+            }
+            5 => Some(format!("era-{}", self.u64()?)),
+            _ => Some(format!("key-{tag}-{}", hex::encode(self.take(32)?))),
+        }
+    }
+}
+
+/// Splits the bytes of a dictionary `WriteCLValue` into the dictionary seed URef address and
+/// the inner value bytes.
+fn decode_dictionary_value(bytes: &[u8]) -> Option<(String, Vec<u8>)> {
+    let mut reader = BytesReader::new(bytes);
+    // CLValue: serialized value followed by its CLType, CES events are stored as `List<U8>`
+    let cl_value_bytes = reader.bytes()?;
+    if reader.u8()? != 14 || reader.u8()? != 3 {
+        return None;
+    }
+    let seed_uref_addr = hex::encode(reader.bytes()?);
+    let value = BytesReader::new(cl_value_bytes).bytes()?.to_vec();
+    Some((seed_uref_addr, value))
+}
+
+/// Decodes the bytes of the `__events_schema` of a contract: event names with their fields and
+/// the CLType of each field.
+pub fn decode_schemas(bytes: &[u8]) -> Option<EventSchemas> {
+    let mut reader = BytesReader::new(bytes);
+    let mut schemas = EventSchemas::new();
+    for _ in 0..reader.u32()? {
+        let name = reader.string()?;
+        let fields = (0..reader.u32()?)
+            .map(|_| Some((reader.string()?, reader.cl_type()?)))
+            .collect::<Option<Vec<_>>>()?;
+        schemas.insert(name, fields);
+    }
+    Some(schemas)
+}
+
+/// Decodes the raw bytes of a CES event into its name and fields, with the schemas of the
+/// contract or the token layouts. Bytes left undecoded are kept as a `raw` field.
+pub fn decode_event(
+    bytes: &[u8],
+    maybe_schemas: Option<&EventSchemas>,
+) -> Option<(String, IndexMap<String, String>)> {
+    let mut reader = BytesReader::new(bytes);
+    let name = reader.string()?;
+    let name = name.strip_prefix(EVENT_PREFIX).unwrap_or(&name).to_string();
+
+    let schema: Vec<(String, FieldType)> =
+        match maybe_schemas.and_then(|schemas| schemas.get(&name)) {
+            Some(schema) => schema.clone(),
+            None => token_event_schema(&name).unwrap_or_default(),
+        };
+
+    let mut fields = IndexMap::new();
+    for (field, field_type) in &schema {
+        let value = match reader.value(field_type)? {
+            Value::String(value) => value,
+            value => value.to_string(),
+        };
+        fields.insert(field.clone(), value);
+    }
+    if !reader.remaining().is_empty() {
+        fields.insert("raw".to_string(), hex::encode(reader.remaining()));
+    }
+    Some((name, fields))
+}
+
+/// Extracts the dictionary values written in an execution result (`{"Success": {...}}` or
+/// `{"Failure": {...}}`), as the dictionary seed URef address and the value bytes.
+fn dictionary_writes(execution_result: &Value) -> Vec<(String, Vec<u8>)> {
+    let result = execution_result
+        .get("Success")
+        .or_else(|| execution_result.get("Failure"))
+        .unwrap_or(execution_result);
+
+    let transforms = match result["effect"]["transforms"].as_array() {
+        Some(transforms) => transforms,
+        None => return vec![],
+    };
+
+    transforms
+        .iter()
+        .filter(|transform| {
+            transform["key"]
+                .as_str()
+                .is_some_and(|key| key.starts_with("dictionary-"))
+        })
+        .filter_map(|transform| transform["transform"]["WriteCLValue"]["bytes"].as_str())
+        .filter_map(|hex_string| hex::decode(hex_string).ok())
+        .filter_map(|bytes| decode_dictionary_value(&bytes))
+        .collect()
+}
+
+fn decode_writes(writes: &[(String, Vec<u8>)], sources: &[EventSource]) -> Vec<CesEvent> {
+    writes
+        .iter()
+        .filter_map(|(seed_uref_addr, value)| {
+            let source = sources
+                .iter()
+                .find(|source| source.seed_uref_addr == *seed_uref_addr)?;
+            let (name, fields) = decode_event(value, source.schemas.as_ref())?;
+            Some(CesEvent {
+                source: source.label.clone(),
+                contract_hash: source.contract_hash.clone(),
+                name,
+                fields,
+            })
+        })
+        .collect()
+}
+
+/// Extracts the CES events written by `sources` in an execution result
+/// (`{"Success": {...}}` or `{"Failure": {...}}`).
+pub fn extract_events(execution_result: &Value, sources: &[EventSource]) -> Vec<CesEvent> {
+    decode_writes(&dictionary_writes(execution_result), sources)
+}
+
+async fn query_stored_value_json(key: &str) -> Option<Value> {
+    let query_params = QueryGlobalStateParams {
+        key: KeyIdentifierInput::String(key.to_string()),
+        path: None,
+        maybe_global_state_identifier: None,
+        state_root_hash: None,
+        maybe_block_id: None,
+        node_address: None,
+        verbosity: None,
+    };
+
+    let query_global_state_result = sdk().query_global_state(query_params).await.ok()?;
+    let json_string = to_string(&query_global_state_result.result.stored_value).ok()?;
+    serde_json::from_str(&json_string).ok()
+}
+
+/// Retrieves the seed of the `__events` dictionary of a contract and the URef of its schemas.
+async fn get_events_named_keys(contract_hash: &str) -> Option<(String, Option<String>)> {
+    let parsed_json = query_stored_value_json(contract_hash).await?;
+    let named_keys = parsed_json["Contract"]["named_keys"].as_array()?;
+    let named_key = |name: &str| {
+        named_keys
+            .iter()
+            .find(|obj| obj["name"] == Value::String(name.to_string()))
+            .and_then(|obj| obj["key"].as_str())
+            .map(str::to_string)
+    };
+
+    // uref-<addr>-<access rights>
+    let seed_uref_addr = named_key(EVENTS_NAMED_KEY)?
+        .strip_prefix("uref-")
+        .and_then(|uref| uref.split('-').next())
+        .map(str::to_string)?;
+    Some((seed_uref_addr, named_key(EVENTS_SCHEMA_NAMED_KEY)))
+}
+
+async fn get_event_schemas(schema_uref: &str) -> Option<EventSchemas> {
+    let stored_value = query_stored_value_json(schema_uref).await?;
+    let bytes = hex::decode(stored_value["CLValue"]["bytes"].as_str()?).ok()?;
+    decode_schemas(&bytes)
+}
+
+/// Retrieves the event sources of the installed token, vesting and swap contracts.
+pub async fn get_event_sources() -> Vec<EventSource> {
+    let contracts = [
+        (EVENT_SOURCE_TOKEN, get_contract_cep18_hash_keys().await),
+        (EVENT_SOURCE_VESTING, get_contract_vesting_hash_keys().await),
+        (EVENT_SOURCE_SWAP, get_contract_swap_hash_keys().await),
+    ];
+
+    let mut sources = vec![];
+    for (label, hash_keys) in contracts {
+        let Some((contract_hash, contract_package_hash)) = hash_keys else {
+            log::debug!("No {label} contract in installer named keys");
+            continue;
+        };
+        match get_events_named_keys(&contract_hash).await {
+            Some((seed_uref_addr, maybe_schema_uref)) => {
+                let schemas = match maybe_schema_uref {
+                    Some(schema_uref) => get_event_schemas(&schema_uref).await,
+                    None => None,
+                };
+                if schemas.is_none() {
+                    log::warn!(
+                        "Failed to read the event schemas of {label} contract {contract_hash}"
+                    );
+                }
+                sources.push(EventSource {
+                    label: label.to_string(),
+                    contract_hash,
+                    contract_package_hash,
+                    seed_uref_addr,
+                    schemas,
+                });
+            }
+            None => log::warn!("Events are not enabled on {label} contract {contract_hash}"),
+        }
+    }
+    sources
+}
+
+/// Dictionary values written by a deploy of a scanned block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScannedDeploy {
+    deploy_hash: String,
+    account: Option<String>,
+    /// Dictionary seed URef address and hex value of each write.
+    writes: Vec<(String, String)>,
+}
+
+/// A scanned block holding dictionary values written by the event sources.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScannedBlock {
+    timestamp: Value,
+    deploys: Vec<ScannedDeploy>,
+}
+
+/// Blocks scanned on a network. Blocks never change, so a height range is only scanned once
+/// for the dictionary seeds it was scanned for.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ScanCache {
+    seeds: BTreeSet<String>,
+    /// Inclusive height ranges already scanned.
+    ranges: Vec<(u64, u64)>,
+    blocks: BTreeMap<u64, ScannedBlock>,
+    /// Install height of the contract packages by hash.
+    install_heights: BTreeMap<String, u64>,
+}
+
+impl ScanCache {
+    fn is_scanned(&self, height: u64) -> bool {
+        self.ranges
+            .iter()
+            .any(|(from, to)| (*from..=*to).contains(&height))
+    }
+
+    /// Heights of a range not scanned yet, as inclusive ranges.
+    fn missing_ranges(&self, from_height: u64, to_height: u64) -> Vec<(u64, u64)> {
+        let mut missing: Vec<(u64, u64)> = vec![];
+        for height in from_height..=to_height {
+            if self.is_scanned(height) {
+                continue;
+            }
+            match missing.last_mut() {
+                Some((_, to)) if *to + 1 == height => *to = height,
+                _ => missing.push((height, height)),
+            }
+        }
+        missing
+    }
+
+    fn add_range(&mut self, from_height: u64, to_height: u64) {
+        self.ranges.push((from_height, to_height));
+        self.ranges.sort();
+        let mut merged: Vec<(u64, u64)> = vec![];
+        for (from, to) in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some((_, last_to)) if from <= *last_to + 1 => *last_to = (*last_to).max(to),
+                _ => merged.push((from, to)),
+            }
+        }
+        self.ranges = merged;
+    }
+}
+
+// Scanned blocks of each network, by chain name and genesis block hash
+type ScanCaches = IndexMap<String, ScanCache>;
+
+/// Identifies the network, a chain restarted under the same name having another genesis block.
+async fn scan_cache_key() -> Result<String, String> {
+    let genesis_hash = get_block(Some("0"))
+        .await
+        .and_then(|block| block["hash"].as_str().map(str::to_string))
+        .ok_or_else(|| "Failed to retrieve the genesis block".to_string())?;
+    Ok(format!("{}-{}", *CHAIN_NAME, genesis_hash))
+}
+
+fn load_scan_caches() -> ScanCaches {
+    let Some(path) = EVENTS_CACHE_FILE_PATH.as_deref() else {
+        return ScanCaches::new();
+    };
+    if !Path::new(path).exists() {
+        return ScanCaches::new();
+    }
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| {
+            log::warn!("Ignoring invalid events cache {path}");
+            ScanCaches::new()
+        })
+}
+
+fn save_scan_cache(cache_key: &str, cache: &ScanCache) {
+    let Some(path) = EVENTS_CACHE_FILE_PATH.as_deref() else {
+        return;
+    };
+    let mut caches = load_scan_caches();
+    caches.insert(cache_key.to_string(), cache.clone());
+
+    // Write to a temporary file first so the cache is never left truncated
+    let tmp_path = format!("{path}.tmp");
+    let write = serde_json::to_string(&caches)
+        .map_err(|err| err.to_string())
+        .and_then(|content| fs::write(&tmp_path, content).map_err(|err| err.to_string()))
+        .and_then(|_| fs::rename(&tmp_path, path).map_err(|err| err.to_string()));
+    if let Err(err) = write {
+        log::warn!("Failed to write events cache {path}: {err}");
+    }
+}
+
+/// Tells whether a contract package exists at the state of a block.
+async fn package_exists_at(contract_package_hash: &str, height: u64) -> Result<bool, String> {
+    let state_root_hash = get_state_root_hash(&height.to_string())
+        .await
+        .ok_or_else(|| format!("Failed to retrieve block {height}"))?;
+    let query_params = QueryGlobalStateParams {
+        key: KeyIdentifierInput::String(contract_package_hash.to_string()),
+        path: None,
+        maybe_global_state_identifier: None,
+        state_root_hash: Some(state_root_hash),
+        maybe_block_id: None,
+        node_address: None,
+        verbosity: None,
+    };
+    match sdk().query_global_state(query_params).await {
+        Ok(_) => Ok(true),
+        Err(err) if is_value_not_found(&format!("{:?}", err)) => Ok(false),
+        Err(err) => Err(format!(
+            "Failed to query {contract_package_hash} at block {height}: {:?}",
+            err
+        )),
+    }
+}
+
+/// Finds the block that installed a contract package, the first block whose state holds it.
+async fn find_install_height(
+    contract_package_hash: &str,
+    latest_height: u64,
+) -> Result<u64, String> {
+    if !package_exists_at(contract_package_hash, latest_height).await? {
+        return Err(format!(
+            "Contract package {contract_package_hash} is not installed"
+        ));
+    }
+    let (mut low, mut high) = (0, latest_height);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if package_exists_at(contract_package_hash, middle).await? {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    Ok(low)
+}
+
+/// Height of the first block that can hold events of `sources`: the block installing the
+/// earliest of their contract packages.
+pub async fn events_start_height(sources: &[EventSource]) -> Result<u64, String> {
+    let latest_height = get_block(None)
+        .await
+        .and_then(|block| block["header"]["height"].as_u64())
+        .ok_or_else(|| "Failed to retrieve the latest block".to_string())?;
+    let cache_key = scan_cache_key().await?;
+    let mut cache = load_scan_caches()
+        .get(&cache_key)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut start_height = latest_height;
+    for source in sources {
+        let install_height = match cache.install_heights.get(&source.contract_package_hash) {
+            Some(install_height) => *install_height,
+            None => {
+                let install_height =
+                    find_install_height(&source.contract_package_hash, latest_height).await?;
+                log::info!(
+                    "{} contract package {} installed at block {}",
+                    source.label,
+                    source.contract_package_hash,
+                    install_height
+                );
+                cache
+                    .install_heights
+                    .insert(source.contract_package_hash.clone(), install_height);
+                install_height
+            }
+        };
+        start_height = start_height.min(install_height);
+    }
+    save_scan_cache(&cache_key, &cache);
+    Ok(start_height)
+}
+
+/// Fetches a block and the dictionary values its deploys wrote under `seeds`.
+async fn scan_block(height: u64, seeds: &BTreeSet<String>) -> Result<ScannedBlock, String> {
+    let block = get_block(Some(&height.to_string()))
+        .await
+        .ok_or_else(|| format!("Failed to retrieve block {height}"))?;
+
+    let mut deploys = vec![];
+    let deploy_hashes = block["body"]["deploy_hashes"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    for deploy_hash in deploy_hashes.iter().filter_map(|hash| hash.as_str()) {
+        let deploy = get_deploy_json(deploy_hash).await?;
+        let Some(execution_result) = deploy["execution_results"]
+            .as_array()
+            .and_then(|results| results.first())
+        else {
+            continue;
+        };
+
+        let writes: Vec<(String, String)> = dictionary_writes(&execution_result["result"])
+            .into_iter()
+            .filter(|(seed_uref_addr, _)| seeds.contains(seed_uref_addr))
+            .map(|(seed_uref_addr, value)| (seed_uref_addr, hex::encode(value)))
+            .collect();
+        if writes.is_empty() {
+            continue;
+        }
+        deploys.push(ScannedDeploy {
+            deploy_hash: deploy_hash.to_string(),
+            account: deploy["deploy"]["header"]["account"]
+                .as_str()
+                .map(str::to_string),
+            writes,
+        });
+    }
+
+    Ok(ScannedBlock {
+        timestamp: block["header"]["timestamp"].clone(),
+        deploys,
+    })
+}
+
+/// Walks the blocks between two heights and decodes the CES events emitted by `sources`.
+/// Blocks are fetched concurrently, and the scanned ranges are kept in the events cache so that
+/// a later scan only fetches the new blocks.
+pub async fn scan_events(
+    from_height: u64,
+    to_height: u64,
+    sources: &[EventSource],
+) -> Result<Vec<BlockEvent>, String> {
+    let cache_key = scan_cache_key().await?;
+    let mut cache = load_scan_caches()
+        .get(&cache_key)
+        .cloned()
+        .unwrap_or_default();
+
+    // Blocks scanned for other contracts only are scanned again
+    let seeds: BTreeSet<String> = sources
+        .iter()
+        .map(|source| source.seed_uref_addr.clone())
+        .collect();
+    if !seeds.is_subset(&cache.seeds) {
+        cache.seeds.extend(seeds);
+        cache.ranges.clear();
+        cache.blocks.clear();
+    }
+
+    for (missing_from, missing_to) in cache.missing_ranges(from_height, to_height) {
+        let mut chunk_from = missing_from;
+        while chunk_from <= missing_to {
+            let chunk_to = missing_to.min(chunk_from + BLOCKS_PROGRESS_INTERVAL - 1);
+            log::info!("Scanning blocks {chunk_from} to {chunk_to} of {to_height}");
+
+            let scanned_blocks: Vec<Result<(u64, ScannedBlock), String>> =
+                stream::iter(chunk_from..=chunk_to)
+                    .map(|height| {
+                        let seeds = &cache.seeds;
+                        async move { Ok::<_, String>((height, scan_block(height, seeds).await?)) }
+                    })
+                    .buffered(BLOCK_SCAN_CONCURRENCY)
+                    .collect()
+                    .await;
+            for scanned_block in scanned_blocks {
+                let (height, block) = scanned_block?;
+                if !block.deploys.is_empty() {
+                    cache.blocks.insert(height, block);
+                }
+            }
+
+            // Progress is kept after each chunk, so an interrupted scan resumes from there
+            cache.add_range(chunk_from, chunk_to);
+            save_scan_cache(&cache_key, &cache);
+            chunk_from = chunk_to + 1;
+        }
+    }
+
+    let mut block_events = vec![];
+    for (height, block) in cache.blocks.range(from_height..=to_height) {
+        for deploy in &block.deploys {
+            let writes: Vec<(String, Vec<u8>)> = deploy
+                .writes
+                .iter()
+                .filter_map(|(seed_uref_addr, value)| {
+                    Some((seed_uref_addr.clone(), hex::decode(value).ok()?))
+                })
+                .collect();
+            for event in decode_writes(&writes, sources) {
+                block_events.push(BlockEvent {
+                    block_height: *height,
+                    timestamp: block.timestamp.clone(),
+                    deploy_hash: deploy.deploy_hash.clone(),
+                    deploy_account: deploy.account.clone(),
+                    event,
+                });
+            }
+        }
+    }
+    Ok(block_events)
+}
@@ -0,0 +1,197 @@
+use super::{constants::JOURNAL_FILE_PATH, receipt::DEPLOY_STATUS_PENDING};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+};
+
+// Journal lines recording the final status of a deploy submitted earlier
+const EVENT_STATUS: &str = "status";
+
+// Command line of the command being executed, recorded with each deploy
+static CURRENT_COMMAND: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(vec![]));
+
+// Off-chain memo given to the command being executed, recorded with each deploy
+static CURRENT_MEMO: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// A deploy submitted by the CLI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: String,
+    pub command: String,
+    pub arguments: Vec<String>,
+    pub signer: String,
+    pub contract_package: Option<String>,
+    pub entry_point: Option<String>,
+    pub session_wasm: Option<String>,
+    pub wasm_hash: Option<String>,
+    pub recipient: Option<String>,
+    pub transfer_id: Option<String>,
+    pub memo: Option<String>,
+    pub payment: String,
+    pub deploy_hash: String,
+    pub status: String,
+    pub cost: Option<String>,
+    pub error_message: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn new(deploy_hash: &str, signer: &str, payment: &str) -> Self {
+        let (command, arguments) = {
+            let current_command = CURRENT_COMMAND.lock().unwrap();
+            match current_command.split_first() {
+                Some((command, arguments)) => (command.clone(), arguments.to_vec()),
+                None => (String::new(), vec![]),
+            }
+        };
+
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            command,
+            arguments,
+            signer: signer.to_string(),
+            contract_package: None,
+            entry_point: None,
+            session_wasm: None,
+            wasm_hash: None,
+            recipient: None,
+            transfer_id: None,
+            memo: CURRENT_MEMO.lock().unwrap().clone(),
+            payment: payment.to_string(),
+            deploy_hash: deploy_hash.to_string(),
+            status: DEPLOY_STATUS_PENDING.to_string(),
+            cost: None,
+            error_message: None,
+        }
+    }
+}
+
+/// Final status of a deploy, appended to the journal once it is known.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatusEvent {
+    event: String,
+    timestamp: String,
+    deploy_hash: String,
+    status: String,
+    cost: Option<String>,
+    error_message: Option<String>,
+}
+
+/// Sets the command line (subcommand followed by its arguments) recorded with the next deploys.
+pub fn set_current_command(command_line: Vec<String>) {
+    *CURRENT_COMMAND.lock().unwrap() = command_line;
+}
+
+/// Sets the off-chain memo recorded with the next deploys, like an invoice reference.
+pub fn set_memo(memo: Option<String>) {
+    *CURRENT_MEMO.lock().unwrap() = memo;
+}
+
+/// Loads the submitted deploys, each with the latest status recorded for it. Lines that cannot
+/// be parsed, like one truncated by a crash while appending, are skipped with a warning.
+pub fn load_journal() -> Result<Vec<JournalEntry>, Box<dyn Error>> {
+    if !Path::new(JOURNAL_FILE_PATH.as_str()).exists() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(JOURNAL_FILE_PATH.as_str())?;
+    let mut entries: Vec<JournalEntry> = vec![];
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(err) => {
+                log::warn!("Skipping malformed journal line {}: {err}", index + 1);
+                continue;
+            }
+        };
+        if value["event"] != EVENT_STATUS {
+            match serde_json::from_value(value) {
+                Ok(entry) => entries.push(entry),
+                Err(err) => log::warn!("Skipping malformed journal line {}: {err}", index + 1),
+            }
+            continue;
+        }
+        let status_event: StatusEvent = match serde_json::from_value(value) {
+            Ok(status_event) => status_event,
+            Err(err) => {
+                log::warn!("Skipping malformed journal line {}: {err}", index + 1);
+                continue;
+            }
+        };
+        match entries
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.deploy_hash == status_event.deploy_hash)
+        {
+            Some(entry) => {
+                entry.status = status_event.status;
+                entry.cost = status_event.cost;
+                entry.error_message = status_event.error_message;
+            }
+            None => log::debug!(
+                "Ignoring status of deploy {} missing from the journal",
+                status_event.deploy_hash
+            ),
+        }
+    }
+    Ok(entries)
+}
+
+/// Appends a line to the journal, holding an exclusive lock on the file so that lines of
+/// concurrently running commands are not interleaved.
+fn append_line<T: Serialize>(record: &T) -> Result<(), Box<dyn Error>> {
+    let line = format!("{}\n", serde_json::to_string(record)?);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(JOURNAL_FILE_PATH.as_str())?;
+    file.lock()?;
+    let written = file.write_all(line.as_bytes());
+    file.unlock()?;
+    Ok(written?)
+}
+
+/// Appends a submitted deploy to the journal.
+pub fn record_submitted(entry: JournalEntry) {
+    if let Err(e) = append_line(&entry) {
+        log::warn!(
+            "Failed to record deploy {} in {}: {}",
+            entry.deploy_hash,
+            *JOURNAL_FILE_PATH,
+            e
+        );
+    }
+}
+
+/// Appends the final status of a deploy to the journal, read back as the status of the deploy.
+pub fn record_status(
+    deploy_hash: &str,
+    status: &str,
+    cost: Option<String>,
+    error_message: Option<String>,
+) {
+    let status_event = StatusEvent {
+        event: EVENT_STATUS.to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        deploy_hash: deploy_hash.to_string(),
+        status: status.to_string(),
+        cost,
+        error_message,
+    };
+    if let Err(e) = append_line(&status_event) {
+        log::warn!(
+            "Failed to update deploy {} in {}: {}",
+            deploy_hash,
+            *JOURNAL_FILE_PATH,
+            e
+        );
+    }
+}
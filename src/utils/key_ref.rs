@@ -0,0 +1,211 @@
+use super::{
+    config::get_key_pair_from_vesting,
+    constants::{INSTALLER, USER_1, USER_2},
+    contacts::load_contacts,
+};
+use casper_rust_wasm_sdk::types::{key::Key, public_key::PublicKey};
+use cowl_vesting::enums::VestingType;
+use std::fmt::{self, Display};
+use strum::IntoEnumIterator;
+
+// Formatted prefixes of contract and package hashes, queried as `hash-` keys
+const CONTRACT_HASH_PREFIXES: [&str; 3] =
+    ["contract-package-wasm", "contract-package-", "contract-"];
+
+// Formatted prefixes of the keys of accounts, and of contracts and packages. Other keys, like
+// URefs, balances or dictionary items, cannot hold or spend tokens.
+const ACCOUNT_OR_CONTRACT_KEY_PREFIXES: [&str; 2] = ["account-hash-", "hash-"];
+
+/// A reference to an account or a contract as given on the command line, validated when parsed
+/// and resolved to a key against the configuration and the contacts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyRef {
+    /// A vesting type or a built-in account name, in its canonical case.
+    Name(String),
+    /// An alias of the contacts.
+    Alias(String),
+    /// A hex encoded public key.
+    PublicKey(String),
+    /// A formatted account hash or contract hash key.
+    Key(String),
+}
+
+impl Display for KeyRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyRef::Name(value)
+            | KeyRef::Alias(value)
+            | KeyRef::PublicKey(value)
+            | KeyRef::Key(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Names of the configured accounts: the built-in accounts followed by the vesting types.
+pub fn account_names() -> Vec<String> {
+    [INSTALLER, USER_1, USER_2]
+        .iter()
+        .map(|name| name.to_string())
+        .chain(VestingType::iter().map(|vesting_type| vesting_type.to_string()))
+        .collect()
+}
+
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(a_char != *b_char);
+            let insertion = current_row[j] + 1;
+            let deletion = previous_row[j + 1] + 1;
+            current_row.push(substitution.min(insertion).min(deletion));
+        }
+        previous_row = current_row;
+    }
+    previous_row[b.len()]
+}
+
+fn nearest_vesting_type(input: &str) -> Option<String> {
+    let input = input.to_lowercase();
+    VestingType::iter()
+        .map(|vesting_type| vesting_type.to_string())
+        .map(|name| (levenshtein_distance(&input, &name.to_lowercase()), name))
+        .filter(|(distance, name)| *distance <= name.len() / 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+fn with_suggestion(message: String, input: &str) -> String {
+    match nearest_vesting_type(input) {
+        Some(vesting_type) => format!("{message}\nDid you mean '{vesting_type}'?"),
+        None => message,
+    }
+}
+
+fn normalize_hash_key(input: &str) -> String {
+    CONTRACT_HASH_PREFIXES
+        .iter()
+        .find_map(|prefix| input.strip_prefix(prefix))
+        .map(|hash| format!("hash-{hash}"))
+        .unwrap_or_else(|| input.to_string())
+}
+
+/// Parses a public key, account hash or contract or package hash, public keys being converted
+/// to their account hash. Keys of another kind, like URefs, are rejected.
+pub fn parse_key(formatted_str: &str) -> Result<Key, String> {
+    if let Ok(public_key) = PublicKey::new(formatted_str) {
+        return Ok(Key::from_account(public_key.to_account_hash()));
+    }
+    let key = Key::from_formatted_str(&normalize_hash_key(formatted_str))
+        .map_err(|_| format!("{formatted_str} is not a public key, an account hash or a hash"))?;
+
+    let formatted_key = key.to_formatted_string();
+    if !ACCOUNT_OR_CONTRACT_KEY_PREFIXES
+        .iter()
+        .any(|prefix| formatted_key.starts_with(prefix))
+    {
+        let kind = formatted_key.split('-').next().unwrap_or_default();
+        return Err(format!(
+            "{formatted_str} is a {kind} key, expected a public key, an account hash or a contract or package hash"
+        ));
+    }
+    Ok(key)
+}
+
+/// Parses a vesting type name, case-insensitively.
+pub fn parse_vesting_type(input: &str) -> Result<String, String> {
+    VestingType::iter()
+        .map(|vesting_type| vesting_type.to_string())
+        .find(|name| name.eq_ignore_ascii_case(input))
+        .ok_or_else(|| {
+            let names: Vec<String> = VestingType::iter()
+                .map(|vesting_type| vesting_type.to_string())
+                .collect();
+            with_suggestion(
+                format!("expected one of the vesting types: {}", names.join(", ")),
+                input,
+            )
+        })
+}
+
+/// Parses a key reference: a vesting type or built-in account name (case-insensitive),
+/// a contact alias, a public key, an account hash or a contract or package hash.
+pub fn parse_key_ref(input: &str) -> Result<KeyRef, String> {
+    let input = input.trim();
+
+    if let Some(name) = account_names()
+        .into_iter()
+        .find(|name| name.eq_ignore_ascii_case(input))
+    {
+        return Ok(KeyRef::Name(name));
+    }
+
+    if load_contacts().is_ok_and(|contacts| contacts.contains_key(input)) {
+        return Ok(KeyRef::Alias(input.to_string()));
+    }
+
+    if PublicKey::new(input).is_ok() {
+        return Ok(KeyRef::PublicKey(input.to_string()));
+    }
+
+    match parse_key(input) {
+        Ok(_) => return Ok(KeyRef::Key(normalize_hash_key(input))),
+        // A valid key of another kind than an account or contract
+        Err(err) if Key::from_formatted_str(&normalize_hash_key(input)).is_ok() => return Err(err),
+        Err(_) => {}
+    }
+
+    Err(with_suggestion(
+        format!(
+            "expected one of:\n\
+            - a vesting type or account name: {}\n\
+            - a contact alias (see `contacts list`)\n\
+            - a public key, e.g. 01a1b2...\n\
+            - an account hash, e.g. account-hash-a1b2...\n\
+            - a contract or package hash, e.g. hash-a1b2...",
+            account_names().join(", ")
+        ),
+        input,
+    ))
+}
+
+impl KeyRef {
+    /// Resolves the reference to a key, public keys being converted to their account hash.
+    pub async fn to_key(&self) -> Result<Key, String> {
+        match self {
+            KeyRef::Name(name) => get_key_pair_from_vesting(name)
+                .await
+                .map(|key_pair| Key::from_account(key_pair.public_key.to_account_hash()))
+                .ok_or_else(|| format!("No key configured for {name}")),
+            KeyRef::Alias(alias) => {
+                let contacts = load_contacts().map_err(|err| err.to_string())?;
+                let contact = contacts
+                    .get(alias)
+                    .ok_or_else(|| format!("No contact {alias}"))?;
+                parse_key(contact)
+            }
+            KeyRef::PublicKey(value) | KeyRef::Key(value) => parse_key(value),
+        }
+    }
+
+    /// Resolves the reference to a public key, for references to signing accounts.
+    pub async fn to_public_key(&self) -> Result<PublicKey, String> {
+        let not_a_public_key = || format!("{self} does not refer to a public key");
+        match self {
+            KeyRef::Name(name) => get_key_pair_from_vesting(name)
+                .await
+                .map(|key_pair| key_pair.public_key)
+                .ok_or_else(|| format!("No key configured for {name}")),
+            KeyRef::Alias(alias) => {
+                let contacts = load_contacts().map_err(|err| err.to_string())?;
+                let contact = contacts
+                    .get(alias)
+                    .ok_or_else(|| format!("No contact {alias}"))?;
+                PublicKey::new(contact).map_err(|_| not_a_public_key())
+            }
+            KeyRef::PublicKey(value) => PublicKey::new(value).map_err(|_| not_a_public_key()),
+            KeyRef::Key(_) => Err(not_a_public_key()),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use bigdecimal::BigDecimal;
-use casper_rust_wasm_sdk::deploy_watcher::watcher::EventParseResult;
-use casper_rust_wasm_sdk::helpers::motes_to_cspr;
 use casper_rust_wasm_sdk::rpcs::get_dictionary_item::DictionaryItemInput;
-use casper_rust_wasm_sdk::rpcs::query_global_state::{KeyIdentifierInput, QueryGlobalStateParams};
+use casper_rust_wasm_sdk::rpcs::query_global_state::{
+    KeyIdentifierInput, PathIdentifierInput, QueryGlobalStateParams,
+};
+use casper_rust_wasm_sdk::types::block_identifier::BlockIdentifierInput;
 use casper_rust_wasm_sdk::types::contract_hash::ContractHash;
 use casper_rust_wasm_sdk::types::deploy_hash::DeployHash;
 use casper_rust_wasm_sdk::types::deploy_params::deploy_str_params::DeployStrParams;
@@ -14,12 +15,14 @@ use casper_rust_wasm_sdk::types::public_key::PublicKey;
 use casper_rust_wasm_sdk::types::uref::URef;
 use casper_rust_wasm_sdk::{types::verbosity::Verbosity, SDK};
 use config::get_key_pair_from_vesting;
+use confirmation::confirm_deploy;
 use constants::{
-    CHAIN_NAME, COWL_CEP18_TOKEN_CONTRACT_HASH_NAME, COWL_CEP18_TOKEN_CONTRACT_PACKAGE_HASH_NAME,
+    ARG_ENABLE_MINT_BURN, CHAIN_NAME, COWL_CEP18_TOKEN_CONTRACT_HASH_NAME,
+    COWL_CEP18_TOKEN_CONTRACT_PACKAGE_HASH_NAME, COWL_CEP_18_TOKEN_SYMBOL,
     COWL_SET_MODALITIES_CALL_PAYMENT_AMOUNT, COWL_SWAP_NAME,
     COWL_TOKEN_TRANSFER_CALL_PAYMENT_AMOUNT, COWL_UPDATE_TIMES_CALL_PAYMENT_AMOUNT,
     COWL_VESTING_CALL_PAYMENT_AMOUNT, COWL_VESTING_NAME, COWL_WITHDRAW_CSPR_CALL_PAYMENT_AMOUNT,
-    EVENTS_ADDRESS, INSTALLER, RPC_ADDRESS, TTL,
+    INSTALLER, RPC_ADDRESS, TTL,
 };
 use cowl_vesting::constants::{
     ARG_AMOUNT, ARG_EVENTS_MODE, ARG_OWNER, ARG_RECIPIENT, ARG_SPENDER, ARG_VESTING_TYPE,
@@ -36,23 +39,62 @@ use cowl_swap::constants::{
 };
 use cowl_vesting::enums::{EventsMode, VestingType};
 use cowl_vesting::vesting::VestingData;
+use journal::{record_submitted, JournalEntry};
 use keys::{format_base64_to_pem, KeyPair};
 use num_format::{Locale, ToFormattedString};
 use once_cell::sync::Lazy;
 use serde_json::{json, to_string, Value};
 use std::io::Write;
+use std::panic;
 use std::process;
 use std::str::FromStr;
 use std::{
     env,
     fs::File,
     io::{self, Read},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 pub mod config;
+pub mod confirmation;
 pub mod constants;
+pub mod contacts;
+pub mod events;
+pub mod journal;
+pub mod key_ref;
 pub mod keys;
+pub mod receipt;
+pub mod registry;
+pub mod wasm;
+
+// Entry point name fragments hinting that a contract can move the CEP-18 tokens it holds, a
+// name-based guess only
+const CONTRACT_SPENDING_ENTRY_POINTS: [&str; 3] = ["transfer", "withdraw", "claim"];
+
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+static IN_SHELL: AtomicBool = AtomicBool::new(false);
+
+/// Exit code of a command ended within the shell, unwound up to the shell loop.
+#[derive(Debug)]
+pub struct CommandExit(pub i32);
+
+/// Runs the next commands within the shell, where ending a command does not end the process.
+pub fn set_in_shell(in_shell: bool) {
+    IN_SHELL.store(in_shell, Ordering::Relaxed);
+}
+
+/// Ends the current command with an exit code: the process, or within the shell the command
+/// only, the shell reporting the code before reading the next command.
+pub fn exit(code: i32) -> ! {
+    if IN_SHELL.load(Ordering::Relaxed) {
+        // Unwinding runs the destructors of the command, like the file locks and prompt guards
+        panic::resume_unwind(Box::new(CommandExit(code)))
+    }
+    process::exit(code)
+}
 
 pub static SDK_INSTANCE: Lazy<Mutex<Option<Arc<SDK>>>> = Lazy::new(|| Mutex::new(None));
 
@@ -76,7 +118,35 @@ pub fn read_wasm_file(file_path: &str) -> Result<Vec<u8>, io::Error> {
     Ok(buffer)
 }
 
+/// Answers yes to the next prompts, once a whole plan of deploys has been confirmed.
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::Relaxed);
+}
+
+/// Answers yes to the prompts until dropped, then restores the previous answer mode.
+pub struct AssumeYesGuard {
+    previous: bool,
+}
+
+impl Drop for AssumeYesGuard {
+    fn drop(&mut self) {
+        set_assume_yes(self.previous);
+    }
+}
+
+/// Answers yes to the prompts of a confirmed plan while the returned guard lives, including
+/// when a step of the plan returns early with an error.
+pub fn assume_yes() -> AssumeYesGuard {
+    AssumeYesGuard {
+        previous: ASSUME_YES.swap(true, Ordering::Relaxed),
+    }
+}
+
 pub fn prompt_yes_no(question: &str) -> bool {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        log::info!("{} (y/n): y", question);
+        return true;
+    }
     loop {
         log::warn!("{} (y/n): ", question);
         io::stdout().flush().unwrap(); // Ensure the prompt is printed
@@ -92,16 +162,44 @@ pub fn prompt_yes_no(question: &str) -> bool {
     }
 }
 
+/// Asks to type a word to go on, never answered by a confirmed plan.
+pub fn prompt_acknowledge(question: &str, word: &str) -> bool {
+    log::warn!("{} Type '{}' to continue: ", question, word);
+    io::stdout().flush().unwrap(); // Ensure the prompt is printed
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    input.trim() == word
+}
+
 async fn get_contract_hash_keys(
     public_key: &PublicKey,
     contract_name: &str,
     contract_package_name: &str,
+    maybe_state_root_hash: Option<&str>,
+) -> Option<(String, String)> {
+    registry::resolve(contract_name, maybe_state_root_hash, || {
+        query_contract_hash_keys(
+            public_key,
+            contract_name,
+            contract_package_name,
+            maybe_state_root_hash,
+        )
+    })
+    .await
+}
+
+async fn query_contract_hash_keys(
+    public_key: &PublicKey,
+    contract_name: &str,
+    contract_package_name: &str,
+    maybe_state_root_hash: Option<&str>,
 ) -> Option<(String, String)> {
     let query_params: QueryGlobalStateParams = QueryGlobalStateParams {
         key: KeyIdentifierInput::String(public_key.to_account_hash().to_formatted_string()),
         path: None,
         maybe_global_state_identifier: None,
-        state_root_hash: None,
+        state_root_hash: maybe_state_root_hash.map(str::to_string),
         maybe_block_id: None,
         node_address: None,
         verbosity: None,
@@ -155,6 +253,13 @@ async fn get_contract_hash_keys(
 
 // Specific function for getting CEP18 contract hash keys
 pub async fn get_contract_cep18_hash_keys() -> Option<(String, String)> {
+    get_contract_cep18_hash_keys_at(None).await
+}
+
+// Specific function for getting CEP18 contract hash keys at a state root hash
+pub async fn get_contract_cep18_hash_keys_at(
+    maybe_state_root_hash: Option<&str>,
+) -> Option<(String, String)> {
     let public_key = get_key_pair_from_vesting(INSTALLER)
         .await
         .unwrap()
@@ -163,12 +268,20 @@ pub async fn get_contract_cep18_hash_keys() -> Option<(String, String)> {
         &public_key,
         &COWL_CEP18_TOKEN_CONTRACT_HASH_NAME,
         &COWL_CEP18_TOKEN_CONTRACT_PACKAGE_HASH_NAME,
+        maybe_state_root_hash,
     )
     .await
 }
 
 // Specific function for getting Vesting contract hash keys
 pub async fn get_contract_vesting_hash_keys() -> Option<(String, String)> {
+    get_contract_vesting_hash_keys_at(None).await
+}
+
+// Specific function for getting Vesting contract hash keys at a state root hash
+pub async fn get_contract_vesting_hash_keys_at(
+    maybe_state_root_hash: Option<&str>,
+) -> Option<(String, String)> {
     let public_key = get_key_pair_from_vesting(INSTALLER)
         .await
         .unwrap()
@@ -177,12 +290,20 @@ pub async fn get_contract_vesting_hash_keys() -> Option<(String, String)> {
         &public_key,
         &format!("{PREFIX_CONTRACT_NAME}_{}", *COWL_VESTING_NAME),
         &format!("{PREFIX_CONTRACT_PACKAGE_NAME}_{}", *COWL_VESTING_NAME),
+        maybe_state_root_hash,
     )
     .await
 }
 
 // Specific function for getting Swap contract hash keys
 pub async fn get_contract_swap_hash_keys() -> Option<(String, String)> {
+    get_contract_swap_hash_keys_at(None).await
+}
+
+// Specific function for getting Swap contract hash keys at a state root hash
+pub async fn get_contract_swap_hash_keys_at(
+    maybe_state_root_hash: Option<&str>,
+) -> Option<(String, String)> {
     let public_key = get_key_pair_from_vesting(INSTALLER)
         .await
         .unwrap()
@@ -191,91 +312,179 @@ pub async fn get_contract_swap_hash_keys() -> Option<(String, String)> {
         &public_key,
         &format!("{PREFIX_CONTRACT_SWAP_NAME}_{}", *COWL_SWAP_NAME),
         &format!("{PREFIX_CONTRACT_PACKAGE_SWAP_NAME}_{}", *COWL_SWAP_NAME),
+        maybe_state_root_hash,
     )
     .await
 }
 
-pub async fn get_contract_swap_purse(contract_package: &Key) -> Option<URef> {
-    let query_params: QueryGlobalStateParams = QueryGlobalStateParams {
-        key: KeyIdentifierInput::Key(contract_package.clone()),
-        path: None,
+/// Retrieves the parsed value stored under a named key of a contract.
+pub async fn query_contract_named_value(contract_hash: &str, name: &str) -> Option<Value> {
+    let query_params = QueryGlobalStateParams {
+        key: KeyIdentifierInput::String(contract_hash.to_string()),
+        path: Some(PathIdentifierInput::String(name.to_string())),
         maybe_global_state_identifier: None,
         state_root_hash: None,
         maybe_block_id: None,
         node_address: None,
         verbosity: None,
     };
+    let query_contract_key = sdk().query_contract_key(query_params).await.ok()?;
+    let stored_value = serde_json::to_value(&query_contract_key.result.stored_value).ok()?;
+    Some(stored_value["CLValue"]["parsed"].clone())
+}
 
-    let query_global_state = sdk().query_global_state(query_params).await;
-    let query_global_state_result = query_global_state.unwrap_or_else(|_| {
-        panic!("Failed to query global state");
-    });
-
-    let json_string = to_string(&query_global_state_result.result.stored_value)
-        .unwrap_or_else(|_| panic!("Failed to convert stored value to string"));
-
-    let parsed_json: Value =
-        serde_json::from_str(&json_string).unwrap_or_else(|_| panic!("Failed to parse JSON"));
+/// Tells whether a failed query is a value missing from global state, like a dictionary item
+/// never written, rather than an error of the node.
+pub fn is_value_not_found(error: &str) -> bool {
+    error.contains("ValueNotFound") || error.contains("Failed to find")
+}
 
-    let versions = parsed_json["ContractPackage"]["versions"]
-        .as_array()
-        .unwrap_or_else(|| panic!("named_keys is not an array"));
+/// Retrieves the stored value under a key as JSON.
+pub async fn query_stored_value(key: Key, maybe_state_root_hash: Option<&str>) -> Option<Value> {
+    let query_params: QueryGlobalStateParams = QueryGlobalStateParams {
+        key: KeyIdentifierInput::Key(key.clone()),
+        path: None,
+        maybe_global_state_identifier: None,
+        state_root_hash: maybe_state_root_hash.map(str::to_string),
+        maybe_block_id: None,
+        node_address: None,
+        verbosity: None,
+    };
 
-    // Find the contract hash
-    let contract_hash = versions
-        .last()
-        .and_then(|obj| obj.get("contract_hash"))
-        .and_then(|value| value.as_str())
-        .unwrap_or_else(|| {
-            log::debug!("Contract hash not found in the last version");
-            ""
-        });
+    match sdk().query_global_state(query_params).await {
+        Ok(query_global_state) => {
+            serde_json::to_value(&query_global_state.result.stored_value).ok()
+        }
+        Err(err) => {
+            log::debug!(
+                "Failed to query global state for {}: {:?}",
+                key.to_formatted_string(),
+                err
+            );
+            None
+        }
+    }
+}
 
-    let contract_hash = ContractHash::from_formatted_str(contract_hash)
-        .unwrap_or_else(|_| panic!("contract_hash not found in package versions"));
+/// Retrieves a contract as JSON from its hash, or from the last version of its package.
+pub async fn get_contract(key: &Key, maybe_state_root_hash: Option<&str>) -> Option<Value> {
+    let stored_value = query_stored_value(key.clone(), maybe_state_root_hash).await?;
+    if !stored_value["Contract"].is_null() {
+        return Some(stored_value["Contract"].clone());
+    }
 
+    // Find the contract hash of the last version
+    let contract_hash = stored_value["ContractPackage"]["versions"]
+        .as_array()
+        .and_then(|versions| versions.last())
+        .and_then(|version| version["contract_hash"].as_str())?;
+    let contract_hash = ContractHash::from_formatted_str(contract_hash).ok()?;
     let contract_key = Key::from_formatted_str(
         &contract_hash
             .to_formatted_string()
             .replace("contract", "hash"),
     )
-    .unwrap_or_else(|_| panic!("contract_hash should convert to key"));
+    .ok()?;
 
-    let query_params: QueryGlobalStateParams = QueryGlobalStateParams {
-        key: KeyIdentifierInput::Key(contract_key),
-        path: None,
-        maybe_global_state_identifier: None,
-        state_root_hash: None,
-        maybe_block_id: None,
-        node_address: None,
-        verbosity: None,
+    let stored_value = query_stored_value(contract_key, maybe_state_root_hash).await?;
+    Some(stored_value["Contract"].clone()).filter(|contract| !contract.is_null())
+}
+
+/// Retrieves the `purse` named key of a contract or contract package.
+pub async fn get_contract_purse(key: &Key, maybe_state_root_hash: Option<&str>) -> Option<URef> {
+    let name = format!("purse_{}", key.to_formatted_string());
+    let purse_uref_string = registry::resolve(&name, maybe_state_root_hash, || async {
+        let contract = get_contract(key, maybe_state_root_hash).await?;
+
+        // Find the purse
+        let purse_uref_string = contract["named_keys"]
+            .as_array()?
+            .iter()
+            .find(|obj| obj["name"] == Value::String("purse".to_string()))
+            .and_then(|obj| obj["key"].as_str())
+            .map(str::to_string);
+        if purse_uref_string.is_none() {
+            log::debug!("Contract purse key not found in named_keys");
+        }
+        purse_uref_string
+    })
+    .await?;
+    URef::from_formatted_str(&purse_uref_string).ok()
+}
+
+/// Warns when a contract recipient does not exist, or when none of its entry point names looks
+/// like a transfer or a withdrawal. The latter is a heuristic on names only: a contract may move
+/// tokens through any entry point, or none named so.
+pub async fn warn_if_contract_cannot_spend(key: &Key) {
+    if key.clone().into_account().is_some() {
+        return;
+    }
+    let formatted_key = key.to_formatted_string();
+    let Some(contract) = get_contract(key, None).await else {
+        log::warn!("No contract found at {formatted_key}, tokens sent to it may be locked");
+        return;
     };
 
-    let query_global_state = sdk().query_global_state(query_params).await;
-    let query_global_state_result = query_global_state.unwrap_or_else(|_| {
-        panic!("Failed to query global state");
-    });
+    let can_spend = contract["entry_points"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry_point| entry_point["name"].as_str())
+        .any(|name| {
+            CONTRACT_SPENDING_ENTRY_POINTS
+                .iter()
+                .any(|spending_name| name.contains(spending_name))
+        });
+    if !can_spend {
+        log::warn!(
+            "No entry point of contract {formatted_key} is named like a transfer, withdraw or \
+            claim, check that it can spend the {} tokens it receives (guess from entry point names)",
+            *COWL_CEP_18_TOKEN_SYMBOL
+        );
+    }
+}
 
-    let json_string = to_string(&query_global_state_result.result.stored_value)
-        .unwrap_or_else(|_| panic!("Failed to convert stored value to string"));
+// Function to retrieve the header of a block from its hash or height
+pub async fn get_block_header(block_identifier: &str) -> Option<Value> {
+    if block_identifier.is_empty() {
+        return None;
+    }
+    get_block(Some(block_identifier))
+        .await
+        .map(|block| block["header"].clone())
+}
 
-    let parsed_json: Value =
-        serde_json::from_str(&json_string).unwrap_or_else(|_| panic!("Failed to parse JSON"));
+/// Retrieves the state root hash of a block from its hash or height.
+pub async fn get_state_root_hash(block_identifier: &str) -> Option<String> {
+    get_block_header(block_identifier)
+        .await
+        .and_then(|header| header["state_root_hash"].as_str().map(str::to_string))
+}
 
-    let named_keys = parsed_json["Contract"]["named_keys"]
-        .as_array()
-        .unwrap_or_else(|| panic!("named_keys is not an array"));
+/// Retrieves a block by hash or height as JSON, the latest block if no identifier is given.
+pub async fn get_block(block_identifier: Option<&str>) -> Option<Value> {
+    let get_block = sdk()
+        .get_block(
+            block_identifier
+                .map(|block_identifier| BlockIdentifierInput::String(block_identifier.to_string())),
+            None,
+            None,
+        )
+        .await;
 
-    // Find the purse
-    let purse_uref_string = named_keys
-        .iter()
-        .find(|obj| obj["name"] == Value::String("purse".to_string()))
-        .and_then(|obj| obj["key"].as_str())
-        .unwrap_or_else(|| {
-            log::debug!("Contract purse key not found in named_keys");
-            ""
-        });
-    URef::from_formatted_str(purse_uref_string).ok()
+    let get_block_result = match get_block {
+        Ok(get_block) => serde_json::to_value(&get_block.result).ok()?,
+        Err(err) => {
+            log::warn!(
+                "Failed to retrieve block {}: {:?}",
+                block_identifier.unwrap_or("latest"),
+                err
+            );
+            return None;
+        }
+    };
+
+    Some(get_block_result["block"].clone())
 }
 
 pub fn get_dictionary_item_params(
@@ -388,48 +597,26 @@ async fn execute_contract_entry_point(
 
     if deploy_hash_as_string.is_empty() {
         log::error!("Failed to retrieve deploy hash");
-        process::exit(1)
+        exit(1)
     }
 
+    record_submitted(JournalEntry {
+        contract_package: Some(contract_token_package_hash.to_string()),
+        entry_point: Some(entry_point.to_string()),
+        ..JournalEntry::new(
+            &deploy_hash_as_string,
+            &public_key.to_string(),
+            payment_amount,
+        )
+    });
+
     log::info!(
         "Wait deploy_hash for entry point {} {}",
         entry_point,
         deploy_hash_as_string
     );
 
-    let event_parse_result: EventParseResult = sdk()
-        .wait_deploy(&EVENTS_ADDRESS, &deploy_hash_as_string, None)
-        .await
-        .unwrap();
-
-    let motes = event_parse_result
-        .clone()
-        .body
-        .unwrap()
-        .deploy_processed
-        .unwrap()
-        .execution_result
-        .success
-        .unwrap_or_else(|| {
-            log::error!("Could not retrieve cost for deploy hash {deploy_hash_as_string}");
-            log::error!("{:?}", &event_parse_result);
-            process::exit(1)
-        })
-        .cost;
-
-    let cost = format_with_thousands_separator(&motes_to_cspr(&motes).unwrap());
-
-    let finalized_approvals = true;
-    let get_deploy = sdk()
-        .get_deploy(deploy_hash, Some(finalized_approvals), None, None)
-        .await;
-    let get_deploy = get_deploy.unwrap();
-    let result = DeployHash::from(get_deploy.result.deploy.hash).to_string();
-
-    log::info!("Processed deploy hash {result}");
-    log::info!("Cost {cost} CSPR ({motes} motes)");
-
-    (result, cost)
+    confirm_deploy(&deploy_hash_as_string).await
 }
 
 pub async fn call_vesting_entry_point(
@@ -458,24 +645,33 @@ pub async fn call_vesting_entry_point(
     .await;
 }
 
+/// Sets the modalities of a contract, leaving the ones not given unchanged.
 pub async fn call_set_modalities_entry_point(
-    contract_vesting_package: &str,
-    events_mdoe: EventsMode,
+    contract_package: &str,
+    maybe_events_mode: Option<EventsMode>,
+    maybe_enable_mint_burn: Option<bool>,
 ) -> (String, String) {
     let key_pair = get_key_pair_from_vesting(INSTALLER).await.unwrap();
-    let args = json!([
-        {
+    let mut args = vec![];
+    if let Some(events_mode) = maybe_events_mode {
+        args.push(json!({
             "name": ARG_EVENTS_MODE,
             "type": "U8",
-            "value": events_mdoe as u8
-        },
-    ])
-    .to_string();
+            "value": events_mode as u8
+        }));
+    }
+    if let Some(enable_mint_burn) = maybe_enable_mint_burn {
+        args.push(json!({
+            "name": ARG_ENABLE_MINT_BURN,
+            "type": "Bool",
+            "value": enable_mint_burn
+        }));
+    }
 
     execute_contract_entry_point(
-        contract_vesting_package,
+        contract_package,
         ENTRY_POINT_SET_MODALITIES,
-        &args,
+        &Value::Array(args).to_string(),
         &COWL_SET_MODALITIES_CALL_PAYMENT_AMOUNT,
         &key_pair.public_key,
         format_base64_to_pem(&key_pair.private_key_base64.unwrap()),
@@ -533,7 +729,7 @@ pub async fn call_token_set_allowance_entry_point(
     spender: &Key,
     amount: String,
     decrease: bool,
-) {
+) -> (String, String) {
     let args = json!([
         {
             "name": ARG_SPENDER,
@@ -562,7 +758,7 @@ pub async fn call_token_set_allowance_entry_point(
         public_key,
         secret_key,
     )
-    .await;
+    .await
 }
 
 pub async fn call_withdraw_cowl_entry_point(
@@ -0,0 +1,95 @@
+use super::{
+    events::{extract_events, get_event_sources},
+    format_with_thousands_separator, get_block_header, sdk,
+};
+use casper_rust_wasm_sdk::{helpers::motes_to_cspr, types::deploy_hash::DeployHash};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+
+pub const DEPLOY_STATUS_PENDING: &str = "Pending";
+pub const DEPLOY_STATUS_SUCCESS: &str = "Success";
+pub const DEPLOY_STATUS_FAILURE: &str = "Failure";
+
+/// Retrieves a deploy and its execution results as JSON.
+pub async fn get_deploy_json(deploy_hash: &str) -> Result<Value, String> {
+    let deploy_hash_key = DeployHash::new(deploy_hash)
+        .map_err(|err| format!("Invalid deploy hash {deploy_hash}: {:?}", err))?;
+
+    let finalized_approvals = true;
+    let get_deploy = sdk()
+        .get_deploy(deploy_hash_key, Some(finalized_approvals), None, None)
+        .await
+        .map_err(|err| format!("Failed to retrieve deploy {deploy_hash}: {:?}", err))?;
+
+    serde_json::to_value(&get_deploy.result)
+        .map_err(|err| format!("Failed to serialize deploy {deploy_hash}: {}", err))
+}
+
+pub async fn get_deploy_receipt(deploy_hash: &str) -> Result<IndexMap<String, Value>, String> {
+    let get_deploy_result = get_deploy_json(deploy_hash).await?;
+
+    let mut receipt: IndexMap<String, Value> = IndexMap::new();
+    receipt.insert("deploy_hash".to_string(), json!(deploy_hash));
+    receipt.insert(
+        "account".to_string(),
+        get_deploy_result["deploy"]["header"]["account"].clone(),
+    );
+    receipt.insert(
+        "deploy_timestamp".to_string(),
+        get_deploy_result["deploy"]["header"]["timestamp"].clone(),
+    );
+
+    let execution_result = match get_deploy_result["execution_results"]
+        .as_array()
+        .and_then(|results| results.first())
+    {
+        Some(execution_result) => execution_result,
+        None => {
+            receipt.insert("status".to_string(), json!(DEPLOY_STATUS_PENDING));
+            return Ok(receipt);
+        }
+    };
+
+    let (status, result) = if let Some(success) = execution_result["result"].get("Success") {
+        (DEPLOY_STATUS_SUCCESS, success)
+    } else if let Some(failure) = execution_result["result"].get("Failure") {
+        (DEPLOY_STATUS_FAILURE, failure)
+    } else {
+        return Err(format!(
+            "Unexpected execution result for deploy {deploy_hash}: {}",
+            execution_result["result"]
+        ));
+    };
+    receipt.insert("status".to_string(), json!(status));
+
+    if let Some(error_message) = result["error_message"].as_str() {
+        receipt.insert("error_message".to_string(), json!(error_message));
+    }
+
+    let motes = result["cost"].as_str().unwrap_or_default();
+    receipt.insert("cost_motes".to_string(), json!(motes));
+    receipt.insert(
+        "cost_CSPR".to_string(),
+        json!(format_with_thousands_separator(
+            &motes_to_cspr(motes).unwrap_or_default()
+        )),
+    );
+
+    let block_hash = execution_result["block_hash"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    receipt.insert("block_hash".to_string(), json!(block_hash));
+    if let Some(header) = get_block_header(&block_hash).await {
+        receipt.insert("block_height".to_string(), header["height"].clone());
+        receipt.insert("block_timestamp".to_string(), header["timestamp"].clone());
+    }
+
+    receipt.insert("transfers".to_string(), result["transfers"].clone());
+
+    let event_sources = get_event_sources().await;
+    let events = extract_events(&execution_result["result"], &event_sources);
+    receipt.insert("events".to_string(), json!(events));
+
+    Ok(receipt)
+}
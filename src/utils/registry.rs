@@ -0,0 +1,153 @@
+use super::constants::{CHAIN_NAME, REGISTRY_CACHE_FILE_PATH};
+use indexmap::IndexMap;
+use once_cell::sync::Lazy;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::{self, OpenOptions},
+    future::Future,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+// Values resolved from the chain by name, at the latest state (`None`) or at a state root hash
+type RegistryKey = (Option<String>, String);
+
+// Values at a state root hash never change, they are kept on disk by network and state root
+type DiskRegistry = IndexMap<String, IndexMap<String, IndexMap<String, Value>>>;
+
+static REGISTRY: Lazy<Mutex<HashMap<RegistryKey, Value>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// One lock per value, held while it is resolved, so that concurrent callers query it once
+static RESOLVING: Lazy<Mutex<HashMap<RegistryKey, Arc<tokio::sync::Mutex<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn load_disk_registry(path: &str) -> DiskRegistry {
+    if !Path::new(path).exists() {
+        return DiskRegistry::new();
+    }
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| {
+            log::warn!("Ignoring invalid registry cache {path}");
+            DiskRegistry::new()
+        })
+}
+
+fn lookup(name: &str, maybe_state_root_hash: Option<&str>) -> Option<Value> {
+    let registry_key = (maybe_state_root_hash.map(str::to_string), name.to_string());
+    if let Some(value) = REGISTRY.lock().unwrap().get(&registry_key) {
+        return Some(value.clone());
+    }
+
+    let (path, state_root_hash) = REGISTRY_CACHE_FILE_PATH
+        .as_deref()
+        .zip(maybe_state_root_hash)?;
+    let value = load_disk_registry(path)
+        .get(CHAIN_NAME.as_str())?
+        .get(state_root_hash)?
+        .get(name)?
+        .clone();
+    REGISTRY.lock().unwrap().insert(registry_key, value.clone());
+    Some(value)
+}
+
+/// Adds a value to the registry file. Writers, in this process or another one, are serialized
+/// by a lock file so that no value is lost, and the file is replaced by a rename so that a
+/// reader never sees it half written.
+fn record_on_disk(
+    path: &str,
+    state_root_hash: &str,
+    name: &str,
+    value: Value,
+) -> Result<(), Box<dyn Error>> {
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(format!("{path}.lock"))?;
+    lock_file.lock()?;
+
+    let mut disk_registry = load_disk_registry(path);
+    disk_registry
+        .entry(CHAIN_NAME.to_string())
+        .or_default()
+        .entry(state_root_hash.to_string())
+        .or_default()
+        .insert(name.to_string(), value);
+    let temp_path = format!("{path}.tmp");
+    let written = fs::write(&temp_path, serde_json::to_string_pretty(&disk_registry)?)
+        .and_then(|_| fs::rename(&temp_path, path));
+
+    lock_file.unlock()?;
+    Ok(written?)
+}
+
+fn record(name: &str, maybe_state_root_hash: Option<&str>, value: Value) {
+    REGISTRY.lock().unwrap().insert(
+        (maybe_state_root_hash.map(str::to_string), name.to_string()),
+        value.clone(),
+    );
+
+    let Some((path, state_root_hash)) = REGISTRY_CACHE_FILE_PATH
+        .as_deref()
+        .zip(maybe_state_root_hash)
+    else {
+        return;
+    };
+    if let Err(err) = record_on_disk(path, state_root_hash, name, value) {
+        log::warn!("Failed to write registry cache {path}: {err}");
+    }
+}
+
+fn lookup_as<T: DeserializeOwned>(name: &str, maybe_state_root_hash: Option<&str>) -> Option<T> {
+    lookup(name, maybe_state_root_hash).and_then(|value| serde_json::from_value(value).ok())
+}
+
+/// Resolves a value once per process and state, like the contract hashes of the Installer,
+/// concurrent callers waiting for the first one to query it. Values missing on chain are not
+/// kept, so a later install is found.
+pub async fn resolve<T, F, Fut>(
+    name: &str,
+    maybe_state_root_hash: Option<&str>,
+    query: F,
+) -> Option<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    if let Some(value) = lookup_as(name, maybe_state_root_hash) {
+        return Some(value);
+    }
+
+    let resolving = RESOLVING
+        .lock()
+        .unwrap()
+        .entry((maybe_state_root_hash.map(str::to_string), name.to_string()))
+        .or_default()
+        .clone();
+    let _resolving_guard = resolving.lock().await;
+    // Resolved by another caller while waiting
+    if let Some(value) = lookup_as(name, maybe_state_root_hash) {
+        return Some(value);
+    }
+
+    let value = query().await?;
+    if let Ok(json_value) = serde_json::to_value(&value) {
+        record(name, maybe_state_root_hash, json_value);
+    }
+    Some(value)
+}
+
+/// Forgets the values resolved at the latest state, after a deploy or on `reload`.
+pub fn clear_latest() {
+    REGISTRY
+        .lock()
+        .unwrap()
+        .retain(|(maybe_state_root_hash, _), _| maybe_state_root_hash.is_some());
+}
@@ -0,0 +1,167 @@
+use super::{
+    constants::{DEFAULT_WASM_PATH, WASM_LOCK_FILE_PATH, WASM_PATH_FILE_PATH},
+    exit,
+};
+use indexmap::IndexMap;
+use sha2::{Digest, Sha256};
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+const WASM_EXPORT_SECTION: u8 = 7;
+const WASM_EXPORT_FUNCTION: u8 = 0;
+
+/// SHA-256 hashes of the wasm files, by wasm name.
+pub type WasmLock = IndexMap<String, String>;
+
+static ALLOW_UNLOCKED_WASM: AtomicBool = AtomicBool::new(false);
+
+/// Deploys wasm files missing from or differing from the lockfile, with a warning.
+pub fn set_allow_unlocked_wasm(allow_unlocked_wasm: bool) {
+    ALLOW_UNLOCKED_WASM.store(allow_unlocked_wasm, Ordering::Relaxed);
+}
+
+/// Directory of the wasm files: `WASM_PATH`, then the directory set by `wasm path`, then
+/// `./wasm/`.
+pub fn wasm_dir() -> String {
+    if let Ok(wasm_path) = env::var("WASM_PATH") {
+        return wasm_path;
+    }
+    fs::read_to_string(WASM_PATH_FILE_PATH.as_str())
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|wasm_path| !wasm_path.is_empty())
+        .unwrap_or_else(|| DEFAULT_WASM_PATH.to_string())
+}
+
+pub fn set_wasm_dir(wasm_path: &str) -> Result<(), Box<dyn Error>> {
+    if !Path::new(wasm_path).is_dir() {
+        return Err(format!("{wasm_path} is not a directory").into());
+    }
+    fs::write(WASM_PATH_FILE_PATH.as_str(), format!("{wasm_path}\n"))?;
+    Ok(())
+}
+
+pub fn wasm_file_path(name: &str) -> String {
+    Path::new(&wasm_dir())
+        .join(format!("{name}.wasm"))
+        .to_string_lossy()
+        .to_string()
+}
+
+pub fn wasm_hash(module_bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(module_bytes))
+}
+
+pub fn load_wasm_lock() -> Result<WasmLock, Box<dyn Error>> {
+    if !Path::new(WASM_LOCK_FILE_PATH.as_str()).exists() {
+        return Ok(WasmLock::new());
+    }
+    let content = fs::read_to_string(WASM_LOCK_FILE_PATH.as_str())?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn save_wasm_lock(wasm_lock: &WasmLock) -> Result<(), Box<dyn Error>> {
+    fs::write(
+        WASM_LOCK_FILE_PATH.as_str(),
+        serde_json::to_string_pretty(wasm_lock)?,
+    )?;
+    Ok(())
+}
+
+/// Hashes the wasm about to be deployed. A wasm missing from or differing from the lockfile is
+/// refused unless unlocked wasm files are allowed, without a lockfile it is only warned about.
+pub fn check_wasm_hash(name: &str, module_bytes: &[u8]) -> String {
+    let hash = wasm_hash(module_bytes);
+    if !Path::new(WASM_LOCK_FILE_PATH.as_str()).exists() {
+        log::warn!(
+            "No {} to check {name}.wasm against, run `wasm lock` to pin its hash",
+            *WASM_LOCK_FILE_PATH
+        );
+        return hash;
+    }
+
+    let mismatch = match load_wasm_lock() {
+        Ok(wasm_lock) => match wasm_lock.get(name) {
+            Some(locked_hash) if *locked_hash == hash => return hash,
+            Some(locked_hash) => format!(
+                "{name}.wasm hash {hash} differs from {locked_hash} in {}",
+                *WASM_LOCK_FILE_PATH
+            ),
+            None => format!("{name}.wasm is not locked in {}", *WASM_LOCK_FILE_PATH),
+        },
+        Err(err) => format!("Failed to read {}: {}", *WASM_LOCK_FILE_PATH, err),
+    };
+    if !ALLOW_UNLOCKED_WASM.load(Ordering::Relaxed) {
+        log::error!("{mismatch}, run `wasm lock` or pass --allow-unlocked-wasm to deploy it");
+        exit(1)
+    }
+    log::warn!("{mismatch}, deployed as unlocked wasm files are allowed");
+    hash
+}
+
+fn read_leb128(bytes: &[u8], offset: &mut usize) -> Result<u32, String> {
+    let mut result: u32 = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *bytes
+            .get(*offset)
+            .ok_or_else(|| "unexpected end of module".to_string())?;
+        *offset += 1;
+        if shift > 28 {
+            return Err("invalid LEB128 integer".to_string());
+        }
+        result |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+    }
+}
+
+fn read_bytes<'a>(bytes: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    let end = offset
+        .checked_add(len)
+        .filter(|end| *end <= bytes.len())
+        .ok_or_else(|| "unexpected end of module".to_string())?;
+    let slice = &bytes[*offset..end];
+    *offset = end;
+    Ok(slice)
+}
+
+/// Names of the functions exported by a module, the entry points of a contract or session.
+pub fn exported_functions(module_bytes: &[u8]) -> Result<Vec<String>, String> {
+    if module_bytes.len() < 8 || &module_bytes[..4] != WASM_MAGIC {
+        return Err("not a wasm module".to_string());
+    }
+
+    let mut offset = 8;
+    let mut functions = vec![];
+    while offset < module_bytes.len() {
+        let section_id = module_bytes[offset];
+        offset += 1;
+        let section_size = read_leb128(module_bytes, &mut offset)? as usize;
+        let section = read_bytes(module_bytes, &mut offset, section_size)?;
+        if section_id != WASM_EXPORT_SECTION {
+            continue;
+        }
+
+        let mut section_offset = 0;
+        let count = read_leb128(section, &mut section_offset)?;
+        for _ in 0..count {
+            let name_len = read_leb128(section, &mut section_offset)? as usize;
+            let name = read_bytes(section, &mut section_offset, name_len)?;
+            let kind = read_bytes(section, &mut section_offset, 1)?[0];
+            read_leb128(section, &mut section_offset)?;
+            if kind == WASM_EXPORT_FUNCTION {
+                functions.push(String::from_utf8_lossy(name).to_string());
+            }
+        }
+    }
+    Ok(functions)
+}
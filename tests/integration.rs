@@ -1,13 +1,66 @@
 mod tests_install_vesting {
-    use std::sync::Arc;
+    use std::{io::Write, sync::Arc};
 
     use assert_cmd::Command;
-    use cowl_cli::utils::constants::COWL_CEP_18_TOKEN_SYMBOL;
+    use cowl_cli::utils::{
+        config,
+        constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL, JOURNAL_FILE_PATH},
+        get_block, get_contract_cep18_hash_keys, get_state_root_hash,
+    };
+    use cowl_vesting::enums::EventsMode;
     use once_cell::sync::Lazy;
+    use predicates::prelude::PredicateBooleanExt;
+    use regex::Regex;
     use tokio::{sync::Mutex, test};
 
     const BINARY: &str = "cowl_cli";
 
+    // Runs a command expected to succeed and returns its output
+    fn run_success(cmd: &mut Command) -> String {
+        let output = cmd.assert().success().get_output().stdout.clone();
+        String::from_utf8_lossy(&output).to_string()
+    }
+
+    // First capture of a pattern in a command output
+    fn capture(output: &str, pattern: &str) -> String {
+        Regex::new(pattern)
+            .unwrap()
+            .captures(output)
+            .unwrap_or_else(|| panic!("{pattern} not found in output:\n{output}"))[1]
+            .to_string()
+    }
+
+    fn token_balance_at(vesting_type: &str, block_height: u64) -> u128 {
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("balance")
+                .arg("--vesting-type")
+                .arg(vesting_type)
+                .arg("--block-height")
+                .arg(block_height.to_string()),
+        );
+        capture(
+            &output,
+            &format!("\"balance_{}\": \"(\\d+)\"", *COWL_CEP_18_COOL_SYMBOL),
+        )
+        .parse()
+        .unwrap()
+    }
+
+    fn cspr_balance_motes(key: &str) -> u128 {
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("balance")
+                .arg("--key")
+                .arg(key),
+        );
+        capture(&output, "\"balance_motes\": \"(\\d+)\"")
+            .parse()
+            .unwrap()
+    }
+
     static SETUP_DONE: Lazy<Arc<Mutex<Option<bool>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
 
     // Run the setup (deploy the contract) only if not done yet
@@ -163,6 +216,575 @@ mod tests_install_vesting {
             ));
     }
 
+    #[tokio::test]
+    async fn test_balance_at_block_height() {
+        setup().await;
+        let amount: u128 = 100000000000;
+        let base64_key = "MC4CAQAwBQYDK2VwBCIEIOeKQNbCmsyZme2t5U7Lulnn2TfdZkiFANeg89Sy7Pzn";
+
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("transfer")
+                .arg("--from")
+                .arg("01fbe77037c317c12af3a6af08d02d9fc6b3a1636237ae48f77b198a9483d94801") // Liquidity
+                .arg("--to")
+                .arg("Treasury")
+                .arg("--amount")
+                .arg(amount.to_string())
+                .write_stdin(format!("{base64_key}\ny\n")),
+        );
+        let deploy_hash = capture(&output, "Processed deploy hash ([0-9a-f]{64})");
+
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("deploy-status")
+                .arg(&deploy_hash),
+        );
+        let block_height: u64 = capture(&output, "\"block_height\": (\\d+)")
+            .parse()
+            .unwrap();
+
+        // Balances before and at the block of the transfer differ by the amount
+        let balance_before = token_balance_at("Treasury", block_height - 1);
+        let balance_after = token_balance_at("Treasury", block_height);
+        assert_eq!(balance_after, balance_before + amount);
+    }
+
+    #[tokio::test]
+    async fn test_registry_cache_with_concurrent_writers() {
+        setup().await;
+        let height = get_block(None).await.unwrap()["header"]["height"]
+            .as_u64()
+            .unwrap();
+        let heights: Vec<u64> = (0..4).map(|offset| height - offset).collect();
+        let dir = tempfile::tempdir().unwrap();
+        let registry_path = dir.path().join("registry.json");
+
+        // Commands at several states record their contract hashes in the same file at once
+        let handles: Vec<_> = heights
+            .iter()
+            .map(|height| {
+                let registry_path = registry_path.clone();
+                let height = height.to_string();
+                std::thread::spawn(move || {
+                    Command::cargo_bin(BINARY)
+                        .unwrap()
+                        .env("REGISTRY_CACHE_FILE_PATH", &registry_path)
+                        .arg("balance")
+                        .arg("--vesting-type")
+                        .arg("Treasury")
+                        .arg("--block-height")
+                        .arg(height)
+                        .assert()
+                        .success();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let registry: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&registry_path).unwrap()).unwrap();
+        let state_roots = registry.as_object().unwrap().values().next().unwrap();
+        for height in heights {
+            let state_root_hash = get_state_root_hash(&height.to_string()).await.unwrap();
+            assert!(
+                state_roots.get(&state_root_hash).is_some(),
+                "state root {state_root_hash} of block {height} missing from the registry"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_balance_with_conflicting_state_identifiers() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+
+        cmd.arg("balance")
+            .arg("--vesting-type")
+            .arg("Community")
+            .arg("--block-height")
+            .arg("1")
+            .arg("--state-root-hash")
+            .arg("0000000000000000000000000000000000000000000000000000000000000000")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("cannot be used with"));
+    }
+
+    #[tokio::test]
+    async fn test_balance_with_contact_alias() {
+        setup().await;
+        let contacts_file = tempfile::NamedTempFile::new().unwrap();
+        let contacts_file_path = contacts_file.path().to_str().unwrap();
+        let alias = "community_wallet";
+        let key = "016fd7fb5f002d82f3813c76ac83940d4d886035395ddd9be66c9a4a2993b63aaf"; // Community
+
+        std::fs::write(contacts_file_path, "{}").unwrap();
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("CONTACTS_FILE_PATH", contacts_file_path)
+            .arg("contacts")
+            .arg("add")
+            .arg(alias)
+            .arg(key)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(format!(
+                "Command executed: Add contact {alias} for {key}"
+            )));
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("CONTACTS_FILE_PATH", contacts_file_path)
+            .arg("balance")
+            .arg("--key")
+            .arg(alias)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(format!("Balance for {alias}")))
+            .stdout(predicates::str::contains(format!("\"alias\": \"{alias}\"")));
+    }
+
+    #[tokio::test]
+    async fn test_balance_rejects_uref_key() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        let uref = "uref-2a2d5e0e5b9a5b4d1e4b9e8a3c7d6f1e2a3b4c5d6e7f8091a2b3c4d5e6f70819-007";
+
+        cmd.arg("balance")
+            .arg("--key")
+            .arg(uref)
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("is a uref key"))
+            .stderr(predicates::str::contains(
+                "expected a public key, an account hash or a contract or package hash",
+            ));
+    }
+
+    #[tokio::test]
+    async fn test_balance_with_misspelled_vesting_type() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("balance")
+            .arg("--key")
+            .arg("comunity")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("Did you mean 'Community'?"));
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_missing_plan() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("bootstrap")
+            .arg("--plan")
+            .arg("missing_bootstrap.json")
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains(
+                "Failed to read missing_bootstrap.json",
+            ));
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_skips_applied_steps() {
+        setup().await;
+        let mut plan = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            plan,
+            r#"{{"allocations": [{{"to": "Treasury", "amount": "1"}}]}}"#
+        )
+        .unwrap();
+
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("bootstrap")
+                .arg("--plan")
+                .arg(plan.path())
+                .write_stdin("y\n"),
+        );
+        for step in [
+            "deploy token contract",
+            "deploy vesting contract",
+            "deploy swap contract",
+            "allocate tokens to Treasury",
+        ] {
+            assert!(
+                output.contains(&format!("\"{step}\": \"skipped\"")),
+                "{step} not skipped in output:\n{output}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_reports_token_drifts() {
+        setup().await;
+        let mut manifest = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            manifest,
+            r#"{{"token": {{"symbol": "DRIFT", "events_mode": 0, "enable_mint_burn": false}}, "vesting": {{"events_mode": 0}}}}"#
+        )
+        .unwrap();
+
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("plan")
+                .arg("--manifest")
+                .arg(manifest.path()),
+        );
+        assert!(output.contains(&format!(
+            "token symbol is {} on chain, DRIFT in the manifest",
+            *COWL_CEP_18_TOKEN_SYMBOL
+        )));
+        assert!(output.contains(&format!(
+            "token events_mode is {} on chain, 0 in the manifest",
+            EventsMode::CES as u8
+        )));
+        assert!(output.contains("token enable_mint_burn is 1 on chain, 0 in the manifest"));
+        assert!(output.contains(&format!(
+            "vesting events_mode is {} on chain, 0 in the manifest",
+            EventsMode::CES as u8
+        )));
+        // Drifts cannot be fixed by an upgrade or a call
+        assert!(output.contains("\"actions\": []"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_missing_manifest() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("plan")
+            .arg("--manifest")
+            .arg("missing_manifest.json")
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains(
+                "Failed to read missing_manifest.json",
+            ));
+    }
+
+    #[tokio::test]
+    async fn test_wasm_verify_without_lockfile() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("WASM_LOCK_FILE_PATH", "missing_wasm.lock")
+            .arg("wasm")
+            .arg("verify")
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains("run `wasm lock` first"));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_refuses_wasm_differing_from_lockfile() {
+        setup().await;
+        let mut wasm_lock = tempfile::NamedTempFile::new().unwrap();
+        write!(wasm_lock, r#"{{"cowl_cep18": "{}"}}"#, "0".repeat(64)).unwrap();
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("WASM_LOCK_FILE_PATH", wasm_lock.path())
+            .arg("deploy")
+            .arg("--token")
+            .write_stdin("y\n")
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains(format!(
+                "differs from {} in",
+                "0".repeat(64)
+            )))
+            .stdout(predicates::str::contains("--allow-unlocked-wasm"))
+            .stdout(predicates::str::contains("Processed deploy hash").not());
+    }
+
+    #[tokio::test]
+    async fn test_contracts_versions_requires_contract() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("contracts")
+            .arg("versions")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("--token"));
+    }
+
+    #[tokio::test]
+    async fn test_contracts_versions_lists_live_version() {
+        setup().await;
+        config::init().await;
+        let (contract_hash, contract_package_hash) = get_contract_cep18_hash_keys().await.unwrap();
+        // Hashes are compared without their prefix
+        let hex = |hash: &str| hash[hash.len() - 64..].to_string();
+
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("contracts")
+                .arg("versions")
+                .arg("--token"),
+        );
+        assert_eq!(
+            hex(&capture(&output, "\"contract_package_hash\": \"([^\"]+)\"")),
+            hex(&contract_package_hash)
+        );
+        assert!(output.contains("\"live\": true"));
+        // The contract hash of the Installer named keys is a version of the package
+        assert!(output.contains(&hex(&contract_hash)));
+    }
+
+    #[tokio::test]
+    async fn test_contracts_preflight_single_contract() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("contracts")
+            .arg("preflight")
+            .arg("--token")
+            .arg("--swap")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("cannot be used with"));
+    }
+
+    #[tokio::test]
+    async fn test_contracts_preflight_same_wasm() {
+        setup().await;
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("contracts")
+            .arg("preflight")
+            .arg("--token")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("\"removed\": []"))
+            .stdout(predicates::str::contains("\"breaking\": []"))
+            .stdout(predicates::str::contains("\"blocking\": []"))
+            // The report states that the upgrade was not dry-run
+            .stdout(predicates::str::contains("\"simulation\": \"unavailable: "));
+    }
+
+    #[tokio::test]
+    async fn test_modalities_mint_burn_token_only() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("modalities")
+            .arg("set")
+            .arg("--contract")
+            .arg("vesting")
+            .arg("--enable-mint-burn")
+            .arg("true")
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains(
+                "Only the token contract supports enable_mint_burn",
+            ));
+    }
+
+    #[tokio::test]
+    async fn test_modalities_set_and_get() {
+        setup().await;
+        // Setting the modalities of the install keeps the token unchanged for other tests
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("modalities")
+            .arg("set")
+            .arg("--contract")
+            .arg("token")
+            .arg("--events-mode")
+            .arg("CES")
+            .arg("--enable-mint-burn")
+            .arg("true")
+            .write_stdin("y\n")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Modalities of the token contract set",
+            ));
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("modalities")
+            .arg("get")
+            .arg("--contract")
+            .arg("token")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("\"events_mode\": \"CES\""))
+            // The flag is stored as a U8 or a Bool depending on the token version
+            .stdout(
+                predicates::str::contains("\"enable_mint_burn\": 1")
+                    .or(predicates::str::contains("\"enable_mint_burn\": true")),
+            );
+    }
+
+    #[tokio::test]
+    async fn test_shell_reports_unknown_command() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("SHELL_HISTORY_FILE_PATH", "target/test_shell_history")
+            .arg("shell")
+            .write_stdin("frobnicate\nexit\n")
+            .assert()
+            .success()
+            .stderr(predicates::str::contains(
+                "unrecognized subcommand 'frobnicate'",
+            ));
+    }
+
+    #[tokio::test]
+    async fn test_shell_goes_on_after_failed_command() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("SHELL_HISTORY_FILE_PATH", "target/test_shell_history")
+            .arg("shell")
+            .write_stdin("plan --manifest missing_manifest.json\nlist-types\nexit\n")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Failed to read missing_manifest.json",
+            ))
+            .stdout(predicates::str::contains("Command failed with exit code 1"))
+            // The next command still runs
+            .stdout(predicates::str::contains("Treasury"));
+    }
+
+    #[tokio::test]
+    async fn test_allowances_requires_owner_or_spender() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("allowances")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(
+                "--owner <OWNER>|--spender <SPENDER>",
+            ));
+    }
+
+    #[tokio::test]
+    async fn test_completions_hint_vesting_types() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("completions")
+            .arg("bash")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("COMPLETE"))
+            .stdout(predicates::str::contains("cowl-cli"));
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("COMPLETE", "fish")
+            .args(["--", BINARY, "balance", "--vesting-type", "Tre"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("Treasury"));
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("COMPLETE", "fish")
+            .args(["--", BINARY, "transfer", "--from", "Inst"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("Installer"));
+    }
+
+    #[tokio::test]
+    async fn test_fund_with_amount_and_top_up() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("fund-cspr")
+            .arg("--all-vesting")
+            .arg("--amount")
+            .arg("2500000000")
+            .arg("--top-up-to")
+            .arg("100000000000")
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("cannot be used with"));
+    }
+
+    #[tokio::test]
+    async fn test_fund_targets_file() {
+        setup().await;
+        let targets = [
+            "01868e06026ba9c8695f6f3bb10d44782004dbc144ff65017cf484436f9cf7b0f6",
+            "Treasury",
+        ];
+        let amount = 2_500_000_000u128;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            "# Targets to fund\n{}\n\n{} # vesting",
+            targets[0], targets[1]
+        )
+        .unwrap();
+        let before: Vec<u128> = targets
+            .iter()
+            .map(|target| cspr_balance_motes(target))
+            .collect();
+
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("fund-cspr")
+                .arg("--targets")
+                .arg(file.path())
+                .arg("--amount")
+                .arg(amount.to_string())
+                .write_stdin("y\n"),
+        );
+        assert!(output.contains("before (CSPR)"));
+        assert!(output.contains("balance_changes"));
+        // Each target gets a distinct transfer id
+        let transfer_ids: Vec<u64> = Regex::new(r"Transfer id (\d+) for")
+            .unwrap()
+            .captures_iter(&output)
+            .map(|captures| captures[1].parse().unwrap())
+            .collect();
+        assert_eq!(transfer_ids.len(), targets.len());
+        assert_eq!(transfer_ids[1], transfer_ids[0] + 1);
+
+        for (target, before) in targets.iter().zip(before) {
+            assert_eq!(cspr_balance_motes(target), before + amount);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transfer_id_and_memo_recorded_in_history() {
+        setup().await;
+        let from = "01fbe77037c317c12af3a6af08d02d9fc6b3a1636237ae48f77b198a9483d94801"; // Liquidity
+        let to = "01868e06026ba9c8695f6f3bb10d44782004dbc144ff65017cf484436f9cf7b0f6";
+        let unique = chrono::Utc::now().timestamp_millis();
+        let transfer_id = unique.to_string();
+        let memo = format!("invoice-{unique}");
+
+        let base64_key = "MC4CAQAwBQYDK2VwBCIEIOeKQNbCmsyZme2t5U7Lulnn2TfdZkiFANeg89Sy7Pzn";
+
+        let output = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("fund-cspr")
+                .arg("--key")
+                .arg(to)
+                .arg("--amount")
+                .arg("2500000000")
+                .arg("--transfer-id")
+                .arg(&transfer_id)
+                .write_stdin("y\n"),
+        );
+        assert!(output.contains(&format!("Transfer id {transfer_id} for")));
+
+        run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("transfer")
+                .arg("--from")
+                .arg(from)
+                .arg("--to")
+                .arg(to)
+                .arg("--amount")
+                .arg("100000000000")
+                .arg("--memo")
+                .arg(&memo)
+                .write_stdin(format!("{base64_key}\ny\n")),
+        );
+
+        let history = run_success(
+            Command::cargo_bin(BINARY)
+                .unwrap()
+                .arg("history")
+                .arg("list"),
+        );
+        assert!(history.contains(&format!("\"transfer_id\": \"{transfer_id}\"")));
+        assert!(history.contains(&format!("\"memo\": \"{memo}\"")));
+    }
+
     #[tokio::test]
     async fn test_balance_with_specific_key() {
         setup().await;
@@ -242,6 +864,27 @@ mod tests_install_vesting {
             .stdout(predicates::str::contains(COWL_CEP_18_TOKEN_SYMBOL.clone()));
     }
 
+    #[tokio::test]
+    async fn test_transfer_exceeding_balance_fails_preflight() {
+        setup().await;
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        let from = "01fbe77037c317c12af3a6af08d02d9fc6b3a1636237ae48f77b198a9483d94801"; // Liquidity
+        let to = "Treasury";
+        let amount = "1000000000000000000000000000";
+
+        cmd.arg("transfer")
+            .arg("--from")
+            .arg(from)
+            .arg("--to")
+            .arg(to)
+            .arg("--amount")
+            .arg(amount)
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains("Pre-flight check failed"))
+            .stdout(predicates::str::contains("are required"));
+    }
+
     #[tokio::test]
     async fn test_transfer_command_treasury_recipient() {
         setup().await;
@@ -275,6 +918,32 @@ mod tests_install_vesting {
             .stdout(predicates::str::contains(COWL_CEP_18_TOKEN_SYMBOL.clone()));
     }
 
+    #[tokio::test]
+    async fn test_transfer_verifies_balance_changes() {
+        setup().await;
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        let from = "01fbe77037c317c12af3a6af08d02d9fc6b3a1636237ae48f77b198a9483d94801"; // Liquidity
+        let to = "Treasury";
+        let amount = "100000000000";
+
+        let base64_key = "MC4CAQAwBQYDK2VwBCIEIOeKQNbCmsyZme2t5U7Lulnn2TfdZkiFANeg89Sy7Pzn";
+        let confirmation_response = "y\n";
+
+        cmd.arg("transfer")
+            .arg("--from")
+            .arg(from)
+            .arg("--to")
+            .arg(to)
+            .arg("--amount")
+            .arg(amount)
+            .write_stdin(format!("{base64_key}\n{confirmation_response}"))
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("balance_changes"))
+            .stdout(predicates::str::contains("\"delta\": \"-100000000000\""))
+            .stdout(predicates::str::contains("Unexpected change").not());
+    }
+
     #[tokio::test]
     async fn test_transfer_command_treasury_recipient_account_hash() {
         setup().await;
@@ -440,6 +1109,34 @@ mod tests_install_vesting {
             .stdout(predicates::str::contains(COWL_CEP_18_TOKEN_SYMBOL.clone()));
     }
 
+    #[tokio::test]
+    async fn test_approve_exact_allowance() {
+        setup().await;
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        let owner = "016fd7fb5f002d82f3813c76ac83940d4d886035395ddd9be66c9a4a2993b63aaf"; // Community
+        let spender = "User_2";
+        let amount = "300000000000";
+
+        let base64_key = "MC4CAQAwBQYDK2VwBCIEIPZsIcOa1F3PpF8SoOjIaJ1qIrsraqj2APBA1pZV0N+R";
+        let confirmation_response = "y\n";
+
+        cmd.arg("approve")
+            .arg("--owner")
+            .arg(owner)
+            .arg("--spender")
+            .arg(spender)
+            .arg("--amount")
+            .arg(amount)
+            .write_stdin(format!("{base64_key}\n{confirmation_response}"))
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(format!(
+                "Command executed: Approve Allowance 300.00 {}",
+                COWL_CEP_18_TOKEN_SYMBOL.clone()
+            )))
+            .stdout(predicates::str::contains(amount));
+    }
+
     #[tokio::test]
     async fn test_allowance_with_keys() {
         setup().await;
@@ -488,6 +1185,57 @@ mod tests_install_vesting {
             .stdout(predicates::str::contains(COWL_CEP_18_TOKEN_SYMBOL.clone()));
     }
 
+    #[tokio::test]
+    async fn test_allowances_lists_approved_spender() {
+        setup().await;
+        let owner = "Community";
+        let spender = "User_1";
+        let amount = "400000000000";
+
+        let base64_key = "MC4CAQAwBQYDK2VwBCIEIPZsIcOa1F3PpF8SoOjIaJ1qIrsraqj2APBA1pZV0N+R";
+        let confirmation_response = "y\n";
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("approve")
+            .arg("--owner")
+            .arg(owner)
+            .arg("--spender")
+            .arg(spender)
+            .arg("--amount")
+            .arg(amount)
+            .write_stdin(format!("{base64_key}\n{confirmation_response}"))
+            .assert()
+            .success();
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        let assert = cmd
+            .arg("allowances")
+            .arg("--owner")
+            .arg(owner)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(format!(
+                "Command executed: Allowances of owner {owner}"
+            )));
+        let output = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+        let listing = &output[output.find("\"allowances\"").unwrap()..];
+        let spender_entry = &listing[listing.find(&format!("\"label\": \"{spender}\"")).unwrap()..];
+        assert!(spender_entry.contains(&format!("\"allowance\": \"{amount}\"")));
+        // The owner is never listed as its own spender
+        assert!(!listing.contains(&format!("\"label\": \"{owner}\"")));
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("allowances")
+            .arg("--spender")
+            .arg(spender)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(format!("\"label\": \"{owner}\"")))
+            .stdout(predicates::str::contains(format!(
+                "\"allowance\": \"{amount}\""
+            )));
+    }
+
     #[tokio::test]
     async fn test_transfer_from_key_spender() {
         setup().await;
@@ -613,19 +1361,133 @@ mod tests_install_vesting {
             .stdout(predicates::str::contains("Transfer"))
             .stdout(predicates::str::contains(COWL_CEP_18_TOKEN_SYMBOL.clone()));
     }
+
+    #[tokio::test]
+    async fn test_history_transfers_command() {
+        setup().await;
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("history")
+            .arg("transfers")
+            .arg("--key")
+            .arg("Treasury")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Command executed: Transfer history for Treasury",
+            ))
+            .stdout(predicates::str::contains("\"ledger\""))
+            .stdout(predicates::str::contains("\"computed_balance\""))
+            .stdout(predicates::str::contains("\"current_balance\""));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_holders_matches_total_supply() {
+        setup().await;
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("snapshot")
+            .arg("holders")
+            .arg("--format")
+            .arg("json")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("\"matches_total_supply\": true"))
+            .stdout(predicates::str::contains("\"errors\": []"));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_status_invalid_hash() {
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        let deploy_hash = "not-a-deploy-hash";
+
+        cmd.arg("deploy-status")
+            .arg(deploy_hash)
+            .assert()
+            .failure()
+            .stdout(predicates::str::contains(format!(
+                "Command executed: Deploy status for {}",
+                deploy_hash
+            )))
+            .stdout(predicates::str::contains("Invalid deploy hash"));
+    }
+
+    #[test]
+    async fn test_history_list_deploys() {
+        setup().await;
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("history")
+            .arg("list")
+            .arg("--command")
+            .arg("deploy")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Command executed: List deploy history",
+            ))
+            .stdout(predicates::str::contains("\"session_wasm\""))
+            .stdout(predicates::str::contains("\"status\": \"Success\""));
+    }
+
+    #[tokio::test]
+    async fn test_history_skips_truncated_journal_line() {
+        setup().await;
+        let content = std::fs::read_to_string(JOURNAL_FILE_PATH.as_str()).unwrap();
+        let valid_lines = content.lines().count();
+
+        // A line cut short by a crash while appending
+        let mut journal = tempfile::NamedTempFile::new().unwrap();
+        write!(journal, "{content}{{\"event\":\"submitted\",\"deploy_ha").unwrap();
+
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.env("JOURNAL_FILE_PATH", journal.path())
+            .arg("history")
+            .arg("list")
+            .arg("--command")
+            .arg("deploy")
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(format!(
+                "Skipping malformed journal line {}",
+                valid_lines + 1
+            )))
+            .stdout(predicates::str::contains("\"session_wasm\""));
+    }
 }
 
 mod tests_swap {
     use assert_cmd::Command;
     use chrono::Utc;
     use cowl_cli::utils::constants::{COWL_CEP_18_COOL_SYMBOL, COWL_CEP_18_TOKEN_SYMBOL};
+    use cowl_cli::utils::{config, get_contract_swap_hash_keys};
     use once_cell::sync::Lazy;
     use predicates::prelude::PredicateBooleanExt;
+    use regex::Regex;
     use std::sync::Arc;
     use tokio::sync::Mutex;
 
     const BINARY: &str = "cowl_cli";
 
+    // Token balance of an account or contract key
+    fn token_balance(key: &str) -> u128 {
+        let output = Command::cargo_bin(BINARY)
+            .unwrap()
+            .arg("balance")
+            .arg("--key")
+            .arg(key)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let output = String::from_utf8_lossy(&output);
+        let pattern = format!("\"balance_{}\": \"(\\d+)\"", *COWL_CEP_18_COOL_SYMBOL);
+        Regex::new(&pattern)
+            .unwrap()
+            .captures(&output)
+            .unwrap_or_else(|| panic!("{pattern} not found in output:\n{output}"))[1]
+            .parse()
+            .unwrap()
+    }
+
     static SETUP_DONE: Lazy<Arc<Mutex<Option<bool>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
 
     // Run the setup (deploy the contract) only if not done yet
@@ -769,6 +1631,39 @@ mod tests_swap {
             .stdout(predicates::str::contains("\"0.00\"").not()); // Ensure it doesn't contain "0.00"
     }
 
+    #[tokio::test]
+    async fn test_transfer_to_swap_package_hash() {
+        setup().await;
+        // The Installer key finding the swap contract comes from the configuration
+        config::init().await;
+        let (_, swap_package_hash) = get_contract_swap_hash_keys().await.unwrap();
+        let from = "01fbe77037c317c12af3a6af08d02d9fc6b3a1636237ae48f77b198a9483d94801"; // Liquidity
+        let amount = "100000000000";
+
+        let base64_key = "MC4CAQAwBQYDK2VwBCIEIOeKQNbCmsyZme2t5U7Lulnn2TfdZkiFANeg89Sy7Pzn";
+        let confirmation_response = "y\n";
+
+        let balance_before = token_balance(&swap_package_hash);
+        let mut cmd = Command::cargo_bin(BINARY).unwrap();
+        cmd.arg("transfer")
+            .arg("--from")
+            .arg(from)
+            .arg("--to")
+            .arg(&swap_package_hash)
+            .arg("--amount")
+            .arg(amount)
+            .write_stdin(format!("{base64_key}\n{confirmation_response}"))
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("Processed deploy hash"))
+            .stdout(predicates::str::contains("No contract found").not());
+
+        assert_eq!(
+            token_balance(&swap_package_hash),
+            balance_before + amount.parse::<u128>().unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_update_times_command() {
         setup().await;
@@ -913,3 +1808,82 @@ mod tests_swap {
             .stdout(predicates::str::contains(" motes"));
     }
 }
+
+mod tests_events {
+    use cowl_cli::{
+        commands::events::parse_sse_message,
+        utils::events::{decode_event, decode_schemas},
+    };
+
+    fn string_bytes(value: &str) -> Vec<u8> {
+        let mut bytes = (value.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_parse_sse_message() {
+        let message = "id: 42\ndata: {\"DeployProcessed\":\ndata: {}}\n\n";
+        let sse_message = parse_sse_message(message);
+        assert_eq!(sse_message.id, Some(42));
+        assert_eq!(sse_message.data, "{\"DeployProcessed\":{}}");
+
+        let keep_alive = parse_sse_message(":\n\n");
+        assert_eq!(keep_alive.id, None);
+        assert!(keep_alive.data.is_empty());
+    }
+
+    #[test]
+    fn test_decode_token_transfer_event() {
+        let mut bytes = string_bytes("event_Transfer");
+        bytes.push(0);
+        bytes.extend_from_slice(&[0xaa; 32]);
+        bytes.push(1);
+        bytes.extend_from_slice(&[0xbb; 32]);
+        // U256 100
+        bytes.extend_from_slice(&[1, 100]);
+
+        let (name, fields) = decode_event(&bytes, None).unwrap();
+        assert_eq!(name, "Transfer");
+        assert_eq!(
+            fields["sender"],
+            format!("account-hash-{}", "aa".repeat(32))
+        );
+        assert_eq!(fields["recipient"], format!("hash-{}", "bb".repeat(32)));
+        assert_eq!(fields["amount"], "100");
+        assert!(!fields.contains_key("raw"));
+    }
+
+    #[test]
+    fn test_decode_event_with_contract_schemas() {
+        // One event with a Key, a U512 and an Option<U64> field
+        let mut schema_bytes = 1u32.to_le_bytes().to_vec();
+        schema_bytes.extend(string_bytes("CowlToCspr"));
+        schema_bytes.extend_from_slice(&3u32.to_le_bytes());
+        schema_bytes.extend(string_bytes("account"));
+        schema_bytes.push(11);
+        schema_bytes.extend(string_bytes("amount"));
+        schema_bytes.push(8);
+        schema_bytes.extend(string_bytes("rate"));
+        schema_bytes.extend_from_slice(&[13, 5]);
+        let schemas = decode_schemas(&schema_bytes).unwrap();
+
+        let mut bytes = string_bytes("event_CowlToCspr");
+        bytes.push(0);
+        bytes.extend_from_slice(&[0xcc; 32]);
+        // U512 1000
+        bytes.extend_from_slice(&[2, 0xe8, 0x03]);
+        bytes.push(1);
+        bytes.extend_from_slice(&7u64.to_le_bytes());
+
+        let (name, fields) = decode_event(&bytes, Some(&schemas)).unwrap();
+        assert_eq!(name, "CowlToCspr");
+        assert_eq!(
+            fields["account"],
+            format!("account-hash-{}", "cc".repeat(32))
+        );
+        assert_eq!(fields["amount"], "1000");
+        assert_eq!(fields["rate"], "7");
+        assert!(!fields.contains_key("raw"));
+    }
+}